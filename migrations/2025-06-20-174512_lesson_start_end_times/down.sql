@@ -0,0 +1,15 @@
+DROP INDEX lessons_student_group_id_starts_at_idx;
+
+ALTER TABLE lessons
+ADD COLUMN scheduled_at DATE;
+
+UPDATE lessons
+SET scheduled_at = school_date(starts_at);
+
+ALTER TABLE lessons
+ALTER COLUMN scheduled_at SET NOT NULL,
+DROP CONSTRAINT lessons_ends_after_starts,
+DROP COLUMN starts_at,
+DROP COLUMN ends_at;
+
+DROP FUNCTION school_date(TIMESTAMPTZ);
@@ -0,0 +1,44 @@
+ALTER TABLE lessons
+ADD COLUMN starts_at TIMESTAMPTZ,
+ADD COLUMN ends_at TIMESTAMPTZ;
+
+-- Дата по местному времени школы. Часовой пояс берется из параметра сессии
+-- app.school_timezone, который приложение задает каждому соединению. Без него функция
+-- завершается ошибкой, а не считает дату в часовом поясе сервера.
+CREATE FUNCTION school_date(moment TIMESTAMPTZ) RETURNS DATE AS $$
+    SELECT (moment AT TIME ZONE current_setting('app.school_timezone'))::DATE
+$$ LANGUAGE SQL STABLE;
+
+-- У старых уроков была только дата: считаем, что они начинались в стандартное время школы
+-- и длились стандартную продолжительность. Эти настройки приложение передает в параметрах
+-- сессии app.school_timezone, app.lesson_default_start и app.lesson_default_duration.
+-- При запуске через diesel CLI их нужно задать явно, например
+-- PGOPTIONS="-c app.school_timezone=Europe/Moscow -c app.lesson_default_start=09:00
+-- -c app.lesson_default_duration=45", иначе миграция завершится ошибкой.
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM lessons)
+        AND (COALESCE(current_setting('app.school_timezone', true), '') = ''
+            OR COALESCE(current_setting('app.lesson_default_start', true), '') = ''
+            OR COALESCE(current_setting('app.lesson_default_duration', true), '') = '')
+    THEN
+        RAISE EXCEPTION 'School schedule settings are not set, pass app.school_timezone, '
+            'app.lesson_default_start and app.lesson_default_duration to the session';
+    END IF;
+END
+$$;
+
+UPDATE lessons
+SET starts_at = (scheduled_at + current_setting('app.lesson_default_start')::TIME)
+    AT TIME ZONE current_setting('app.school_timezone');
+
+UPDATE lessons
+SET ends_at = starts_at + make_interval(mins => current_setting('app.lesson_default_duration')::INT);
+
+ALTER TABLE lessons
+ALTER COLUMN starts_at SET NOT NULL,
+ALTER COLUMN ends_at SET NOT NULL,
+ADD CONSTRAINT lessons_ends_after_starts CHECK (ends_at > starts_at),
+DROP COLUMN scheduled_at;
+
+CREATE INDEX lessons_student_group_id_starts_at_idx ON lessons (student_group_id, starts_at);
@@ -0,0 +1,8 @@
+DROP INDEX lessons_lesson_series_id_series_date_idx;
+
+ALTER TABLE lessons
+DROP COLUMN lesson_series_id,
+DROP COLUMN series_date;
+
+DROP TABLE lesson_series_cancellations;
+DROP TABLE lesson_series;
@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS lesson_series (
+    id SERIAL PRIMARY KEY,
+    topic VARCHAR NOT NULL,
+    student_group_id INTEGER REFERENCES student_groups (id) ON DELETE SET NULL,
+    start_date DATE NOT NULL,
+    start_time TIME NOT NULL,
+    duration_minutes INTEGER NOT NULL CHECK (duration_minutes > 0),
+    -- Битовая маска дней недели: бит 0 - понедельник, бит 6 - воскресенье
+    weekdays INTEGER NOT NULL CHECK (weekdays > 0 AND weekdays < 128),
+    interval_weeks INTEGER NOT NULL DEFAULT 1 CHECK (interval_weeks > 0),
+    until_date DATE,
+    occurrence_count INTEGER CHECK (occurrence_count > 0),
+    CHECK (until_date IS NOT NULL OR occurrence_count IS NOT NULL)
+);
+
+CREATE TABLE IF NOT EXISTS lesson_series_cancellations (
+    lesson_series_id INTEGER NOT NULL REFERENCES lesson_series (id) ON DELETE CASCADE,
+    occurrence_date DATE NOT NULL,
+    PRIMARY KEY (lesson_series_id, occurrence_date)
+);
+
+ALTER TABLE lessons
+ADD COLUMN lesson_series_id INTEGER REFERENCES lesson_series (id) ON DELETE SET NULL,
+ADD COLUMN series_date DATE;
+
+CREATE INDEX lessons_lesson_series_id_series_date_idx ON lessons (lesson_series_id, series_date);
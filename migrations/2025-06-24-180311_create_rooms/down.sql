@@ -0,0 +1,9 @@
+DROP INDEX lessons_room_id_starts_at_idx;
+
+ALTER TABLE lesson_series
+DROP COLUMN room_id;
+
+ALTER TABLE lessons
+DROP COLUMN room_id;
+
+DROP TABLE rooms;
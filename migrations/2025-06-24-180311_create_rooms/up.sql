@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS rooms (
+    id SERIAL PRIMARY KEY,
+    name VARCHAR NOT NULL UNIQUE,
+    capacity INTEGER NOT NULL CHECK (capacity > 0),
+    building VARCHAR,
+    equipment TEXT[] NOT NULL DEFAULT '{}' CHECK (array_position(equipment, NULL) IS NULL)
+);
+
+ALTER TABLE lessons
+ADD COLUMN room_id INTEGER REFERENCES rooms (id) ON DELETE SET NULL;
+
+ALTER TABLE lesson_series
+ADD COLUMN room_id INTEGER REFERENCES rooms (id) ON DELETE SET NULL;
+
+CREATE INDEX lessons_room_id_starts_at_idx ON lessons (room_id, starts_at);
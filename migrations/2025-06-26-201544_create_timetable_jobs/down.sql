@@ -0,0 +1,4 @@
+DROP TABLE timetable_violations;
+DROP TABLE timetable_proposed_lessons;
+DROP TABLE timetable_jobs;
+DROP TYPE timetable_job_status;
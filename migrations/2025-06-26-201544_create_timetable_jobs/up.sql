@@ -0,0 +1,32 @@
+CREATE TYPE timetable_job_status AS ENUM ('pending', 'running', 'completed', 'failed', 'accepted');
+
+CREATE TABLE IF NOT EXISTS timetable_jobs (
+    id SERIAL PRIMARY KEY,
+    status timetable_job_status NOT NULL DEFAULT 'pending',
+    week_start DATE NOT NULL,
+    seed BIGINT NOT NULL,
+    hard_violations INTEGER,
+    soft_violations INTEGER,
+    score INTEGER,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    finished_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS timetable_proposed_lessons (
+    id SERIAL PRIMARY KEY,
+    timetable_job_id INTEGER NOT NULL REFERENCES timetable_jobs (id) ON DELETE CASCADE,
+    topic VARCHAR NOT NULL,
+    student_group_id INTEGER NOT NULL REFERENCES student_groups (id) ON DELETE CASCADE,
+    room_id INTEGER REFERENCES rooms (id) ON DELETE SET NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS timetable_violations (
+    id SERIAL PRIMARY KEY,
+    timetable_job_id INTEGER NOT NULL REFERENCES timetable_jobs (id) ON DELETE CASCADE,
+    kind VARCHAR NOT NULL,
+    is_hard BOOLEAN NOT NULL,
+    description TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+ALTER TABLE student_groups DROP CONSTRAINT student_groups_free_spots_non_negative;
+
+UPDATE student_groups
+SET free_spots = free_spots + (
+    SELECT count(*) FROM students WHERE students.student_group_id = student_groups.id
+);
@@ -0,0 +1,11 @@
+-- Раньше free_spots не уменьшался при зачислении: вычитаем уже зачисленных учеников
+UPDATE student_groups
+SET free_spots = GREATEST(
+    free_spots - (
+        SELECT count(*) FROM students WHERE students.student_group_id = student_groups.id
+    ),
+    0
+);
+
+ALTER TABLE student_groups
+    ADD CONSTRAINT student_groups_free_spots_non_negative CHECK (free_spots >= 0);
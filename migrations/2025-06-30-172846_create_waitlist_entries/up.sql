@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS waitlist_entries (
+    id SERIAL PRIMARY KEY,
+    student_group_id INTEGER NOT NULL REFERENCES student_groups (id) ON DELETE CASCADE,
+    student_id INTEGER NOT NULL REFERENCES students (id) ON DELETE CASCADE,
+    position INTEGER NOT NULL CHECK (position > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (student_group_id, student_id),
+    -- Deferred so that positions can be shifted by a single UPDATE when the queue is reordered
+    UNIQUE (student_group_id, position) DEFERRABLE INITIALLY DEFERRED
+);
+
+CREATE INDEX waitlist_entries_student_id_idx ON waitlist_entries (student_id);
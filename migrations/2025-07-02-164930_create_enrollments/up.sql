@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS enrollments (
+    id SERIAL PRIMARY KEY,
+    student_id INTEGER NOT NULL REFERENCES students (id) ON DELETE CASCADE,
+    student_group_id INTEGER NOT NULL REFERENCES student_groups (id) ON DELETE CASCADE,
+    start_date DATE NOT NULL,
+    -- Первый день, когда ученик уже не в группе, NULL у текущего зачисления
+    end_date DATE,
+    CHECK (end_date IS NULL OR end_date > start_date)
+);
+
+CREATE UNIQUE INDEX enrollments_current_student_idx ON enrollments (student_id) WHERE end_date IS NULL;
+CREATE INDEX enrollments_student_group_id_idx ON enrollments (student_group_id, start_date);
+
+-- Существующие ученики считаются зачисленными с первого урока своей группы. Даты берутся
+-- по местному времени школы, см. school_date() в миграции lesson_start_end_times
+INSERT INTO enrollments (student_id, student_group_id, start_date)
+SELECT s.id,
+       s.student_group_id,
+       LEAST(school_date(now()), COALESCE((SELECT school_date(MIN(l.starts_at))
+                                           FROM lessons l
+                                           WHERE l.student_group_id = s.student_group_id),
+                                          school_date(now())))
+FROM students s
+WHERE s.student_group_id IS NOT NULL;
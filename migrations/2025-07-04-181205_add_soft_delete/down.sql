@@ -0,0 +1,5 @@
+ALTER TABLE lessons DROP COLUMN deleted_at;
+ALTER TABLE student_groups DROP COLUMN deleted_at;
+ALTER TABLE teachers DROP COLUMN deleted_at;
+ALTER TABLE parents DROP COLUMN deleted_at;
+ALTER TABLE students DROP COLUMN deleted_at;
@@ -0,0 +1,12 @@
+ALTER TABLE students ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE parents ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE teachers ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE student_groups ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE lessons ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Индексы нужны задаче очистки, которая ищет записи, удаленные раньше срока хранения
+CREATE INDEX students_deleted_at_idx ON students (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX parents_deleted_at_idx ON parents (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX teachers_deleted_at_idx ON teachers (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX student_groups_deleted_at_idx ON student_groups (deleted_at) WHERE deleted_at IS NOT NULL;
+CREATE INDEX lessons_deleted_at_idx ON lessons (deleted_at) WHERE deleted_at IS NOT NULL;
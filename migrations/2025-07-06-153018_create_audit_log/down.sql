@@ -0,0 +1,4 @@
+DROP TABLE audit_log;
+DROP FUNCTION audit_log_reject_modification;
+DROP TYPE audit_entity_type;
+DROP TYPE audit_action;
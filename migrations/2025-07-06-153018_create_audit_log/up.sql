@@ -0,0 +1,49 @@
+CREATE TYPE audit_action AS ENUM ('create', 'update', 'delete', 'restore');
+
+CREATE TYPE audit_entity_type AS ENUM (
+    'student',
+    'parent',
+    'teacher',
+    'student_group',
+    'lesson',
+    'lesson_series',
+    'attendance',
+    'document',
+    'room',
+    'user',
+    'waitlist_entry'
+);
+
+-- Пользователь хранится без внешнего ключа: запись журнала должна пережить удаление
+-- пользователя, поэтому рядом сохраняется и его логин
+CREATE TABLE IF NOT EXISTS audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    user_id INTEGER,
+    username VARCHAR,
+    action audit_action NOT NULL,
+    entity_type audit_entity_type NOT NULL,
+    entity_id VARCHAR NOT NULL,
+    before JSONB,
+    after JSONB,
+    request_id VARCHAR
+);
+
+CREATE INDEX audit_log_entity_idx ON audit_log (entity_type, entity_id, created_at);
+CREATE INDEX audit_log_user_id_idx ON audit_log (user_id, created_at);
+CREATE INDEX audit_log_created_at_idx ON audit_log (created_at);
+
+-- Журнал только дополняется: изменение и удаление записей запрещено
+CREATE FUNCTION audit_log_reject_modification() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE FUNCTION audit_log_reject_modification();
+
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION audit_log_reject_modification();
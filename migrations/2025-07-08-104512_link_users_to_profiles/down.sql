@@ -0,0 +1,3 @@
+ALTER TABLE users
+    DROP COLUMN parent_id,
+    DROP COLUMN teacher_id;
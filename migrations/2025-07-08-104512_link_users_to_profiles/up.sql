@@ -0,0 +1,3 @@
+ALTER TABLE users
+    ADD COLUMN teacher_id INTEGER UNIQUE REFERENCES teachers (id) ON DELETE SET NULL,
+    ADD COLUMN parent_id INTEGER UNIQUE REFERENCES parents (id) ON DELETE SET NULL;
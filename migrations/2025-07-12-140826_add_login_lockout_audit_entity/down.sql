@@ -0,0 +1,3 @@
+-- PostgreSQL не умеет удалять значения из перечисления, а записи audit_log с этим
+-- значением удалять нельзя. Значение остается, старые версии приложения его не используют.
+SELECT 1;
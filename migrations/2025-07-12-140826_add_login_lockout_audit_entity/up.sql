@@ -0,0 +1 @@
+ALTER TYPE audit_entity_type ADD VALUE IF NOT EXISTS 'login_lockout';
@@ -0,0 +1,2 @@
+-- Значение 'api_token' остается в audit_entity_type, см. миграцию login_lockout.
+DROP TABLE IF EXISTS api_tokens;
@@ -0,0 +1,5 @@
+ALTER TABLE documents
+    DROP COLUMN content_type,
+    DROP COLUMN size,
+    DROP COLUMN checksum,
+    DROP COLUMN storage_key;
@@ -0,0 +1,24 @@
+-- У документов, загруженных до этой миграции, ключ файла и тип определяются по имени,
+-- а размер и контрольная сумма неизвестны.
+ALTER TABLE documents
+    ADD COLUMN content_type VARCHAR(255),
+    ADD COLUMN size BIGINT,
+    ADD COLUMN checksum VARCHAR(64),
+    ADD COLUMN storage_key VARCHAR(512);
+
+UPDATE documents
+SET storage_key  = 'teachers/' || teacher_id || '/' || id || '.' || substring(name FROM '[^.]*$'),
+    content_type = CASE lower(substring(name FROM '[^.]*$'))
+                       WHEN 'png' THEN 'image/png'
+                       WHEN 'jpg' THEN 'image/jpeg'
+                       WHEN 'jpeg' THEN 'image/jpeg'
+                       WHEN 'webp' THEN 'image/webp'
+                       WHEN 'pdf' THEN 'application/pdf'
+                       ELSE 'application/octet-stream'
+        END;
+
+ALTER TABLE documents
+    ALTER COLUMN content_type SET NOT NULL,
+    ALTER COLUMN storage_key SET NOT NULL;
+
+CREATE UNIQUE INDEX documents_teacher_id_checksum_key ON documents (teacher_id, checksum);
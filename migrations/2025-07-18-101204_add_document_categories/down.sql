@@ -0,0 +1,8 @@
+DROP INDEX documents_teacher_id_category_idx;
+
+ALTER TABLE documents
+    DROP COLUMN valid_until,
+    DROP COLUMN description,
+    DROP COLUMN category;
+
+DROP TYPE document_category;
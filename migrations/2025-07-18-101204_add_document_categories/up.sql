@@ -0,0 +1,8 @@
+CREATE TYPE document_category AS ENUM ('certificate', 'medical_check', 'contract', 'other');
+
+ALTER TABLE documents
+    ADD COLUMN category document_category NOT NULL DEFAULT 'other',
+    ADD COLUMN description TEXT,
+    ADD COLUMN valid_until DATE;
+
+CREATE INDEX documents_teacher_id_category_idx ON documents (teacher_id, category);
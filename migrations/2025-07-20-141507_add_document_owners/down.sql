@@ -0,0 +1,12 @@
+DELETE FROM documents WHERE teacher_id IS NULL;
+
+DROP INDEX documents_lesson_id_checksum_key;
+DROP INDEX documents_student_group_id_checksum_key;
+DROP INDEX documents_student_id_checksum_key;
+
+ALTER TABLE documents
+    DROP CONSTRAINT documents_single_owner,
+    DROP COLUMN lesson_id,
+    DROP COLUMN student_group_id,
+    DROP COLUMN student_id,
+    ALTER COLUMN teacher_id SET NOT NULL;
@@ -0,0 +1,11 @@
+ALTER TABLE documents
+    ALTER COLUMN teacher_id DROP NOT NULL,
+    ADD COLUMN student_id INTEGER REFERENCES students (id) ON DELETE CASCADE,
+    ADD COLUMN student_group_id INTEGER REFERENCES student_groups (id) ON DELETE CASCADE,
+    ADD COLUMN lesson_id INTEGER REFERENCES lessons (id) ON DELETE CASCADE,
+    ADD CONSTRAINT documents_single_owner
+        CHECK (num_nonnulls(teacher_id, student_id, student_group_id, lesson_id) = 1);
+
+CREATE UNIQUE INDEX documents_student_id_checksum_key ON documents (student_id, checksum);
+CREATE UNIQUE INDEX documents_student_group_id_checksum_key ON documents (student_group_id, checksum);
+CREATE UNIQUE INDEX documents_lesson_id_checksum_key ON documents (lesson_id, checksum);
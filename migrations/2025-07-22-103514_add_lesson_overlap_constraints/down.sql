@@ -0,0 +1,5 @@
+ALTER TABLE lessons
+    DROP CONSTRAINT lessons_room_id_overlap,
+    DROP CONSTRAINT lessons_student_group_id_overlap;
+
+DROP EXTENSION IF EXISTS btree_gist;
@@ -0,0 +1,12 @@
+CREATE EXTENSION IF NOT EXISTS btree_gist;
+
+-- Приложение проверяет пересечения уроков перед сохранением, а эти ограничения ловят уроки,
+-- сохраненные параллельным запросом между проверкой и вставкой. Пересекающиеся уроки,
+-- оставшиеся от таких запросов раньше, нужно перенести или удалить до миграции.
+ALTER TABLE lessons
+    ADD CONSTRAINT lessons_room_id_overlap
+        EXCLUDE USING gist (room_id WITH =, tstzrange(starts_at, ends_at) WITH &&)
+        WHERE (deleted_at IS NULL),
+    ADD CONSTRAINT lessons_student_group_id_overlap
+        EXCLUDE USING gist (student_group_id WITH =, tstzrange(starts_at, ends_at) WITH &&)
+        WHERE (deleted_at IS NULL);
@@ -1,13 +1,21 @@
 use crate::{
+    auth::token::hash_token,
     db::PostgresPool,
     error::AppError,
-    models::user::{Credentials, User},
-    schema::users::{self},
+    models::{
+        api_token::ApiToken,
+        user::{Credentials, User},
+    },
+    schema::{
+        api_tokens,
+        users::{self},
+    },
 };
 use async_trait::async_trait;
 use axum_login::{AuthnBackend, UserId};
+use chrono::Utc;
 use diesel::prelude::*;
-use tracing::info;
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct AuthBackend {
@@ -18,6 +26,40 @@ impl AuthBackend {
     pub fn new(pool: PostgresPool) -> Self {
         AuthBackend { pool }
     }
+
+    /// Пользователь, от имени которого действует API токен. Роль пользователя понижается
+    /// до роли токена. Отозванные и истекшие токены не принимаются.
+    pub fn authenticate_token(&self, token: &str) -> Result<Option<User>, AppError> {
+        let mut connection = self.pool.get()?;
+        let token_result = api_tokens::table
+            .inner_join(users::table)
+            .filter(api_tokens::token_hash.eq(hash_token(token)))
+            .select((ApiToken::as_select(), User::as_select()))
+            .first::<(ApiToken, User)>(&mut connection)
+            .optional()?;
+        let Some((api_token, mut user)) = token_result else {
+            warn!("Unknown API token was presented");
+            return Ok(None);
+        };
+
+        let now = Utc::now();
+        if !api_token.is_active(now) {
+            warn!(
+                "Inactive API token with ID {} of user {} was presented",
+                api_token.id, user.id
+            );
+            return Ok(None);
+        }
+        diesel::update(api_tokens::table.find(api_token.id))
+            .set(api_tokens::last_used_at.eq(now))
+            .execute(&mut connection)?;
+        info!(
+            "Authenticated user {} with API token {}",
+            user.id, api_token.id
+        );
+        user.role = user.role.min(api_token.role);
+        Ok(Some(user))
+    }
 }
 
 #[async_trait]
@@ -0,0 +1,36 @@
+use axum::{extract::Request, http::header::AUTHORIZATION, middleware::Next, response::Response};
+use axum_login::AuthSession;
+
+use crate::{auth::backend::AuthBackend, error::AppError};
+
+/// Middleware, авторизующий запрос по API токену из заголовка `Authorization: Bearer`.
+///
+/// Пользователь токена подставляется в [`AuthSession`] запроса вместо пользователя сессии,
+/// поэтому проверки прав и хендлеры работают с ним так же, как с пользователем, вошедшим
+/// по логину и паролю. Сессия при этом не создается. Запрос с неверным токеном
+/// отклоняется с 401, запрос без заголовка проходит без изменений.
+pub async fn bearer_auth(
+    mut auth_session: AuthSession<AuthBackend>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(header) = request.headers().get(AUTHORIZATION) else {
+        return Ok(next.run(request).await);
+    };
+    let token = header
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or_else(|| {
+            AppError::Unauthorized("Authorization header must contain a Bearer token".to_string())
+        })?;
+    let user = auth_session
+        .backend
+        .authenticate_token(token)?
+        .ok_or_else(|| AppError::Unauthorized("API token is invalid or expired".to_string()))?;
+
+    auth_session.user = Some(user);
+    request.extensions_mut().insert(auth_session);
+    Ok(next.run(request).await)
+}
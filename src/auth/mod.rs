@@ -7,6 +7,9 @@ use crate::{
 };
 
 pub mod backend;
+pub mod bearer;
+pub mod permission;
+pub mod token;
 
 pub fn get_auth_layer(
     pg_pool: PostgresPool,
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_login::AuthSession;
+use tracing::warn;
+
+use crate::{
+    auth::backend::AuthBackend,
+    error::AppError,
+    models::user::{PermissionRole, User},
+};
+
+/// Middleware, пропускающий запрос дальше только если у текущего пользователя роль не ниже
+/// переданной в состоянии.
+///
+/// Подключается к роутерам через `route_layer(from_fn_with_state(PermissionRole::..., require_role))`.
+pub async fn require_role(
+    State(required_role): State<PermissionRole>,
+    auth_session: AuthSession<AuthBackend>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    check_role(auth_session.user.as_ref(), required_role)?;
+    Ok(next.run(request).await)
+}
+
+/// Проверяет, что пользователь авторизован и его роль не ниже `required_role`.
+///
+/// Используется внутри хендлеров, когда права зависят от параметров запроса.
+pub fn check_role(user: Option<&User>, required_role: PermissionRole) -> Result<(), AppError> {
+    let user = authenticated_user(user)?;
+
+    if user.role < required_role {
+        warn!(
+            "User with ID {} and role {:?} tried to access route requiring {:?}",
+            user.id, user.role, required_role
+        );
+        return Err(AppError::Forbidden(format!(
+            "Role {:?} required, user has {:?}",
+            required_role, user.role
+        )));
+    }
+
+    Ok(())
+}
+
+/// Пользователь текущей сессии. Если пользователь не авторизован, возвращает 401.
+pub fn authenticated_user(user: Option<&User>) -> Result<&User, AppError> {
+    user.ok_or_else(|| AppError::Unauthorized("Request requires an authenticated user".to_string()))
+}
@@ -0,0 +1,19 @@
+use rand::{Rng, distributions::Alphanumeric};
+use sha2::{Digest, Sha256};
+
+const TOKEN_LENGTH: usize = 48;
+
+/// Случайный токен из латинских букв и цифр, который выдается клиенту один раз.
+pub fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// SHA-256 токена в виде hex-строки. В базе хранится только хеш, поэтому утечка
+/// таблицы не раскрывает действующие токены.
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,346 @@
+use std::{env, path::PathBuf, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeDelta, Utc};
+use chrono_tz::Tz;
+
+use crate::{error::AppError, models::document::DocumentCategory};
+
+/// Настройки приложения, читаемые из переменных окружения при старте.
+#[derive(Clone)]
+pub struct AppConfig {
+    pub schedule: ScheduleConfig,
+    pub retention: RetentionConfig,
+    pub password: PasswordConfig,
+    pub login_throttle: LoginThrottleConfig,
+    pub storage: StorageConfig,
+    pub document: DocumentConfig,
+}
+
+impl AppConfig {
+    pub fn from_env() -> Self {
+        Self {
+            schedule: ScheduleConfig::from_env(),
+            retention: RetentionConfig::from_env(),
+            password: PasswordConfig::from_env(),
+            login_throttle: LoginThrottleConfig::from_env(),
+            storage: StorageConfig::from_env(),
+            document: DocumentConfig::from_env(),
+        }
+    }
+}
+
+/// Настройки хранения удаленных записей.
+///
+/// Удаленные ученики, родители, преподаватели, группы и уроки хранятся `period`, после
+/// чего задача очистки, запускаемая раз в `purge_interval`, удаляет их окончательно.
+#[derive(Clone)]
+pub struct RetentionConfig {
+    pub period: TimeDelta,
+    pub purge_interval: TimeDelta,
+}
+
+impl RetentionConfig {
+    pub fn from_env() -> Self {
+        let days = env::var("SOFT_DELETE_RETENTION_DAYS")
+            .map(|value| {
+                value
+                    .parse::<i64>()
+                    .expect("SOFT_DELETE_RETENTION_DAYS must be a number of days")
+            })
+            .unwrap_or(90);
+        let purge_interval = minutes_from_env("SOFT_DELETE_PURGE_INTERVAL_MINUTES", 60);
+        assert!(
+            purge_interval > TimeDelta::zero(),
+            "SOFT_DELETE_PURGE_INTERVAL_MINUTES must be positive"
+        );
+
+        Self {
+            period: TimeDelta::days(days),
+            purge_interval,
+        }
+    }
+}
+
+/// Требования к паролям пользователей и настройки их сброса.
+///
+/// Длина пароля не может быть меньше 8 символов, остальные требования по умолчанию
+/// выключены. Ссылка на сброс пароля действует `reset_token_ttl`.
+#[derive(Clone)]
+pub struct PasswordConfig {
+    pub min_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    pub reset_token_ttl: TimeDelta,
+}
+
+impl PasswordConfig {
+    pub fn from_env() -> Self {
+        let min_length = env::var("PASSWORD_MIN_LENGTH")
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .expect("PASSWORD_MIN_LENGTH must be a number of characters")
+            })
+            .unwrap_or(8);
+        assert!(min_length >= 8, "PASSWORD_MIN_LENGTH must be at least 8");
+        let reset_token_ttl = minutes_from_env("PASSWORD_RESET_TOKEN_TTL_MINUTES", 60);
+        assert!(
+            reset_token_ttl > TimeDelta::zero(),
+            "PASSWORD_RESET_TOKEN_TTL_MINUTES must be positive"
+        );
+
+        Self {
+            min_length,
+            require_lowercase: flag_from_env("PASSWORD_REQUIRE_LOWERCASE"),
+            require_uppercase: flag_from_env("PASSWORD_REQUIRE_UPPERCASE"),
+            require_digit: flag_from_env("PASSWORD_REQUIRE_DIGIT"),
+            require_symbol: flag_from_env("PASSWORD_REQUIRE_SYMBOL"),
+            reset_token_ttl,
+        }
+    }
+}
+
+/// Защита входа от перебора паролей.
+///
+/// Неудачные попытки входа считаются отдельно по логину и по IP-адресу. Счетчик
+/// сбрасывается, если в течение `failure_window` не было новых неудачных попыток. Когда
+/// число попыток достигает порога, вход блокируется на `lockout`, и каждая следующая
+/// неудачная попытка удваивает срок блокировки, но не больше чем до `max_lockout`.
+#[derive(Clone)]
+pub struct LoginThrottleConfig {
+    pub max_failures_per_username: i64,
+    pub max_failures_per_ip: i64,
+    pub failure_window: TimeDelta,
+    pub lockout: TimeDelta,
+    pub max_lockout: TimeDelta,
+}
+
+impl LoginThrottleConfig {
+    pub fn from_env() -> Self {
+        let config = Self {
+            max_failures_per_username: count_from_env("LOGIN_MAX_FAILURES_PER_USERNAME", 5),
+            max_failures_per_ip: count_from_env("LOGIN_MAX_FAILURES_PER_IP", 20),
+            failure_window: minutes_from_env("LOGIN_FAILURE_WINDOW_MINUTES", 15),
+            lockout: minutes_from_env("LOGIN_LOCKOUT_MINUTES", 1),
+            max_lockout: minutes_from_env("LOGIN_MAX_LOCKOUT_MINUTES", 60),
+        };
+        assert!(
+            config.max_failures_per_username > 0 && config.max_failures_per_ip > 0,
+            "LOGIN_MAX_FAILURES_PER_USERNAME and LOGIN_MAX_FAILURES_PER_IP must be positive"
+        );
+        assert!(
+            config.failure_window > TimeDelta::zero(),
+            "LOGIN_FAILURE_WINDOW_MINUTES must be positive"
+        );
+        assert!(
+            config.lockout > TimeDelta::zero() && config.lockout <= config.max_lockout,
+            "LOGIN_LOCKOUT_MINUTES must be positive and not greater than LOGIN_MAX_LOCKOUT_MINUTES"
+        );
+        config
+    }
+
+    /// Срок блокировки после `failures` неудачных попыток подряд при пороге `max_failures`.
+    pub fn lockout_after(&self, failures: i64, max_failures: i64) -> TimeDelta {
+        let doublings = (failures - max_failures).clamp(0, 16) as u32;
+        (self.lockout * 2_i32.pow(doublings)).min(self.max_lockout)
+    }
+}
+
+/// Хранилище файлов документов.
+///
+/// По умолчанию файлы хранятся на локальном диске в каталоге `STORAGE_LOCAL_ROOT`. Если
+/// сервис запущен в нескольких экземплярах, нужно S3-совместимое хранилище
+/// (`STORAGE_BACKEND=s3`), общее для всех экземпляров.
+#[derive(Clone)]
+pub enum StorageConfig {
+    Local { root: PathBuf },
+    S3(S3Config),
+}
+
+impl StorageConfig {
+    pub fn from_env() -> Self {
+        let backend = env::var("STORAGE_BACKEND").unwrap_or("local".to_string());
+        match backend.as_str() {
+            "local" => Self::Local {
+                root: env::var("STORAGE_LOCAL_ROOT")
+                    .unwrap_or("./storage".to_string())
+                    .into(),
+            },
+            "s3" => Self::S3(S3Config::from_env()),
+            _ => panic!("STORAGE_BACKEND must be local or s3"),
+        }
+    }
+}
+
+/// Ограничения на загружаемые документы и требования к документам преподавателей.
+///
+/// Документы видов из `mandatory_categories` должны быть у каждого преподавателя.
+/// Отчет по документам предупреждает о них за `expiry_warning_days` дней до окончания
+/// срока действия.
+#[derive(Clone)]
+pub struct DocumentConfig {
+    /// Максимальный размер файла в байтах
+    pub max_size: u64,
+    pub mandatory_categories: Vec<DocumentCategory>,
+    pub expiry_warning_days: i64,
+}
+
+impl DocumentConfig {
+    pub fn from_env() -> Self {
+        let max_size_mb = count_from_env("DOCUMENT_MAX_SIZE_MB", 10);
+        assert!(max_size_mb > 0, "DOCUMENT_MAX_SIZE_MB must be positive");
+        let mandatory_categories = env::var("DOCUMENT_MANDATORY_CATEGORIES")
+            .unwrap_or("medical_check,contract".to_string())
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| {
+                DocumentCategory::from_name(name).unwrap_or_else(|| {
+                    panic!(
+                        "DOCUMENT_MANDATORY_CATEGORIES contains unknown category {}",
+                        name
+                    )
+                })
+            })
+            .collect();
+        let expiry_warning_days = count_from_env("DOCUMENT_EXPIRY_WARNING_DAYS", 30);
+        assert!(
+            expiry_warning_days >= 0,
+            "DOCUMENT_EXPIRY_WARNING_DAYS must not be negative"
+        );
+        Self {
+            max_size: max_size_mb as u64 * 1024 * 1024,
+            mandatory_categories,
+            expiry_warning_days,
+        }
+    }
+}
+
+/// Подключение к S3-совместимому хранилищу. Бакет должен быть создан заранее.
+///
+/// Для MinIO и других хранилищ, которые не поддерживают адреса вида
+/// `<бакет>.<хост>`, нужно включить `path_style`.
+#[derive(Clone)]
+pub struct S3Config {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub path_style: bool,
+}
+
+impl S3Config {
+    pub fn from_env() -> Self {
+        Self {
+            endpoint: env::var("S3_ENDPOINT").expect("S3_ENDPOINT must be set"),
+            bucket: env::var("S3_BUCKET").expect("S3_BUCKET must be set"),
+            region: env::var("S3_REGION").unwrap_or("us-east-1".to_string()),
+            access_key_id: env::var("S3_ACCESS_KEY_ID").expect("S3_ACCESS_KEY_ID must be set"),
+            secret_access_key: env::var("S3_SECRET_ACCESS_KEY")
+                .expect("S3_SECRET_ACCESS_KEY must be set"),
+            path_style: flag_from_env("S3_PATH_STYLE"),
+        }
+    }
+}
+
+/// Настройки расписания школы.
+///
+/// Время уроков хранится в UTC, а часовой пояс школы используется для перевода дат,
+/// которые присылают клиенты, в конкретные моменты времени.
+#[derive(Clone)]
+pub struct ScheduleConfig {
+    pub timezone: Tz,
+    pub default_lesson_start: NaiveTime,
+    pub default_lesson_duration: TimeDelta,
+    pub max_lesson_duration: TimeDelta,
+}
+
+impl ScheduleConfig {
+    pub fn from_env() -> Self {
+        let timezone = env::var("SCHOOL_TIMEZONE")
+            .map(|tz| Tz::from_str(&tz).expect("SCHOOL_TIMEZONE must be a valid IANA time zone"))
+            .unwrap_or(Tz::UTC);
+        let default_lesson_start = env::var("LESSON_DEFAULT_START")
+            .map(|time| {
+                NaiveTime::parse_from_str(&time, "%H:%M")
+                    .expect("LESSON_DEFAULT_START must be in HH:MM format")
+            })
+            .unwrap_or(NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+        Self {
+            timezone,
+            default_lesson_start,
+            default_lesson_duration: minutes_from_env("LESSON_DEFAULT_DURATION_MINUTES", 45),
+            max_lesson_duration: minutes_from_env("LESSON_MAX_DURATION_MINUTES", 240),
+        }
+    }
+
+    /// Переводит местное время школы в UTC. Для времени, попадающего на переход
+    /// на летнее время, берется более ранний из возможных моментов.
+    pub fn local_to_utc(
+        &self,
+        date: NaiveDate,
+        time: NaiveTime,
+    ) -> Result<DateTime<Utc>, AppError> {
+        date.and_time(time)
+            .and_local_timezone(self.timezone)
+            .earliest()
+            .map(|local| local.with_timezone(&Utc))
+            .ok_or(AppError::BadRequest(format!(
+                "Time {} {} does not exist in time zone {}",
+                date, time, self.timezone
+            )))
+    }
+
+    /// Начало указанного дня по местному времени школы.
+    pub fn start_of_day(&self, date: NaiveDate) -> Result<DateTime<Utc>, AppError> {
+        self.local_to_utc(date, NaiveTime::MIN)
+    }
+
+    pub fn local_date(&self, moment: DateTime<Utc>) -> NaiveDate {
+        moment.with_timezone(&self.timezone).date_naive()
+    }
+
+    pub fn local_time(&self, moment: DateTime<Utc>) -> NaiveTime {
+        moment.with_timezone(&self.timezone).time()
+    }
+
+    /// Текущая дата по местному времени школы.
+    pub fn today(&self) -> NaiveDate {
+        self.local_date(Utc::now())
+    }
+}
+
+fn minutes_from_env(name: &str, default: i64) -> TimeDelta {
+    let minutes = env::var(name)
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .unwrap_or_else(|_| panic!("{} must be a number of minutes", name))
+        })
+        .unwrap_or(default);
+    TimeDelta::minutes(minutes)
+}
+
+fn flag_from_env(name: &str) -> bool {
+    env::var(name)
+        .map(|value| {
+            value
+                .parse::<bool>()
+                .unwrap_or_else(|_| panic!("{} must be true or false", name))
+        })
+        .unwrap_or(false)
+}
+
+fn count_from_env(name: &str, default: i64) -> i64 {
+    env::var(name)
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .unwrap_or_else(|_| panic!("{} must be a number", name))
+        })
+        .unwrap_or(default)
+}
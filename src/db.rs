@@ -2,13 +2,16 @@ use std::env;
 
 use diesel::{
     prelude::*,
-    r2d2::{self, ConnectionManager, Pool, PooledConnection},
+    r2d2::{self, ConnectionManager, CustomizeConnection, Pool, PooledConnection},
+    sql_types::{Integer, Text},
 };
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use tower_sessions_redis_store::fred::prelude::{
     Config, ConnectionConfig, PerformanceConfig, ReconnectPolicy,
 };
 
+use crate::config::ScheduleConfig;
+
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
 
 pub type PostgresPool = Pool<ConnectionManager<PgConnection>>;
@@ -20,10 +23,46 @@ pub fn establish_postgres_connection() -> PostgresPool {
     let postgres_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let connection_manager = ConnectionManager::<PgConnection>::new(postgres_url);
     Pool::builder()
+        .connection_customizer(Box::new(ScheduleSettings::from(ScheduleConfig::from_env())))
         .build(connection_manager)
         .expect("Failed to create Postgres pool")
 }
 
+/// Передает настройки расписания школы в параметры сессии Postgres. По ним миграции
+/// и функция `school_date` переводят время уроков в местное время школы.
+#[derive(Debug)]
+struct ScheduleSettings {
+    timezone: String,
+    default_lesson_start: String,
+    default_lesson_duration: i32,
+}
+
+impl From<ScheduleConfig> for ScheduleSettings {
+    fn from(schedule: ScheduleConfig) -> Self {
+        Self {
+            timezone: schedule.timezone.name().to_string(),
+            default_lesson_start: schedule.default_lesson_start.format("%H:%M").to_string(),
+            default_lesson_duration: schedule.default_lesson_duration.num_minutes() as i32,
+        }
+    }
+}
+
+impl CustomizeConnection<PgConnection, r2d2::Error> for ScheduleSettings {
+    fn on_acquire(&self, connection: &mut PgConnection) -> Result<(), r2d2::Error> {
+        diesel::sql_query(
+            "SELECT set_config('app.school_timezone', $1, false), \
+             set_config('app.lesson_default_start', $2, false), \
+             set_config('app.lesson_default_duration', $3::TEXT, false)",
+        )
+        .bind::<Text, _>(&self.timezone)
+        .bind::<Text, _>(&self.default_lesson_start)
+        .bind::<Integer, _>(self.default_lesson_duration)
+        .execute(connection)
+        .map(|_| ())
+        .map_err(r2d2::Error::QueryError)
+    }
+}
+
 pub async fn establish_redis_connection() -> RedisPool {
     let redis_url = env::var("REDIS_URL").expect("REDIS_URL must be set");
     RedisPool::new(
@@ -1,10 +1,20 @@
 use axum::{
     Json,
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Response},
 };
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use thiserror::Error;
-use tracing::error;
+use tower_sessions_redis_store::fred::prelude::Error as RedisError;
+use tracing::{error, warn};
+
+use crate::{
+    handlers::utils::request_id::current_request_id,
+    models::{
+        lesson_conflict::LessonConflict,
+        problem_details::{FieldError, ProblemDetails},
+    },
+};
 
 #[derive(Error, Debug)]
 pub enum AppError {
@@ -23,12 +33,41 @@ pub enum AppError {
     #[error("Authentication required")]
     Unauthorized(String),
 
+    #[error("Schedule conflict")]
+    Conflict(Vec<LessonConflict>),
+
+    #[error("Student group is full")]
+    GroupFull(String),
+
+    #[error("Entity already exists")]
+    AlreadyExists(String),
+
+    #[error("Payload too large")]
+    PayloadTooLarge(String),
+
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+
+    /// Описание и число секунд, через которое запрос можно повторить.
+    #[error("Too many requests")]
+    TooManyRequests(String, i64),
+
+    /// Размер файла, из которого запрошен диапазон байт.
+    #[error("Range not satisfiable")]
+    RangeNotSatisfiable(u64),
+
     #[error("Database error")]
     Database(#[from] diesel::result::Error),
 
     #[error("Connection pool error")]
     Pool(#[from] r2d2::Error),
 
+    #[error("Redis error")]
+    Redis(#[from] RedisError),
+
+    #[error("Storage error")]
+    Storage(String),
+
     #[error("Multipart parsing error")]
     Multipart(#[from] axum::extract::multipart::MultipartError),
 
@@ -44,22 +83,73 @@ impl AppError {
             AppError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Forbidden(_) => StatusCode::FORBIDDEN,
             AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::GroupFull(_) => StatusCode::CONFLICT,
+            AppError::AlreadyExists(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            AppError::TooManyRequests(..) => StatusCode::TOO_MANY_REQUESTS,
+            AppError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+            AppError::Database(DieselError::NotFound) => StatusCode::NOT_FOUND,
+            AppError::Database(DieselError::DatabaseError(kind, _)) => match kind {
+                DatabaseErrorKind::UniqueViolation => StatusCode::CONFLICT,
+                DatabaseErrorKind::ForeignKeyViolation | DatabaseErrorKind::CheckViolation => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            },
             AppError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
             AppError::Pool(_) => StatusCode::INTERNAL_SERVER_ERROR,
-            AppError::Multipart(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Redis(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            AppError::Multipart(e) => e.status(),
             AppError::IO(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
+    /// Стабильный машиночитаемый код ошибки, который клиенты могут сравнивать.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::BadRequest(_) => "bad_request",
+            AppError::NotFound(_) => "not_found",
+            AppError::InternalServerError(_) => "internal_error",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Conflict(_) => "schedule_conflict",
+            AppError::GroupFull(_) => "group_full",
+            AppError::AlreadyExists(_) => "already_exists",
+            AppError::PayloadTooLarge(_) => "payload_too_large",
+            AppError::Validation(_) => "validation_failed",
+            AppError::TooManyRequests(..) => "too_many_requests",
+            AppError::RangeNotSatisfiable(_) => "range_not_satisfiable",
+            AppError::Database(DieselError::NotFound) => "not_found",
+            AppError::Database(DieselError::DatabaseError(kind, _)) => match kind {
+                DatabaseErrorKind::UniqueViolation => "already_exists",
+                DatabaseErrorKind::ForeignKeyViolation => "invalid_reference",
+                DatabaseErrorKind::CheckViolation => "constraint_violation",
+                _ => "database_error",
+            },
+            AppError::Database(_) => "database_error",
+            AppError::Pool(_) => "database_unavailable",
+            AppError::Redis(_) => "cache_unavailable",
+            AppError::Storage(_) => "storage_unavailable",
+            AppError::Multipart(e) if e.status().is_client_error() => "invalid_multipart",
+            AppError::Multipart(_) => "internal_error",
+            AppError::IO(_) => "internal_error",
+        }
+    }
+
+    /// Логирует ошибку и возвращает ее описание для клиента. Подробности внутренних ошибок
+    /// клиенту не отдаются и остаются только в логах.
     pub fn message(&self) -> String {
         match self {
             AppError::BadRequest(msg) => {
                 error!("Bad Request: {}", msg);
-                "Bad request".to_string()
+                msg.clone()
             }
             AppError::NotFound(msg) => {
                 error!("Not Found: {}", msg);
-                "Resource not found".to_string()
+                msg.clone()
             }
             AppError::InternalServerError(msg) => {
                 error!("Internal Server Error: {}", msg);
@@ -67,11 +157,73 @@ impl AppError {
             }
             AppError::Forbidden(msg) => {
                 error!("Forbidden: {}", msg);
-                "Forbidden access".to_string()
+                msg.clone()
             }
             AppError::Unauthorized(msg) => {
                 error!("Unauthorized: {}", msg);
-                "Unauthorized access".to_string()
+                msg.clone()
+            }
+            AppError::Conflict(conflicts) => {
+                error!("Schedule conflict with {} lessons", conflicts.len());
+                "Lesson conflicts with existing lessons".to_string()
+            }
+            AppError::GroupFull(msg) => {
+                error!("Group Full: {}", msg);
+                msg.clone()
+            }
+            AppError::AlreadyExists(msg) => {
+                warn!("Already Exists: {}", msg);
+                msg.clone()
+            }
+            AppError::PayloadTooLarge(msg) => {
+                warn!("Payload Too Large: {}", msg);
+                msg.clone()
+            }
+            AppError::Validation(errors) => {
+                warn!("Validation failed for {} fields", errors.len());
+                "Request contains invalid fields".to_string()
+            }
+            AppError::TooManyRequests(msg, retry_after) => {
+                warn!("Too Many Requests: {} (retry after {} s)", msg, retry_after);
+                msg.clone()
+            }
+            AppError::RangeNotSatisfiable(size) => {
+                warn!("Range Not Satisfiable: file size is {} bytes", size);
+                format!(
+                    "Requested range is not satisfiable, file size is {} bytes",
+                    size
+                )
+            }
+            AppError::Database(DieselError::NotFound) => {
+                error!("Database record not found");
+                "Resource not found".to_string()
+            }
+            AppError::Database(DieselError::DatabaseError(kind, info)) => {
+                let constraint = info.constraint_name().unwrap_or("unknown");
+                match kind {
+                    DatabaseErrorKind::UniqueViolation => {
+                        warn!("Unique violation: {}", info.message());
+                        format!(
+                            "Resource with the same unique fields already exists (constraint {})",
+                            constraint
+                        )
+                    }
+                    DatabaseErrorKind::ForeignKeyViolation => {
+                        warn!("Foreign key violation: {}", info.message());
+                        format!(
+                            "Referenced resource does not exist or is still in use (constraint {})",
+                            constraint
+                        )
+                    }
+                    DatabaseErrorKind::CheckViolation => {
+                        warn!("Check violation: {}", info.message());
+                        format!("Value violates constraint {}", constraint)
+                    }
+                    _ => {
+                        error!("Database error occurred: {}", info.message());
+                        "Database error occurred".to_string()
+                    }
+                }
             }
             AppError::Database(e) => {
                 error!("Database error occurred: {}", e);
@@ -81,9 +233,21 @@ impl AppError {
                 error!("Connection pool error: {}", e);
                 "Connection pool error occurred".to_string()
             }
+            AppError::Redis(e) => {
+                error!("Redis error: {}", e);
+                "Redis error occurred".to_string()
+            }
+            AppError::Storage(msg) => {
+                error!("Storage error: {}", msg);
+                "Storage error occurred".to_string()
+            }
             AppError::Multipart(e) => {
                 error!("Multipart error occurred: {}", e);
-                "Multipart error occurred".to_string()
+                if e.status().is_client_error() {
+                    e.body_text()
+                } else {
+                    "Multipart error occurred".to_string()
+                }
             }
             AppError::IO(e) => {
                 error!("I/O error occurred: {}", e);
@@ -92,9 +256,54 @@ impl AppError {
         }
     }
 }
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        (self.status_code(), Json(self.message())).into_response()
+        let status_code = self.status_code();
+        let detail = self.message();
+        let code = self.code().to_string();
+        let retry_after = match &self {
+            AppError::TooManyRequests(_, retry_after) => Some(*retry_after),
+            _ => None,
+        };
+        let content_range = match &self {
+            AppError::RangeNotSatisfiable(size) => Some(format!("bytes */{}", size)),
+            _ => None,
+        };
+        let (errors, conflicts) = match self {
+            AppError::Validation(errors) => (errors, None),
+            AppError::Conflict(conflicts) => (Vec::new(), Some(conflicts)),
+            _ => (Vec::new(), None),
+        };
+        let problem = ProblemDetails {
+            problem_type: "about:blank".to_string(),
+            title: status_code
+                .canonical_reason()
+                .unwrap_or("Unknown error")
+                .to_string(),
+            status: status_code.as_u16(),
+            detail,
+            code,
+            request_id: current_request_id(),
+            errors,
+            conflicts,
+        };
+
+        let mut response = (status_code, Json(problem)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/problem+json"),
+        );
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after.max(1)));
+        }
+        if let Some(content_range) = content_range
+            && let Ok(value) = HeaderValue::from_str(&content_range)
+        {
+            response.headers_mut().insert(header::CONTENT_RANGE, value);
+        }
+        response
     }
 }
-//TODO: сделать как здесь https://github.com/launchbadge/realworld-axum-sqlx/blob/main/src/http/error.rs
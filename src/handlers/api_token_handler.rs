@@ -0,0 +1,174 @@
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use axum_login::AuthSession;
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
+    error::AppError,
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::api_token_service::ApiTokenService,
+    models::{
+        api_token::{ApiToken, ApiTokenFilter, CreatedApiToken, RawNewApiToken},
+        pagination::{PaginatedResponse, PaginationParams},
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(create_api_token, get_api_tokens))
+        .routes(routes!(revoke_api_token))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    OpenApiRouter::new().merge(need_user)
+}
+
+/// Создание API токена
+///
+/// Этот эндпоинт создает API токен, с которым скрипты и другие программные клиенты могут
+/// обращаться к API без входа по логину и паролю. Токен передается в заголовке
+/// `Authorization: Bearer <токен>` и дает те же права, что и пользователь, но не выше
+/// роли токена.
+///
+/// ### Входные данные:
+/// - `name`: Название токена (обязательное поле)
+/// - `role`: Роль токена (необязательное поле, по умолчанию роль пользователя). Не может быть
+///   выше роли пользователя
+/// - `expires_at`: Время истечения токена (необязательное поле, по умолчанию токен
+///   бессрочный)
+/// - `user_id`: ID пользователя, для которого создается токен (необязательное поле, по
+///   умолчанию текущий пользователь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Создавать токены для других пользователей может только `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Токен успешно создан. Возвращает данные токена и сам токен. Токен больше
+///   нигде не показывается, его нужно сохранить сразу.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав для создания токена другому пользователю.
+/// - **404 Not Found**: Пользователь не найден.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = RawNewApiToken,
+    responses(
+        (status = 200, body = CreatedApiToken, description = "Токен успешно создан"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ApiToken"
+)]
+async fn create_api_token(
+    State(api_token_service): State<ApiTokenService>,
+    auth_session: AuthSession<AuthBackend>,
+    ValidatedJson(new_api_token): ValidatedJson<RawNewApiToken>,
+) -> Result<Json<CreatedApiToken>, AppError> {
+    info!("Creating new API token");
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let created_api_token = api_token_service.create(user, new_api_token)?;
+    Ok(Json(created_api_token))
+}
+
+/// Получение списка API токенов
+///
+/// Этот эндпоинт возвращает постраничный список API токенов пользователя, включая
+/// отозванные и истекшие. Сами токены не возвращаются.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `user_id`: ID пользователя (необязательный, по умолчанию текущий пользователь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Смотреть токены других пользователей может только `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Список токенов успешно получен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав для просмотра токенов другого пользователя.
+/// - **404 Not Found**: Пользователь не найден.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, ApiTokenFilter),
+    responses(
+        (status = 200, body = PaginatedResponse<ApiToken>, description = "Список токенов успешно получен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ApiToken"
+)]
+async fn get_api_tokens(
+    State(api_token_service): State<ApiTokenService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<ApiTokenFilter>,
+) -> Result<Json<PaginatedResponse<ApiToken>>, AppError> {
+    info!("Getting API tokens");
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let api_tokens = api_token_service.get_all(user, filter, &pagination)?;
+    Ok(Json(api_tokens))
+}
+
+/// Отзыв API токена
+///
+/// Этот эндпоинт отзывает API токен. Отозванный токен сразу перестает действовать и не
+/// может быть восстановлен.
+///
+/// ### Параметры:
+/// - `id`: ID токена (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Отзывать токены других пользователей может только `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Токен отозван. Возвращает данные токена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **404 Not Found**: Токен не найден.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID токена")
+    ),
+    responses(
+        (status = 200, body = ApiToken, description = "Токен отозван"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 404, description = "Токен не найден"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ApiToken"
+)]
+async fn revoke_api_token(
+    State(api_token_service): State<ApiTokenService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(api_token_id): Path<i32>,
+) -> Result<Json<ApiToken>, AppError> {
+    info!("Revoking API token with ID {}", api_token_id);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let api_token = api_token_service.revoke(user, api_token_id)?;
+    Ok(Json(api_token))
+}
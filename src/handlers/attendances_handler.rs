@@ -1,27 +1,38 @@
-use axum::{
-    Json,
-    extract::{Path, State},
-};
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
     error::AppError,
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
     logic::services::attendance_service::AttendanceService,
-    models::attendance::{AttendanceWithRelations, NewAttendance, UpdateAttendance},
+    models::{
+        attendance::{AttendanceFilter, AttendanceWithRelations, NewAttendance, UpdateAttendance},
+        pagination::{PaginatedResponse, PaginationParams},
+        user::PermissionRole,
+    },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new()
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(create_attendance, get_attendances))
         .routes(routes!(
-            create_attendance,
             get_attendance,
             update_attendance,
-            delete_attendance,
+            delete_attendance
         ))
-        .routes(routes!(get_attendances_by_lesson));
-    OpenApiRouter::new().merge(dont_need_permissions)
+        .routes(routes!(get_attendances_by_lesson))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    OpenApiRouter::new().merge(need_teacher)
 }
 
 /// Создание нового посещения
@@ -35,9 +46,16 @@ pub fn router() -> OpenApiRouter<AppState> {
 /// false)
 /// - `skip_reason`: Причина пропуска
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может отмечать посещения только уроков групп, которые он ведет
+///
 /// ### Ответы:
 /// - **201 Created**: Посещение успешно создано. Возвращает данные созданного посещения.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствуют обязательные поля).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав или преподаватель не ведет группу урока.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -46,19 +64,69 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 201, body = AttendanceWithRelations, description = "Посещение успешно создано"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Attendance"
 )]
 async fn create_attendance(
     State(attendance_service): State<AttendanceService>,
-    Json(new_attendance): Json<NewAttendance>,
+    auth_session: AuthSession<AuthBackend>,
+    ValidatedJson(new_attendance): ValidatedJson<NewAttendance>,
 ) -> Result<Json<AttendanceWithRelations>, AppError> {
     info!("Creating new attendance");
-    let created_attendance = attendance_service.create(new_attendance)?;
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let created_attendance = attendance_service.create(new_attendance, user)?;
     Ok(Json(created_attendance))
 }
 
+/// Получение списка посещений
+///
+/// Этот эндпоинт возвращает постраничный список посещений с возможностью фильтрации.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `student_id`: ID ученика (необязательный)
+/// - `lesson_id`: ID урока (необязательный)
+/// - `is_present`: Отметка о присутствии (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Список посещений успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, AttendanceFilter),
+    responses(
+        (status = 200, body = PaginatedResponse<AttendanceWithRelations>, description = "Список посещений успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Attendance"
+)]
+async fn get_attendances(
+    State(attendance_service): State<AttendanceService>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<AttendanceFilter>,
+) -> Result<Json<PaginatedResponse<AttendanceWithRelations>>, AppError> {
+    info!("Getting attendances");
+    let attendances = attendance_service.get_all(filter, &pagination)?;
+    Ok(Json(attendances))
+}
+
 /// Получение посещения по ID
 ///
 /// Этот эндпоинт позволяет получить данные конкретного посещения по его идентификатору.
@@ -66,9 +134,14 @@ async fn create_attendance(
 /// ### Параметры:
 /// - `id`: ID посещения (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
 /// ### Ответы:
 /// - **200 OK**: Данные посещения успешно получены.
 /// - **404 Not Found**: Посещение с указанным ID не найдено.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
@@ -79,8 +152,11 @@ async fn create_attendance(
     responses(
         (status = 200, body = AttendanceWithRelations, description = "Данные посещения успешно получены"),
         (status = 404, description = "Посещение не найдено"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Attendance"
 )]
 async fn get_attendance(
@@ -99,9 +175,14 @@ async fn get_attendance(
 /// ### Параметры:
 /// - `lesson_id`: ID урока (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
 /// ### Ответы:
 /// - **200 OK**: Список посещений успешно получен.
 /// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
@@ -112,8 +193,11 @@ async fn get_attendance(
     responses(
         (status = 200, body = Vec<AttendanceWithRelations>, description = "Список посещений успешно получен"),
         (status = 404, description = "Урок не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Attendance"
 )]
 async fn get_attendances_by_lesson(
@@ -136,13 +220,20 @@ async fn get_attendances_by_lesson(
 /// - `is_present`: Отметка о том, что студент присутствовал
 /// - `skip_reason`: Причина пропуска
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может изменять посещения только уроков групп, которые он ведет
+///
 /// ### Ответы:
 /// - **200 OK**: Данные посещения успешно обновлены.
 /// - **404 Not Found**: Посещение с указанным ID не найдено.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав или преподаватель не ведет группу урока.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID посещения которое требуется обновить")
@@ -152,17 +243,23 @@ async fn get_attendances_by_lesson(
         (status = 200, body = AttendanceWithRelations, description = "Данные посещения успешно обновлены"),
         (status = 404, description = "Посещение не найдено"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Attendance"
 )]
 async fn update_attendance(
     State(attendance_service): State<AttendanceService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(attendance_id): Path<i32>,
-    Json(update_attendance): Json<UpdateAttendance>,
+    ValidatedJson(update_attendance): ValidatedJson<UpdateAttendance>,
 ) -> Result<Json<AttendanceWithRelations>, AppError> {
     info!("Updating attendance");
-    let updated_attendance = attendance_service.update(attendance_id, update_attendance)?;
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let updated_attendance = attendance_service.update(attendance_id, update_attendance, user)?;
     Ok(Json(updated_attendance))
 }
 
@@ -173,9 +270,15 @@ async fn update_attendance(
 /// ### Параметры:
 /// - `id`: ID посещения (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может удалять посещения только уроков групп, которые он ведет
+///
 /// ### Ответы:
 /// - **200 OK**: Посещение успешно удалено.
 /// - **404 Not Found**: Посещение с указанным ID не найдено.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав или преподаватель не ведет группу урока.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -186,16 +289,21 @@ async fn update_attendance(
     responses(
         (status = 200, body = String, description = "Посещение успешно удалено"),
         (status = 404, description = "Посещение не найдено"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Attendance"
 )]
 async fn delete_attendance(
     State(attendance_service): State<AttendanceService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(attendance_id): Path<i32>,
 ) -> Result<Json<String>, AppError> {
     info!("Deleting attendance");
-    let deleted = attendance_service.delete(attendance_id)?;
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let deleted = attendance_service.delete(attendance_id, user)?;
     if deleted {
         Ok(Json("Successfully deleted".to_string()))
     } else {
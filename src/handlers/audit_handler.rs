@@ -0,0 +1,72 @@
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::permission::require_role,
+    error::AppError,
+    handlers::utils::extract::Query,
+    logic::services::audit_service::AuditService,
+    models::{
+        audit::{AuditEntry, AuditFilter},
+        pagination::{PaginatedResponse, PaginationParams},
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(get_audit_entries))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role))
+}
+
+/// Получение журнала изменений
+///
+/// Этот эндпоинт возвращает постраничный журнал изменений данных, начиная с последних.
+/// В журнал попадает каждое создание, изменение, удаление и восстановление записи: кто
+/// выполнил действие, когда, и состояние записи до и после него. Журнал нельзя изменить
+/// или очистить.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `entity_type`: Тип измененной записи, например `student` или `lesson` (необязательный)
+/// - `entity_id`: ID измененной записи (необязательный)
+/// - `user_id`: ID пользователя, выполнившего действие (необязательный)
+/// - `from`: Начало промежутка времени в формате RFC 3339 (необязательный)
+/// - `to`: Конец промежутка времени в формате RFC 3339, не включается (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Журнал успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса (например, промежуток заканчивается
+///   раньше, чем начинается).
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, AuditFilter),
+    responses(
+        (status = 200, body = PaginatedResponse<AuditEntry>, description = "Журнал успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Audit"
+)]
+async fn get_audit_entries(
+    State(audit_service): State<AuditService>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<AuditFilter>,
+) -> Result<Json<PaginatedResponse<AuditEntry>>, AppError> {
+    info!("Getting audit entries");
+    let entries = audit_service.get_all(filter, &pagination)?;
+    Ok(Json(entries))
+}
@@ -1,25 +1,50 @@
-use axum::{Form, Json, http::StatusCode};
+use std::net::SocketAddr;
+
+use axum::{
+    Form, Json,
+    extract::{ConnectInfo, State},
+    http::StatusCode,
+    middleware::from_fn_with_state,
+};
 use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
-    auth::backend::AuthBackend,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
     error::AppError,
-    models::user::{Credentials, User},
+    handlers::utils::validated_json::ValidatedJson,
+    logic::services::{login_throttle_service::LoginThrottleService, user_service::UserService},
+    models::{
+        password_reset::PasswordReset,
+        user::{ChangePassword, Credentials, PermissionRole, User, UserWithProfile},
+    },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_me))
+        .routes(routes!(change_password))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
     OpenApiRouter::new()
         .routes(routes!(login))
         .routes(routes!(logout))
+        .routes(routes!(reset_password))
+        .merge(need_user)
 }
 
 /// Вход пользователя в систему
 ///
 /// Этот эндпоинт позволяет авторизовать пользователя по его логину и паролю.
 ///
+/// После нескольких неудачных попыток подряд вход по этому логину или с этого IP-адреса
+/// временно блокируется. Каждая следующая неудачная попытка удваивает срок блокировки.
+/// Пока вход заблокирован, пароль не проверяется.
+///
 /// ### Входные данные:
 /// - `username`: Имя пользователя (обязательное поле)
 /// - `password`: Пароль (обязательное поле)
@@ -27,6 +52,8 @@ pub fn router() -> OpenApiRouter<AppState> {
 /// ### Ответы:
 /// - **200 OK**: Пользователь успешно авторизован. Возвращает объект пользователя.
 /// - **401 Unauthorized**: Неверный логин или пароль.
+/// - **429 Too Many Requests**: Вход временно заблокирован. Заголовок `Retry-After`
+///   содержит число секунд до окончания блокировки.
 /// - **500 Internal Server Error**: Ошибка сервера при аутентификации.
 #[utoipa::path(
     post,
@@ -35,11 +62,18 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 200, body = User, description = "Пользователь успешно авторизован"),
         (status = 401, description = "Неверные данные"),
+        (
+            status = 429,
+            description = "Вход временно заблокирован",
+            headers(("Retry-After" = i64, description = "Число секунд до окончания блокировки"))
+        ),
         (status = 500, description = "Ошибка сервера")
     ),
     tag = "Auth"
 )]
 async fn login(
+    State(login_throttle_service): State<LoginThrottleService>,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
     mut auth_session: AuthSession<AuthBackend>,
     Form(creds): Form<Credentials>,
 ) -> Result<Json<User>, AppError> {
@@ -47,13 +81,21 @@ async fn login(
         "Attempting to log in user with username: {}",
         creds.username
     );
+    let username = creds.username.clone();
+    login_throttle_service
+        .ensure_not_locked(&username, address.ip())
+        .await?;
     let user = match auth_session.authenticate(creds).await {
         Ok(Some(user)) => user,
         Ok(None) => {
+            login_throttle_service
+                .register_failure(&username, address.ip())
+                .await?;
             return Err(AppError::Unauthorized("User not found".to_string()));
         }
         Err(e) => return Err(AppError::Unauthorized(e.to_string())),
     };
+    login_throttle_service.register_success(&username).await?;
 
     match auth_session.login(&user).await {
         Ok(_) => Ok(Json(user)),
@@ -83,3 +125,120 @@ async fn logout(mut auth_session: AuthSession<AuthBackend>) -> Result<StatusCode
         Err(e) => Err(AppError::InternalServerError(e.to_string())),
     }
 }
+
+/// Получение текущего пользователя
+///
+/// Этот эндпоинт возвращает авторизованного пользователя вместе со связанными с ним
+/// профилями преподавателя и родителя.
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Пользователь успешно получен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/me",
+    responses(
+        (status = 200, body = UserWithProfile, description = "Пользователь успешно получен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Auth"
+)]
+async fn get_me(
+    State(user_service): State<UserService>,
+    auth_session: AuthSession<AuthBackend>,
+) -> Result<Json<UserWithProfile>, AppError> {
+    info!("Getting current user");
+    let user = authenticated_user(auth_session.user.as_ref())?.clone();
+    let user = user_service.get_with_profile(user)?;
+    Ok(Json(user))
+}
+
+/// Смена пароля
+///
+/// Этот эндпоинт позволяет авторизованному пользователю сменить свой пароль. Все
+/// остальные сессии пользователя после смены пароля завершаются, текущая сессия
+/// остается активной.
+///
+/// ### Входные данные:
+/// - `old_password`: Текущий пароль (обязательное поле)
+/// - `new_password`: Новый пароль (обязательное поле). Должен отличаться от текущего и
+///   соответствовать требованиям к паролям
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Пароль успешно изменен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **422 Unprocessable Entity**: Текущий пароль неверен или новый пароль не соответствует
+///   требованиям. Возвращает ошибки по полям.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/password",
+    request_body = ChangePassword,
+    responses(
+        (status = 200, description = "Пароль успешно изменен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Auth"
+)]
+async fn change_password(
+    State(user_service): State<UserService>,
+    mut auth_session: AuthSession<AuthBackend>,
+    ValidatedJson(change): ValidatedJson<ChangePassword>,
+) -> Result<StatusCode, AppError> {
+    info!("Changing password of current user");
+    let user = authenticated_user(auth_session.user.as_ref())?.clone();
+    let updated_user = user_service.change_password(&user, change)?;
+    match auth_session.login(&updated_user).await {
+        Ok(_) => Ok(StatusCode::OK),
+        Err(e) => Err(AppError::InternalServerError(e.to_string())),
+    }
+}
+
+/// Сброс пароля по токену
+///
+/// Этот эндпоинт позволяет задать новый пароль по одноразовому токену, который выдал
+/// администратор. После сброса все сессии пользователя завершаются.
+///
+/// ### Входные данные:
+/// - `token`: Токен сброса пароля (обязательное поле)
+/// - `new_password`: Новый пароль (обязательное поле). Должен соответствовать требованиям к
+///   паролям
+///
+/// ### Ответы:
+/// - **200 OK**: Пароль успешно изменен.
+/// - **400 Bad Request**: Токен неверен, уже использован или истек.
+/// - **422 Unprocessable Entity**: Новый пароль не соответствует требованиям. Возвращает
+///   ошибки по полям.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/password/reset",
+    request_body = PasswordReset,
+    responses(
+        (status = 200, description = "Пароль успешно изменен"),
+        (status = 400, description = "Токен неверен, уже использован или истек"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    tag = "Auth"
+)]
+async fn reset_password(
+    State(user_service): State<UserService>,
+    ValidatedJson(password_reset): ValidatedJson<PasswordReset>,
+) -> Result<StatusCode, AppError> {
+    info!("Resetting password by token");
+    user_service.reset_password(password_reset)?;
+    Ok(StatusCode::OK)
+}
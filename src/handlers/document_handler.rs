@@ -0,0 +1,366 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{DefaultBodyLimit, Multipart, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::from_fn_with_state,
+    response::{IntoResponse, Response},
+};
+use axum_login::AuthSession;
+use percent_encoding::{AsciiSet, NON_ALPHANUMERIC, utf8_percent_encode};
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+use uuid::Uuid;
+
+use crate::{
+    AppState,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
+    error::AppError,
+    handlers::utils::{
+        byte_range::parse_range,
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::document_service::DocumentService,
+    models::{
+        document::{
+            Document, DocumentContent, DocumentFilter, DocumentOwner, DocumentReportParams,
+            RawUpdateDocument, TeacherDocumentReport,
+        },
+        user::PermissionRole,
+    },
+};
+
+/// Символы, которые можно не кодировать в `filename*` заголовка `Content-Disposition`
+/// (RFC 8187).
+const FILENAME_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'!')
+    .remove(b'#')
+    .remove(b'$')
+    .remove(b'&')
+    .remove(b'+')
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'^')
+    .remove(b'_')
+    .remove(b'`')
+    .remove(b'|')
+    .remove(b'~');
+
+pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_document_content))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(update_document))
+        .routes(routes!(get_expiry_report))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    OpenApiRouter::new().merge(need_user).merge(need_director)
+}
+
+/// Роутер с эндпоинтами загрузки документов из `router`, доступными начиная с роли `role`.
+pub fn upload_router(
+    router: OpenApiRouter<AppState>,
+    role: PermissionRole,
+) -> OpenApiRouter<AppState> {
+    // Размер загружаемого файла проверяет DocumentService по мере чтения
+    router
+        .layer(DefaultBodyLimit::disable())
+        .route_layer(from_fn_with_state(role, require_role))
+}
+
+/// Загружает документ из `multipart` и прикрепляет его к записи `owner`.
+pub async fn upload_owner_document(
+    document_service: DocumentService,
+    auth_session: AuthSession<AuthBackend>,
+    owner: DocumentOwner,
+    multipart: Multipart,
+) -> Result<Json<Document>, AppError> {
+    info!("Uploading document to {}", owner);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let document = document_service.create(user, multipart, owner).await?;
+    Ok(Json(document))
+}
+
+/// Документы записи `owner`, подходящие под `filter`.
+pub fn get_owner_documents(
+    document_service: DocumentService,
+    auth_session: AuthSession<AuthBackend>,
+    owner: DocumentOwner,
+    filter: DocumentFilter,
+) -> Result<Json<Vec<Document>>, AppError> {
+    info!("Getting documents for {}", owner);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let documents = document_service.get_by_owner(user, owner, &filter)?;
+    Ok(Json(documents))
+}
+
+/// Обновление сведений о документе
+///
+/// Этот эндпоинт позволяет изменить вид, описание и срок действия документа. Файл документа
+/// не меняется. Меняются только переданные поля, запрос доступен методами `PUT` и `PATCH`.
+///
+/// ### Параметры:
+/// - `id`: ID документа (обязательный путь)
+///
+/// ### Входные данные:
+/// - `category`: Вид документа: `certificate`, `medical_check`, `contract` или `other`
+/// - `description`: Описание документа, `null` удаляет описание
+/// - `valid_until`: Последний день действия документа, `null` делает документ бессрочным
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Сведения о документе успешно обновлены.
+/// - **404 Not Found**: Документ с указанным ID не найден.
+/// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    method(put, patch),
+    path = "/{id}",
+    params(
+        ("id" = Uuid, Path, description = "ID документа который требуется обновить")
+    ),
+    request_body = RawUpdateDocument,
+    responses(
+        (status = 200, body = Document, description = "Сведения о документе успешно обновлены"),
+        (status = 404, description = "Документ не найден"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Document"
+)]
+async fn update_document(
+    State(document_service): State<DocumentService>,
+    Path(document_id): Path<Uuid>,
+    ValidatedJson(update_document): ValidatedJson<RawUpdateDocument>,
+) -> Result<Json<Document>, AppError> {
+    info!("Updating document with ID {}", document_id);
+    let updated_document = document_service.update(document_id, update_document)?;
+    Ok(Json(updated_document))
+}
+
+/// Отчет по обязательным документам преподавателей
+///
+/// Этот эндпоинт возвращает преподавателей, у которых нет обязательного документа какого-либо
+/// вида, срок его действия уже истек или истекает в ближайшие `days` дней. Обязательные виды
+/// документов задаются настройкой `DOCUMENT_MANDATORY_CATEGORIES`.
+///
+/// Если документов одного вида несколько, учитывается тот, что действует дольше всех.
+/// Бессрочные документы не истекают. Удаленные преподаватели в отчет не попадают. Срок
+/// действия сравнивается с текущей датой в часовом поясе школы.
+///
+/// ### Параметры:
+/// - `days`: За сколько дней до окончания срока действия документ попадает в отчет
+///   (необязательный, от 0 до 366, по умолчанию `DOCUMENT_EXPIRY_WARNING_DAYS`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Отчет успешно построен. Преподаватели упорядочены по имени.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/expiry-report",
+    params(DocumentReportParams),
+    responses(
+        (status = 200, body = Vec<TeacherDocumentReport>, description = "Отчет успешно построен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Document"
+)]
+async fn get_expiry_report(
+    State(document_service): State<DocumentService>,
+    Query(params): Query<DocumentReportParams>,
+) -> Result<Json<Vec<TeacherDocumentReport>>, AppError> {
+    info!("Building document expiry report");
+    let report = document_service.get_expiry_report(&params)?;
+    Ok(Json(report))
+}
+
+/// Скачивание файла документа
+///
+/// Этот эндпоинт отдает содержимое файла документа. Файл передается потоком, поэтому
+/// подходит и для больших PDF. Имя файла в заголовке `Content-Disposition` совпадает с
+/// именем, под которым документ был загружен.
+///
+/// Поддерживаются условные запросы и запросы части файла:
+/// - `If-None-Match`: если у клиента уже есть файл с указанным `ETag`, возвращается 304
+///   без тела
+/// - `Range`: отдается только указанный диапазон байт, например `bytes=0-1023`. Поддерживается
+///   один диапазон, заголовок с несколькими диапазонами игнорируется
+/// - `If-Range`: диапазон отдается, только если `ETag` файла совпадает с указанным, иначе
+///   файл отдается целиком
+///
+/// ### Параметры:
+/// - `id`: ID документа (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Документы преподавателя доступны директору и самому преподавателю
+/// - Документы ученика доступны директору и преподавателю группы ученика
+///
+/// ### Ответы:
+/// - **200 OK**: Возвращает файл целиком.
+/// - **206 Partial Content**: Возвращает запрошенный диапазон байт файла.
+/// - **304 Not Modified**: Файл не изменился с указанного в `If-None-Match` `ETag`.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **404 Not Found**: Документ или его файл не найден.
+/// - **416 Range Not Satisfiable**: Запрошенный диапазон лежит за концом файла. Размер файла
+///   возвращается в заголовке `Content-Range`.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/content",
+    params(
+        ("id" = Uuid, Path, description = "ID документа"),
+        ("Range" = Option<String>, Header, description = "Диапазон байт, например `bytes=0-1023`"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag файла, который уже есть у клиента"),
+        ("If-Range" = Option<String>, Header, description = "ETag файла, диапазон которого запрашивается")
+    ),
+    responses(
+        (status = 200, description = "Файл документа", content_type = "application/octet-stream",
+            body = DocumentContent,
+            headers(
+                ("ETag" = String, description = "Версия файла"),
+                ("Content-Disposition" = String, description = "Имя файла")
+            )
+        ),
+        (status = 206, description = "Часть файла документа", content_type = "application/octet-stream",
+            body = DocumentContent,
+            headers(
+                ("ETag" = String, description = "Версия файла"),
+                ("Content-Range" = String, description = "Отданный диапазон и размер файла")
+            )
+        ),
+        (status = 304, description = "Файл не изменился"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Документ не найден"),
+        (status = 416, description = "Диапазон лежит за концом файла"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Document"
+)]
+async fn get_document_content(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(document_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    info!("Getting content of document with ID {}", document_id);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let document = document_service.get(user, document_id)?;
+    let etag = etag(&document);
+
+    let if_none_match = header_str(&headers, header::IF_NONE_MATCH);
+    if if_none_match.is_some_and(|value| etag_matches(value, &etag)) {
+        info!("Document with ID {} is not modified", document_id);
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    let size = document_service.get_content_size(&document).await?;
+    let if_range = header_str(&headers, header::IF_RANGE);
+    let range = match header_str(&headers, header::RANGE) {
+        Some(range) if if_range.is_none_or(|value| value.trim() == etag) => {
+            parse_range(range, size)?
+        }
+        _ => None,
+    };
+    let content = document_service
+        .get_content(&document, range.clone())
+        .await?;
+
+    let mut response = Response::new(Body::from_stream(content));
+    let response_headers = response.headers_mut();
+    response_headers.insert(
+        header::CONTENT_TYPE,
+        header_value(document.content_type.clone())?,
+    );
+    response_headers.insert(
+        header::CONTENT_DISPOSITION,
+        content_disposition(&document.name)?,
+    );
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    response_headers.insert(header::ETAG, header_value(etag)?);
+    match range {
+        Some(range) => {
+            *response.status_mut() = StatusCode::PARTIAL_CONTENT;
+            let response_headers = response.headers_mut();
+            response_headers.insert(
+                header::CONTENT_RANGE,
+                header_value(format!("bytes {}-{}/{}", range.start, range.end - 1, size))?,
+            );
+            response_headers.insert(
+                header::CONTENT_LENGTH,
+                HeaderValue::from(range.end - range.start),
+            );
+        }
+        None => {
+            response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(size));
+        }
+    }
+    Ok(response)
+}
+
+/// Файл документа после загрузки не меняется, поэтому его версией служит ID документа.
+fn etag(document: &Document) -> String {
+    format!("\"{}\"", document.id)
+}
+
+/// Совпадает ли `ETag` с одним из перечисленных в `If-None-Match`. Слабые `ETag`
+/// сравниваются как обычные, как требует RFC 9110.
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Заголовок `Content-Disposition` с именем файла. Старые клиенты, не понимающие
+/// `filename*`, получат имя, где все символы кроме ASCII заменены на `_`.
+fn content_disposition(name: &str) -> Result<HeaderValue, AppError> {
+    let fallback: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    header_value(format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback,
+        utf8_percent_encode(name, FILENAME_ENCODE_SET)
+    ))
+}
+
+fn header_str(headers: &HeaderMap, name: header::HeaderName) -> Option<&str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+fn header_value(value: String) -> Result<HeaderValue, AppError> {
+    HeaderValue::from_str(&value).map_err(|e| AppError::InternalServerError(e.to_string()))
+}
@@ -1,30 +1,63 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Multipart, State},
+    middleware::from_fn_with_state,
 };
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{backend::AuthBackend, permission::require_role},
     error::AppError,
-    logic::services::{attendance_service::AttendanceService, lesson_service::LessonService},
+    handlers::{
+        document_handler::{get_owner_documents, upload_owner_document, upload_router},
+        utils::{
+            deleted_params::DeletedParams,
+            extract::{Path, Query},
+            validated_json::ValidatedJson,
+        },
+    },
+    logic::services::{
+        attendance_service::AttendanceService, document_service::DocumentService,
+        lesson_service::LessonService,
+    },
     models::{
         attendance::AttendanceWithRelations,
-        lesson::{LessonWithRelations, NewLesson, UpdateLesson},
+        document::{Document, DocumentFileForm, DocumentFilter, DocumentOwner},
+        lesson::{LessonFilter, LessonWithRelations, RawNewLesson, RawUpdateLesson},
+        lesson_conflict::{LessonConflict, LessonConflictCheck},
+        pagination::{PaginatedResponse, PaginationParams},
+        problem_details::ProblemDetails,
+        user::PermissionRole,
     },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new()
-        .routes(routes!(
-            create_lesson,
-            get_lesson,
-            update_lesson,
-            delete_lesson
-        ))
-        .routes(routes!(get_attendances_for_lesson));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_lessons))
+        .routes(routes!(get_lesson))
+        .routes(routes!(get_lesson_documents))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(create_lesson))
+        .routes(routes!(check_lesson_conflicts))
+        .routes(routes!(update_lesson, delete_lesson))
+        .routes(routes!(get_attendances_for_lesson))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    let need_teacher_upload = upload_router(
+        OpenApiRouter::new().routes(routes!(upload_lesson_document)),
+        PermissionRole::Teacher,
+    );
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(restore_lesson))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new()
+        .merge(need_user)
+        .merge(need_teacher)
+        .merge(need_teacher_upload)
+        .merge(need_admin)
 }
 
 /// Создание нового урока
@@ -33,63 +66,191 @@ pub fn router() -> OpenApiRouter<AppState> {
 ///
 /// ### Входные данные:
 /// - `student_group_id`: ID учебной группы
+/// - `room_id`: ID кабинета. Все студенты группы должны помещаться в кабинет
 /// - `topic`: Название предмета (обязательное поле)
-/// - `scheduled_at`: Дата проведения урока (обязательное поле)
+/// - `starts_at`: Время начала урока в формате RFC 3339 (обязательное поле, если не передан
+/// `scheduled_at`)
+/// - `ends_at`: Время окончания урока в формате RFC 3339 (необязательное поле, по умолчанию
+/// урок длится стандартную продолжительность)
+/// - `scheduled_at`: Устаревшее поле с датой урока (формат: YYYY-MM-DD). Урок начнется в
+/// стандартное время школы
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
 ///
 /// ### Ответы:
 /// - **201 Created**: Урок успешно создан. Возвращает данные созданного урока.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствуют обязательные поля).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **409 Conflict**: В это время у группы или ее преподавателя уже есть урок или кабинет
+/// занят. Возвращает список пересекающихся уроков.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
     path = "/",
-    request_body = NewLesson,
+    request_body = RawNewLesson,
     responses(
         (status = 201, body = LessonWithRelations, description = "Урок успешно создан"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 409, body = ProblemDetails, description = "Урок пересекается с другими уроками"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Lesson"
 )]
 async fn create_lesson(
     State(lesson_service): State<LessonService>,
-    Json(new_lesson): Json<NewLesson>,
+    ValidatedJson(new_lesson): ValidatedJson<RawNewLesson>,
 ) -> Result<Json<LessonWithRelations>, AppError> {
     info!("Creating new lesson");
     let new_lesson = lesson_service.create(new_lesson)?;
     Ok(Json(new_lesson))
 }
 
+/// Проверка урока на пересечения
+///
+/// Этот эндпоинт ничего не сохраняет: он возвращает уроки, с которыми пересекся бы урок с
+/// указанным временем, группой и кабинетом. Урок пересекается с уроками той же группы, с
+/// уроками других групп того же преподавателя и с уроками в том же кабинете.
+///
+/// ### Входные данные:
+/// - `lesson_id`: ID изменяемого урока, чтобы он не считался пересечением сам с собой
+/// - `starts_at`: Время начала урока в формате RFC 3339 (обязательное поле)
+/// - `ends_at`: Время окончания урока (необязательное поле, по умолчанию урок длится
+/// стандартную продолжительность)
+/// - `student_group_id`: ID учебной группы
+/// - `room_id`: ID кабинета
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Проверка выполнена. Возвращает список пересекающихся уроков (пустой, если
+/// пересечений нет).
+/// - **400 Bad Request**: Неверные входные данные.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/check-conflicts",
+    request_body = LessonConflictCheck,
+    responses(
+        (status = 200, body = Vec<LessonConflict>, description = "Проверка выполнена"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Lesson"
+)]
+async fn check_lesson_conflicts(
+    State(lesson_service): State<LessonService>,
+    Json(check): Json<LessonConflictCheck>,
+) -> Result<Json<Vec<LessonConflict>>, AppError> {
+    info!("Checking lesson conflicts");
+    let conflicts = lesson_service.check_conflicts(check)?;
+    Ok(Json(conflicts))
+}
+
+/// Получение списка уроков
+///
+/// Этот эндпоинт возвращает постраничный список уроков с возможностью фильтрации.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+/// - `student_group_id`: ID учебной группы (необязательный)
+/// - `room_id`: ID кабинета (необязательный)
+/// - `lesson_series_id`: ID серии уроков (необязательный)
+/// - `from`: Уроки, заканчивающиеся позже указанного момента, формат RFC 3339 (необязательный)
+/// - `to`: Уроки, начинающиеся раньше указанного момента, формат RFC 3339 (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список уроков успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, LessonFilter, DeletedParams),
+    responses(
+        (status = 200, body = PaginatedResponse<LessonWithRelations>, description = "Список уроков успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Lesson"
+)]
+async fn get_lessons(
+    State(lesson_service): State<LessonService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(deleted): Query<DeletedParams>,
+    Query(filter): Query<LessonFilter>,
+) -> Result<Json<PaginatedResponse<LessonWithRelations>>, AppError> {
+    info!("Getting lessons");
+    let lessons =
+        lesson_service.get_all(filter, &pagination, deleted.include_deleted(&auth_session)?)?;
+    Ok(Json(lessons))
+}
+
 /// Получение урока по ID
 ///
 /// Этот эндпоинт позволяет получить данные конкретного урока по его идентификатору.
 ///
 /// ### Параметры:
 /// - `id`: ID урока (обязательный путь)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные урока успешно получены.
 /// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}",
     params(
-        ("id" = i32, Path, description = "ID запрашиваемого урока")
+        ("id" = i32, Path, description = "ID запрашиваемого урока"),
+        DeletedParams
     ),
     responses(
         (status = 200, body = LessonWithRelations, description = "Данные урока успешно получены"),
         (status = 404, description = "Урок не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["User"])),
     tag = "Lesson"
 )]
 async fn get_lesson(
     State(lesson_service): State<LessonService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(lesson_id): Path<i32>,
+    Query(deleted): Query<DeletedParams>,
 ) -> Result<Json<LessonWithRelations>, AppError> {
     info!("Getting lesson");
-    let lesson = lesson_service.get(lesson_id)?;
+    let lesson = lesson_service.get(lesson_id, deleted.include_deleted(&auth_session)?)?;
     Ok(Json(lesson))
 }
 
@@ -100,9 +261,14 @@ async fn get_lesson(
 /// ### Параметры:
 /// - `id`: ID урока (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
 /// ### Ответы:
 /// - **200 OK**: Список посещений успешно получен.
 /// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
@@ -113,8 +279,11 @@ async fn get_lesson(
     responses(
         (status = 200, body = Vec<AttendanceWithRelations>, description = "Список посещений успешно получен"),
         (status = 404, description = "Урок не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Lesson"
 )]
 async fn get_attendances_for_lesson(
@@ -126,42 +295,183 @@ async fn get_attendances_for_lesson(
     Ok(Json(attendances))
 }
 
+/// Загрузка документа для урока
+///
+/// Этот эндпоинт позволяет прикрепить документ к уроку, например раздаточный материал или домашнее задание.
+///
+/// Принимаются файлы png, jpeg, webp и pdf не больше `DOCUMENT_MAX_SIZE_MB` мегабайт. Тип
+/// файла определяется по его содержимому. Имя файла очищается от пути и недопустимых
+/// символов, а его расширение приводится в соответствие с типом файла.
+///
+/// ### Параметры:
+/// - `id`: ID урока (обязательный путь)
+///
+/// ### Входные данные (multipart/form-data):
+/// - `document`: Файл документа (обязательное поле)
+/// - `category`: Вид документа: `certificate`, `medical_check`, `contract` или `other`
+///   (необязательное поле, по умолчанию `other`)
+/// - `description`: Описание документа (необязательное поле)
+/// - `valid_until`: Последний день действия документа в формате `YYYY-MM-DD` (необязательное
+///   поле, без него документ считается бессрочным)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может загружать документы только для уроков групп, которые он ведет
+///
+/// ### Ответы:
+/// - **201 Created**: Документ успешно загружен. Возвращает данные документа.
+/// - **400 Bad Request**: Неверные входные данные, отсутствует файл или тип файла не
+///   поддерживается.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **409 Conflict**: К уроку уже прикреплен документ с таким же содержимым.
+/// - **413 Payload Too Large**: Файл больше допустимого размера.
+/// - **422 Unprocessable Entity**: Описание пустое или слишком длинное.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID урока к которому загружаем документ")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = DocumentFileForm,
+        description = "Загружаемый документ"
+    ),
+    responses(
+        (status = 201, body = Document, description = "Документ успешно загружен"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Урок не найден"),
+        (status = 409, description = "Такой документ уже загружен"),
+        (status = 413, description = "Файл слишком большой"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Lesson"
+)]
+async fn upload_lesson_document(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(lesson_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<Json<Document>, AppError> {
+    let owner = DocumentOwner::Lesson(lesson_id);
+    upload_owner_document(document_service, auth_session, owner, multipart).await
+}
+
+/// Получение всех документов урока
+///
+/// Этот эндпоинт позволяет получить список всех документов, прикрепленных к уроку.
+/// Срок действия документов сравнивается с текущей датой в часовом поясе школы.
+///
+/// ### Параметры:
+/// - `id`: ID урока (обязательный путь)
+/// - `category`: Вид документа (необязательный)
+/// - `expired`: `true` — только документы с истекшим сроком действия, `false` — только
+///   действующие и бессрочные (необязательный)
+/// - `expires_within_days`: Только действующие документы, срок действия которых истекает в
+///   ближайшие N дней (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список документов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID урока у которого запрашиваются документы"),
+        DocumentFilter
+    ),
+    responses(
+        (status = 200, body = Vec<Document>, description = "Список документов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 404, description = "Урок не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Lesson"
+)]
+async fn get_lesson_documents(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(lesson_id): Path<i32>,
+    Query(filter): Query<DocumentFilter>,
+) -> Result<Json<Vec<Document>>, AppError> {
+    let owner = DocumentOwner::Lesson(lesson_id);
+    get_owner_documents(document_service, auth_session, owner, filter)
+}
+
 /// Обновление существующего урока
 ///
-/// Этот эндпоинт позволяет обновить данные урока по его идентификатору.
+/// Этот эндпоинт позволяет обновить данные урока по его идентификатору. Для урока из серии
+/// изменяется только это занятие, остальные уроки серии остаются без изменений. Меняются
+/// только переданные поля, запрос доступен методами `PUT` и `PATCH`.
 ///
 /// ### Параметры:
 /// - `id`: ID урока (обязательный путь)
 ///
 /// ### Входные данные:
-/// - `student_group_id`: ID учебной группы
+/// - `student_group_id`: ID учебной группы, `null` отвязывает урок от группы. При смене
+/// группы посещения урока создаются заново для учеников новой группы
+/// - `room_id`: ID кабинета, `null` отвязывает урок от кабинета. Все студенты группы должны
+/// помещаться в кабинет
 /// - `topic`: Название предмета
-/// - `scheduled_at`: Дата проведения урока
+/// - `starts_at`: Новое время начала урока (продолжительность сохраняется, если не передан
+/// `ends_at`)
+/// - `ends_at`: Новое время окончания урока
+/// - `scheduled_at`: Устаревшее поле, переносит урок на другую дату с сохранением времени
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные урока успешно обновлены.
 /// - **404 Not Found**: Урок с указанным ID не найден.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **409 Conflict**: В новое время у группы или ее преподавателя уже есть урок или кабинет
+/// занят.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID урока который требуется обновить")
     ),
-    request_body = UpdateLesson,
+    request_body = RawUpdateLesson,
     responses(
         (status = 200, body = LessonWithRelations, description = "Данные урока успешно обновлены"),
         (status = 404, description = "Урок не найден"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 409, body = ProblemDetails, description = "Урок пересекается с другими уроками"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Lesson"
 )]
 async fn update_lesson(
     State(lesson_service): State<LessonService>,
     Path(lesson_id): Path<i32>,
-    Json(update_lesson): Json<UpdateLesson>,
+    ValidatedJson(update_lesson): ValidatedJson<RawUpdateLesson>,
 ) -> Result<Json<LessonWithRelations>, AppError> {
     info!("Updating lesson");
     let updated_lesson = lesson_service.update(lesson_id, update_lesson)?;
@@ -170,14 +480,23 @@ async fn update_lesson(
 
 /// Удаление урока
 ///
-/// Этот эндпоинт позволяет удалить существующий урок по его идентификатору.
+/// Этот эндпоинт позволяет удалить существующий урок по его идентификатору. Если урок
+/// относится к серии, занятие отмечается в серии как отмененное.
+///
+/// Урок помечается удаленным, отметки посещаемости сохраняются. Администратор может
+/// восстановить урок, пока не истек срок хранения удаленных записей.
 ///
 /// ### Параметры:
 /// - `id`: ID урока (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
 /// ### Ответы:
 /// - **200 OK**: Урок успешно удален.
 /// - **404 Not Found**: Урок с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -188,8 +507,11 @@ async fn update_lesson(
     responses(
         (status = 200, body = String, description = "Урок успешно удален"),
         (status = 404, description = "Урок не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Lesson"
 )]
 async fn delete_lesson(
@@ -204,3 +526,48 @@ async fn delete_lesson(
         Ok(Json("Lesson not found".to_string()))
     }
 }
+
+/// Восстановление удаленного урока
+///
+/// Этот эндпоинт снимает с урока отметку об удалении. Если урок был создан серией, его дата
+/// перестает считаться отмененной. Восстановленный урок не должен пересекаться с другими
+/// уроками группы, преподавателя или кабинета.
+///
+/// ### Параметры:
+/// - `id`: ID урока (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Урок успешно восстановлен. Возвращает данные урока.
+/// - **404 Not Found**: Удаленный урок с указанным ID не найден.
+/// - **409 Conflict**: Урок пересекается с другими уроками. Возвращает список пересечений.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/restore",
+    params(
+        ("id" = i32, Path, description = "ID восстанавливаемого урока")
+    ),
+    responses(
+        (status = 200, body = LessonWithRelations, description = "Урок успешно восстановлен"),
+        (status = 404, description = "Удаленный урок не найден"),
+        (status = 409, body = ProblemDetails, description = "Урок пересекается с другими уроками"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "Lesson"
+)]
+async fn restore_lesson(
+    State(lesson_service): State<LessonService>,
+    Path(lesson_id): Path<i32>,
+) -> Result<Json<LessonWithRelations>, AppError> {
+    info!("Restoring lesson with ID {}", lesson_id);
+    let lesson = lesson_service.restore(lesson_id)?;
+    Ok(Json(lesson))
+}
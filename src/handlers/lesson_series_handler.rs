@@ -0,0 +1,251 @@
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use chrono::NaiveDate;
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::permission::require_role,
+    error::AppError,
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::lesson_series_service::LessonSeriesService,
+    models::{
+        lesson_series::{
+            LessonSeriesWithLessons, OccurrenceScopeParams, RawNewLessonSeries,
+            RawUpdateLessonSeries,
+        },
+        problem_details::ProblemDetails,
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_lesson_series))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(create_lesson_series))
+        .routes(routes!(update_following_occurrences, cancel_occurrence))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    OpenApiRouter::new().merge(need_user).merge(need_teacher)
+}
+
+/// Создание новой серии уроков
+///
+/// Этот эндпоинт создает серию повторяющихся уроков и сразу создает все уроки серии
+/// вместе с посещениями для студентов группы.
+///
+/// ### Входные данные:
+/// - `topic`: Название предмета (обязательное поле)
+/// - `student_group_id`: ID учебной группы
+/// - `room_id`: ID кабинета. Все студенты группы должны помещаться в кабинет
+/// - `start_date`: Дата, с которой начинается серия (формат: YYYY-MM-DD, обязательное поле)
+/// - `start_time`: Местное время начала уроков (формат: HH:MM:SS, обязательное поле)
+/// - `duration_minutes`: Продолжительность урока в минутах (обязательное поле)
+/// - `weekdays`: Дни недели, 1 - понедельник, 7 - воскресенье (обязательное поле)
+/// - `interval_weeks`: Повторять каждые N недель (необязательное поле, по умолчанию 1)
+/// - `until_date`: Дата последнего возможного урока серии
+/// - `occurrence_count`: Количество уроков в серии
+///
+/// Должно быть передано хотя бы одно из полей `until_date` и `occurrence_count`.
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **201 Created**: Серия успешно создана. Возвращает серию и созданные уроки.
+/// - **400 Bad Request**: Неверные входные данные (например, серия не порождает ни одного урока).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **409 Conflict**: Уроки серии пересекаются с уроками группы, ее преподавателя или
+/// с уроками в том же кабинете.
+/// Возвращает список пересекающихся уроков.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = RawNewLessonSeries,
+    responses(
+        (status = 201, body = LessonSeriesWithLessons, description = "Серия уроков успешно создана"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 409, body = ProblemDetails, description = "Уроки серии пересекаются с другими уроками"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "LessonSeries"
+)]
+async fn create_lesson_series(
+    State(lesson_series_service): State<LessonSeriesService>,
+    ValidatedJson(new_series): ValidatedJson<RawNewLessonSeries>,
+) -> Result<Json<LessonSeriesWithLessons>, AppError> {
+    info!("Creating new lesson series");
+    let new_series = lesson_series_service.create(new_series)?;
+    Ok(Json(new_series))
+}
+
+/// Получение серии уроков по ID
+///
+/// Этот эндпоинт возвращает правило повторения серии, отмененные даты и уроки серии.
+///
+/// ### Параметры:
+/// - `id`: ID серии уроков (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Данные серии успешно получены.
+/// - **404 Not Found**: Серия с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID запрашиваемой серии уроков")
+    ),
+    responses(
+        (status = 200, body = LessonSeriesWithLessons, description = "Данные серии успешно получены"),
+        (status = 404, description = "Серия уроков не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "LessonSeries"
+)]
+async fn get_lesson_series(
+    State(lesson_series_service): State<LessonSeriesService>,
+    Path(series_id): Path<i32>,
+) -> Result<Json<LessonSeriesWithLessons>, AppError> {
+    info!("Getting lesson series");
+    let series = lesson_series_service.get(series_id)?;
+    Ok(Json(series))
+}
+
+/// Изменение занятия серии и всех следующих за ним
+///
+/// Исходная серия заканчивается перед выбранным занятием, а для него и всех следующих
+/// создается новая серия с измененными параметрами. Уроки начиная с выбранной даты
+/// пересоздаются, отмененные занятия остаются отмененными.
+///
+/// Чтобы изменить или перенести только одно занятие, используйте `PUT /api/v1/lessons/{id}`.
+///
+/// ### Параметры:
+/// - `id`: ID серии уроков (обязательный путь)
+/// - `date`: Дата занятия по правилу серии (формат: YYYY-MM-DD, обязательный путь)
+///
+/// ### Входные данные:
+/// - `topic`: Название предмета
+/// - `student_group_id`: ID учебной группы, `null` отвязывает серию от группы
+/// - `room_id`: ID кабинета, `null` отвязывает серию от кабинета. Все студенты группы
+/// должны помещаться в кабинет
+/// - `start_time`: Местное время начала уроков
+/// - `duration_minutes`: Продолжительность урока в минутах
+/// - `weekdays`: Дни недели, 1 - понедельник, 7 - воскресенье
+/// - `interval_weeks`: Повторять каждые N недель
+/// - `until_date`: Дата последнего возможного урока серии
+/// - `occurrence_count`: Количество уроков в новой серии
+///
+/// Не переданные поля берутся из исходной серии.
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Занятия успешно изменены. Возвращает новую серию.
+/// - **400 Bad Request**: Неверные входные данные или в эту дату у серии нет занятия.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **409 Conflict**: Новые уроки серии пересекаются с другими уроками.
+/// - **404 Not Found**: Серия с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    method(put, patch),
+    path = "/{id}/occurrences/{date}",
+    params(
+        ("id" = i32, Path, description = "ID серии уроков"),
+        ("date" = NaiveDate, Path, description = "Дата занятия по правилу серии")
+    ),
+    request_body = RawUpdateLessonSeries,
+    responses(
+        (status = 200, body = LessonSeriesWithLessons, description = "Занятия успешно изменены"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 409, body = ProblemDetails, description = "Уроки серии пересекаются с другими уроками"),
+        (status = 404, description = "Серия уроков не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "LessonSeries"
+)]
+async fn update_following_occurrences(
+    State(lesson_series_service): State<LessonSeriesService>,
+    Path((series_id, date)): Path<(i32, NaiveDate)>,
+    ValidatedJson(update_series): ValidatedJson<RawUpdateLessonSeries>,
+) -> Result<Json<LessonSeriesWithLessons>, AppError> {
+    info!("Updating following occurrences of lesson series");
+    let updated_series = lesson_series_service.update_following(series_id, date, update_series)?;
+    Ok(Json(updated_series))
+}
+
+/// Отмена занятия серии
+///
+/// Этот эндпоинт отменяет одно занятие серии или его и все следующие. Уроки отмененных
+/// занятий удаляются вместе с посещениями.
+///
+/// ### Параметры:
+/// - `id`: ID серии уроков (обязательный путь)
+/// - `date`: Дата занятия по правилу серии (формат: YYYY-MM-DD, обязательный путь)
+/// - `scope`: `this` - только выбранное занятие, `following` - выбранное и все следующие
+/// (необязательный, по умолчанию `this`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Занятия успешно отменены.
+/// - **400 Bad Request**: В эту дату у серии нет занятия.
+/// - **404 Not Found**: Серия с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    delete,
+    path = "/{id}/occurrences/{date}",
+    params(
+        ("id" = i32, Path, description = "ID серии уроков"),
+        ("date" = NaiveDate, Path, description = "Дата занятия по правилу серии"),
+        OccurrenceScopeParams
+    ),
+    responses(
+        (status = 200, body = String, description = "Занятия успешно отменены"),
+        (status = 400, description = "В эту дату у серии нет занятия"),
+        (status = 404, description = "Серия уроков не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "LessonSeries"
+)]
+async fn cancel_occurrence(
+    State(lesson_series_service): State<LessonSeriesService>,
+    Path((series_id, date)): Path<(i32, NaiveDate)>,
+    Query(params): Query<OccurrenceScopeParams>,
+) -> Result<Json<String>, AppError> {
+    info!("Cancelling occurrence of lesson series");
+    lesson_series_service.cancel(series_id, date, params.scope)?;
+    Ok(Json("Successfully cancelled".to_string()))
+}
@@ -1,9 +1,16 @@
+pub mod api_token_handler;
 pub mod attendances_handler;
+pub mod audit_handler;
 pub mod auth_handler;
+pub mod document_handler;
 pub mod lesson_handler;
+pub mod lesson_series_handler;
 pub mod parent_handler;
+pub mod parent_portal_handler;
+pub mod room_handler;
 pub mod student_group_handler;
 pub mod student_handler;
 pub mod teacher_handler;
+pub mod timetable_handler;
 pub mod user_handler;
-mod utils;
+pub mod utils;
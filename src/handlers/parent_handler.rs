@@ -1,25 +1,41 @@
-use axum::{
-    Json,
-    extract::{Path, State},
-};
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{backend::AuthBackend, permission::require_role},
     error::AppError,
+    handlers::utils::{
+        deleted_params::DeletedParams,
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
     logic::services::parent_service::ParentService,
-    models::parent::{NewParent, Parent, UpdateParent},
+    models::{
+        pagination::{PaginatedResponse, PaginationParams},
+        parent::{NewParent, Parent, UpdateParent},
+        user::PermissionRole,
+    },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new().routes(routes!(
-        create_parent,
-        get_parent,
-        update_parent,
-        delete_parent
-    ));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(get_parents))
+        .routes(routes!(get_parent))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(create_parent))
+        .routes(routes!(update_parent, delete_parent))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(restore_parent))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new()
+        .merge(need_teacher)
+        .merge(need_director)
+        .merge(need_admin)
 }
 
 /// Создание нового родителя
@@ -30,9 +46,15 @@ pub fn router() -> OpenApiRouter<AppState> {
 /// - `name`: Имя родителя (обязательное поле)
 /// - `additional_info`: Дополнительная информация (необязательное поле)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **201 Created**: Родитель успешно создан. Возвращает данные созданного родителя.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствуют обязательные поля).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -41,49 +63,108 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 201, body = Parent, description = "Родитель успешно создан"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Parent"
 )]
 async fn create_parent(
     State(parent_service): State<ParentService>,
-    Json(new_parent): Json<NewParent>,
+    ValidatedJson(new_parent): ValidatedJson<NewParent>,
 ) -> Result<Json<Parent>, AppError> {
     info!("Creating new parent");
     let new_parent = parent_service.create(new_parent)?;
     Ok(Json(new_parent))
 }
 
+/// Получение списка родителей
+///
+/// Этот эндпоинт возвращает постраничный список родителей.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Список родителей успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, DeletedParams),
+    responses(
+        (status = 200, body = PaginatedResponse<Parent>, description = "Список родителей успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Parent"
+)]
+async fn get_parents(
+    State(parent_service): State<ParentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(deleted): Query<DeletedParams>,
+) -> Result<Json<PaginatedResponse<Parent>>, AppError> {
+    info!("Getting parents");
+    let parents = parent_service.get_all(&pagination, deleted.include_deleted(&auth_session)?)?;
+    Ok(Json(parents))
+}
+
 /// Получение родителя по ID
 ///
 /// Этот эндпоинт позволяет получить данные конкретного родителя по его идентификатору.
 ///
 /// ### Параметры:
 /// - `id`: ID родителя (обязательный путь)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные родителя успешно получены.
 /// - **404 Not Found**: Родитель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}",
     params(
-        ("id" = i32, Path, description = "ID запрашиваемого родителя")
+        ("id" = i32, Path, description = "ID запрашиваемого родителя"),
+        DeletedParams
     ),
     responses(
         (status = 200, body = Parent, description = "Данные родителя успешно получены"),
         (status = 404, description = "Родитель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Parent"
 )]
 async fn get_parent(
     State(parent_service): State<ParentService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(parent_id): Path<i32>,
+    Query(deleted): Query<DeletedParams>,
 ) -> Result<Json<Parent>, AppError> {
     info!("Getting parent");
-    let parent = parent_service.get(parent_id)?;
+    let parent = parent_service.get(parent_id, deleted.include_deleted(&auth_session)?)?;
     Ok(Json(parent))
 }
 
@@ -98,13 +179,19 @@ async fn get_parent(
 /// - `name`: Новое имя родителя (необязательное поле)
 /// - `additional_info`: Новая дополнительная информация (необязательное поле)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Данные родителя успешно обновлены.
 /// - **404 Not Found**: Родитель с указанным ID не найден.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID родителя которого требуется обновить")
@@ -114,14 +201,18 @@ async fn get_parent(
         (status = 200, body = Parent, description = "Данные родителя успешно обновлены"),
         (status = 404, description = "Родитель не найден"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Parent"
 )]
 async fn update_parent(
     State(parent_service): State<ParentService>,
     Path(parent_id): Path<i32>,
-    Json(update_parent): Json<UpdateParent>,
+    ValidatedJson(update_parent): ValidatedJson<UpdateParent>,
 ) -> Result<Json<Parent>, AppError> {
     info!("Updating parent");
     let updated_parent = parent_service.update(parent_id, update_parent)?;
@@ -132,12 +223,20 @@ async fn update_parent(
 ///
 /// Этот эндпоинт позволяет удалить существующего родителя по его идентификатору.
 ///
+/// Родитель помечается удаленным, связи с учениками сохраняются. Администратор может
+/// восстановить родителя, пока не истек срок хранения удаленных записей.
+///
 /// ### Параметры:
 /// - `id`: ID родителя (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Родитель успешно удален.
 /// - **404 Not Found**: Родитель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -148,8 +247,11 @@ async fn update_parent(
     responses(
         (status = 200, body = String, description = "Родитель успешно удален"),
         (status = 404, description = "Родитель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Parent"
 )]
 async fn delete_parent(
@@ -164,3 +266,45 @@ async fn delete_parent(
         Ok(Json("Parent not found".to_string()))
     }
 }
+
+/// Восстановление удаленного родителя
+///
+/// Этот эндпоинт снимает с родителя отметку об удалении. Связи с учениками остаются
+/// прежними.
+///
+/// ### Параметры:
+/// - `id`: ID родителя (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Родитель успешно восстановлен. Возвращает данные родителя.
+/// - **404 Not Found**: Удаленный родитель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/restore",
+    params(
+        ("id" = i32, Path, description = "ID восстанавливаемого родителя")
+    ),
+    responses(
+        (status = 200, body = Parent, description = "Родитель успешно восстановлен"),
+        (status = 404, description = "Удаленный родитель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "Parent"
+)]
+async fn restore_parent(
+    State(parent_service): State<ParentService>,
+    Path(parent_id): Path<i32>,
+) -> Result<Json<Parent>, AppError> {
+    info!("Restoring parent with ID {}", parent_id);
+    let parent = parent_service.restore(parent_id)?;
+    Ok(Json(parent))
+}
@@ -0,0 +1,279 @@
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use axum_login::AuthSession;
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
+    error::AppError,
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::parent_portal_service::ParentPortalService,
+    models::{
+        attendance::{AttendanceWithRelations, NewAbsenceExcuse},
+        lesson::LessonWithRelations,
+        pagination::{PaginatedResponse, PaginationParams},
+        student::StudentWithRelations,
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_children))
+        .routes(routes!(get_child))
+        .routes(routes!(get_child_lessons))
+        .routes(routes!(get_child_attendances))
+        .routes(routes!(excuse_child_absence))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    OpenApiRouter::new().merge(need_user)
+}
+
+/// Получение списка своих детей
+///
+/// Этот эндпоинт возвращает постраничный список учеников, родителем которых является
+/// текущий пользователь.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Пользователь должен быть связан с родителем
+///
+/// ### Ответы:
+/// - **200 OK**: Список детей успешно получен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Пользователь не связан с родителем.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/children",
+    params(PaginationParams),
+    responses(
+        (status = 200, body = PaginatedResponse<StudentWithRelations>, description = "Список детей успешно получен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Пользователь не связан с родителем"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ParentPortal"
+)]
+async fn get_children(
+    State(parent_portal_service): State<ParentPortalService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<StudentWithRelations>>, AppError> {
+    info!("Getting children of current user");
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let children = parent_portal_service.get_children(user, &pagination)?;
+    Ok(Json(children))
+}
+
+/// Получение своего ребенка по ID
+///
+/// Этот эндпоинт возвращает данные ученика вместе с его группой и родителем.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Пользователь должен быть связан с родителем ученика
+///
+/// ### Ответы:
+/// - **200 OK**: Ученик найден. Возвращает данные ученика.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Пользователь не связан с родителем.
+/// - **404 Not Found**: Ученик не найден или не является ребенком пользователя.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/children/{id}",
+    params(
+        ("id" = i32, Path, description = "ID ученика")
+    ),
+    responses(
+        (status = 200, body = StudentWithRelations, description = "Ученик найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Пользователь не связан с родителем"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ParentPortal"
+)]
+async fn get_child(
+    State(parent_portal_service): State<ParentPortalService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+) -> Result<Json<StudentWithRelations>, AppError> {
+    info!("Getting child with ID {}", student_id);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let child = parent_portal_service.get_child(user, student_id)?;
+    Ok(Json(child))
+}
+
+/// Получение предстоящих уроков ребенка
+///
+/// Этот эндпоинт возвращает постраничный список уроков группы ученика, которые еще
+/// не закончились. Если ученик не состоит в группе, список пуст.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Пользователь должен быть связан с родителем ученика
+///
+/// ### Ответы:
+/// - **200 OK**: Список уроков успешно получен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Пользователь не связан с родителем.
+/// - **404 Not Found**: Ученик не найден или не является ребенком пользователя.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/children/{id}/lessons",
+    params(
+        ("id" = i32, Path, description = "ID ученика"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, body = PaginatedResponse<LessonWithRelations>, description = "Список уроков успешно получен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Пользователь не связан с родителем"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ParentPortal"
+)]
+async fn get_child_lessons(
+    State(parent_portal_service): State<ParentPortalService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<LessonWithRelations>>, AppError> {
+    info!("Getting upcoming lessons of child with ID {}", student_id);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let lessons = parent_portal_service.get_upcoming_lessons(user, student_id, &pagination)?;
+    Ok(Json(lessons))
+}
+
+/// Получение истории посещений ребенка
+///
+/// Этот эндпоинт возвращает постраничный список посещений ученика вместе с причинами
+/// пропусков.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Пользователь должен быть связан с родителем ученика
+///
+/// ### Ответы:
+/// - **200 OK**: Список посещений успешно получен.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Пользователь не связан с родителем.
+/// - **404 Not Found**: Ученик не найден или не является ребенком пользователя.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/children/{id}/attendances",
+    params(
+        ("id" = i32, Path, description = "ID ученика"),
+        PaginationParams
+    ),
+    responses(
+        (status = 200, body = PaginatedResponse<AttendanceWithRelations>, description = "Список посещений успешно получен"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Пользователь не связан с родителем"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ParentPortal"
+)]
+async fn get_child_attendances(
+    State(parent_portal_service): State<ParentPortalService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<AttendanceWithRelations>>, AppError> {
+    info!("Getting attendances of child with ID {}", student_id);
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let attendances = parent_portal_service.get_attendances(user, student_id, &pagination)?;
+    Ok(Json(attendances))
+}
+
+/// Сообщение о пропуске урока
+///
+/// Этот эндпоинт позволяет родителю заранее сообщить, что ребенок пропустит урок.
+/// Посещение урока отмечается как пропуск с указанной причиной. Если посещение уже
+/// существует, причина в нем заменяется.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+///
+/// ### Входные данные:
+/// - `lesson_id`: ID урока группы ученика, который еще не начался (обязательное поле)
+/// - `skip_reason`: Причина пропуска (обязательное поле)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+/// - Пользователь должен быть связан с родителем ученика
+///
+/// ### Ответы:
+/// - **200 OK**: Пропуск успешно сохранен. Возвращает данные посещения.
+/// - **400 Bad Request**: Урок уже начался или ученик его не посещает.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Пользователь не связан с родителем.
+/// - **404 Not Found**: Ученик или урок не найден.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/children/{id}/absences",
+    params(
+        ("id" = i32, Path, description = "ID ученика")
+    ),
+    request_body = NewAbsenceExcuse,
+    responses(
+        (status = 200, body = AttendanceWithRelations, description = "Пропуск успешно сохранен"),
+        (status = 400, description = "Урок уже начался или ученик его не посещает"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Пользователь не связан с родителем"),
+        (status = 404, description = "Ученик или урок не найден"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "ParentPortal"
+)]
+async fn excuse_child_absence(
+    State(parent_portal_service): State<ParentPortalService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+    ValidatedJson(excuse): ValidatedJson<NewAbsenceExcuse>,
+) -> Result<Json<AttendanceWithRelations>, AppError> {
+    info!(
+        "Reporting absence of child with ID {} from lesson {}",
+        student_id, excuse.lesson_id
+    );
+    let user = authenticated_user(auth_session.user.as_ref())?;
+    let attendance = parent_portal_service.excuse_absence(user, student_id, excuse)?;
+    Ok(Json(attendance))
+}
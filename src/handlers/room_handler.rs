@@ -0,0 +1,307 @@
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::permission::require_role,
+    error::AppError,
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::room_service::RoomService,
+    models::{
+        pagination::{PaginatedResponse, PaginationParams},
+        room::{NewRoom, Room, RoomAvailabilityParams, RoomFilter, UpdateRoom},
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_rooms))
+        .routes(routes!(get_available_rooms))
+        .routes(routes!(get_room))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(create_room))
+        .routes(routes!(update_room, delete_room))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    OpenApiRouter::new().merge(need_user).merge(need_director)
+}
+
+/// Создание нового кабинета
+///
+/// Этот эндпоинт позволяет создать новый кабинет в базе данных.
+///
+/// ### Входные данные:
+/// - `name`: Название кабинета, уникальное (обязательное поле)
+/// - `capacity`: Количество мест (обязательное поле, больше нуля)
+/// - `building`: Здание
+/// - `equipment`: Список оборудования кабинета (необязательное поле, по умолчанию пустой)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **201 Created**: Кабинет успешно создан. Возвращает данные созданного кабинета.
+/// - **400 Bad Request**: Неверные входные данные (например, отсутствует обязательное поле).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = NewRoom,
+    responses(
+        (status = 201, body = Room, description = "Кабинет успешно создан"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Room"
+)]
+async fn create_room(
+    State(room_service): State<RoomService>,
+    ValidatedJson(new_room): ValidatedJson<NewRoom>,
+) -> Result<Json<Room>, AppError> {
+    info!("Creating new room");
+    let new_room = room_service.create(new_room)?;
+    Ok(Json(new_room))
+}
+
+/// Получение списка кабинетов
+///
+/// Этот эндпоинт возвращает постраничный список кабинетов с возможностью фильтрации.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `name`: Подстрока названия кабинета (необязательный)
+/// - `building`: Здание (необязательный)
+/// - `min_capacity`: Минимальное количество мест (необязательный)
+/// - `equipment`: Необходимое оборудование через запятую (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список кабинетов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, RoomFilter),
+    responses(
+        (status = 200, body = PaginatedResponse<Room>, description = "Список кабинетов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Room"
+)]
+async fn get_rooms(
+    State(room_service): State<RoomService>,
+    Query(pagination): Query<PaginationParams>,
+    Query(filter): Query<RoomFilter>,
+) -> Result<Json<PaginatedResponse<Room>>, AppError> {
+    info!("Getting rooms");
+    let rooms = room_service.get_all(filter, &pagination)?;
+    Ok(Json(rooms))
+}
+
+/// Получение свободных кабинетов
+///
+/// Этот эндпоинт возвращает кабинеты, в которых нет уроков в указанный промежуток времени.
+/// Дополнительно можно отфильтровать кабинеты так же, как в списке кабинетов.
+///
+/// ### Параметры:
+/// - `from`: Начало промежутка в формате RFC 3339 (обязательный)
+/// - `to`: Конец промежутка в формате RFC 3339 (обязательный)
+/// - `name`: Подстрока названия кабинета (необязательный)
+/// - `building`: Здание (необязательный)
+/// - `min_capacity`: Минимальное количество мест (необязательный)
+/// - `equipment`: Необходимое оборудование через запятую (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список свободных кабинетов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/available",
+    params(RoomAvailabilityParams, RoomFilter),
+    responses(
+        (status = 200, body = Vec<Room>, description = "Список свободных кабинетов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Room"
+)]
+async fn get_available_rooms(
+    State(room_service): State<RoomService>,
+    Query(window): Query<RoomAvailabilityParams>,
+    Query(filter): Query<RoomFilter>,
+) -> Result<Json<Vec<Room>>, AppError> {
+    info!("Getting available rooms");
+    let rooms = room_service.get_available(filter, window)?;
+    Ok(Json(rooms))
+}
+
+/// Получение кабинета по ID
+///
+/// Этот эндпоинт позволяет получить данные конкретного кабинета по его идентификатору.
+///
+/// ### Параметры:
+/// - `id`: ID кабинета (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Данные кабинета успешно получены.
+/// - **404 Not Found**: Кабинет с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID запрашиваемого кабинета")
+    ),
+    responses(
+        (status = 200, body = Room, description = "Данные кабинета успешно получены"),
+        (status = 404, description = "Кабинет не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Room"
+)]
+async fn get_room(
+    State(room_service): State<RoomService>,
+    Path(room_id): Path<i32>,
+) -> Result<Json<Room>, AppError> {
+    info!("Getting room with ID {}", room_id);
+    let room = room_service.get(room_id)?;
+    Ok(Json(room))
+}
+
+/// Обновление существующего кабинета
+///
+/// Этот эндпоинт позволяет обновить данные кабинета по его идентификатору.
+///
+/// ### Параметры:
+/// - `id`: ID кабинета (обязательный путь)
+///
+/// ### Входные данные:
+/// - `name`: Новое название кабинета (необязательное поле)
+/// - `capacity`: Новое количество мест (необязательное поле)
+/// - `building`: Здание (необязательное поле)
+/// - `equipment`: Новый список оборудования, заменяет старый (необязательное поле)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Данные кабинета успешно обновлены.
+/// - **404 Not Found**: Кабинет с указанным ID не найден.
+/// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    method(put, patch),
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID кабинета который требуется обновить")
+    ),
+    request_body = UpdateRoom,
+    responses(
+        (status = 200, body = Room, description = "Данные кабинета успешно обновлены"),
+        (status = 404, description = "Кабинет не найден"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Room"
+)]
+async fn update_room(
+    State(room_service): State<RoomService>,
+    Path(room_id): Path<i32>,
+    ValidatedJson(update_room): ValidatedJson<UpdateRoom>,
+) -> Result<Json<Room>, AppError> {
+    info!("Updating room with ID {}", room_id);
+    let updated_room = room_service.update(room_id, update_room)?;
+    Ok(Json(updated_room))
+}
+
+/// Удаление кабинета
+///
+/// Этот эндпоинт позволяет удалить существующий кабинет по его идентификатору. У уроков,
+/// которые проходили в этом кабинете, кабинет сбрасывается.
+///
+/// ### Параметры:
+/// - `id`: ID кабинета (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Кабинет успешно удален.
+/// - **404 Not Found**: Кабинет с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    delete,
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID кабинета который требуется удалить")
+    ),
+    responses(
+        (status = 200, body = String, description = "Кабинет успешно удален"),
+        (status = 404, description = "Кабинет не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Room"
+)]
+async fn delete_room(
+    State(room_service): State<RoomService>,
+    Path(room_id): Path<i32>,
+) -> Result<Json<String>, AppError> {
+    info!("Deleting room with ID {}", room_id);
+    let deleted = room_service.delete(room_id)?;
+    if deleted {
+        Ok(Json("Successfully deleted".to_string()))
+    } else {
+        Ok(Json("Room not found".to_string()))
+    }
+}
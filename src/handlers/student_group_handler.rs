@@ -1,33 +1,69 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Multipart, State},
+    middleware::from_fn_with_state,
 };
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{backend::AuthBackend, permission::require_role},
     error::AppError,
-    logic::services::{lesson_service::LessonService, student_group_service::StudentGroupService},
+    handlers::{
+        document_handler::{get_owner_documents, upload_owner_document, upload_router},
+        utils::{
+            deleted_params::DeletedParams,
+            extract::{Path, Query},
+            validated_json::ValidatedJson,
+        },
+    },
+    logic::services::{
+        document_service::DocumentService, lesson_service::LessonService,
+        student_group_service::StudentGroupService, waitlist_service::WaitlistService,
+    },
     models::{
-        lesson::{Lesson, LessonWithRelations, NewLesson},
-        student_group::{NewStudentGroup, StudentGroupWithRelations, UpdateStudentGroup},
+        document::{Document, DocumentFileForm, DocumentFilter, DocumentOwner},
+        lesson::{Lesson, LessonWithRelations, RawNewLesson},
+        pagination::{PaginatedResponse, PaginationParams},
+        problem_details::ProblemDetails,
+        student_group::{NewStudentGroup, RawUpdateStudentGroup, StudentGroupWithRelations},
+        user::PermissionRole,
+        waitlist::{RawNewWaitlistEntry, UpdateWaitlistEntry, WaitlistEntryWithStudent},
     },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new()
-        .routes(routes!(
-            create_student_group,
-            get_student_group,
-            update_student_group,
-            delete_student_group
-        ))
-        .routes(routes!(
-            get_lessons_for_student_group,
-            create_lesson_for_student_group
-        ));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_student_groups))
+        .routes(routes!(get_student_group))
+        .routes(routes!(get_lessons_for_student_group))
+        .routes(routes!(get_waitlist))
+        .routes(routes!(get_student_group_documents))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(create_lesson_for_student_group))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    let need_teacher_upload = upload_router(
+        OpenApiRouter::new().routes(routes!(upload_student_group_document)),
+        PermissionRole::Teacher,
+    );
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(create_student_group))
+        .routes(routes!(update_student_group, delete_student_group))
+        .routes(routes!(add_to_waitlist))
+        .routes(routes!(update_waitlist_entry, delete_waitlist_entry))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(restore_student_group))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new()
+        .merge(need_user)
+        .merge(need_teacher)
+        .merge(need_teacher_upload)
+        .merge(need_director)
+        .merge(need_admin)
 }
 
 /// Создание новой учебной группы
@@ -36,12 +72,19 @@ pub fn router() -> OpenApiRouter<AppState> {
 ///
 /// ### Входные данные:
 /// - `direction`: Направление обучения (необязательное поле)
-/// - `free_spots`: Количество свободных мест (обязательное поле)
+/// - `free_spots`: Количество свободных мест, не меньше нуля (обязательное поле). Место
+///   занимается при зачислении ученика в группу и освобождается при его переводе или удалении
 /// - `teacher_id`: ID преподавателя (необязательное поле)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **201 Created**: Группа успешно создана. Возвращает данные созданной группы с преподавателем.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствует обязательное поле).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -50,13 +93,17 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 201, body = StudentGroupWithRelations, description = "Группа успешно создана"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "StudentGroup"
 )]
 async fn create_student_group(
     State(student_group_service): State<StudentGroupService>,
-    Json(new_student_group): Json<NewStudentGroup>,
+    ValidatedJson(new_student_group): ValidatedJson<NewStudentGroup>,
 ) -> Result<Json<StudentGroupWithRelations>, AppError> {
     info!("Creating new student group");
     let new_student_group = student_group_service.create(new_student_group)?;
@@ -72,13 +119,25 @@ async fn create_student_group(
 ///
 /// ### Входные данные:
 /// - `topic`: Название предмета (обязательное поле)
-/// - `scheduled_at`: Дата проведения урока (обязательное поле)
+/// - `starts_at`: Время начала урока в формате RFC 3339 (обязательное поле, если не передан
+/// `scheduled_at`)
+/// - `ends_at`: Время окончания урока в формате RFC 3339 (необязательное поле)
+/// - `scheduled_at`: Устаревшее поле с датой урока (формат: YYYY-MM-DD)
+/// - `room_id`: ID кабинета. Все студенты группы должны помещаться в кабинет
 /// -  Поле `student_group_id` будет проигнорировано, даже если передано - в этом эндпоинте всегда
 /// используется айди группы указанный в пути.
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
 /// ### Ответы:
 /// - **201 Created**: Урок успешно создан. Возвращает данные созданного урока.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **409 Conflict**: В это время у группы или ее преподавателя уже есть урок или кабинет
+/// занят.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -86,18 +145,23 @@ async fn create_student_group(
     params(
         ("id" = i32, Path, description = "ID группы учеников для которой создаем урок")
     ),
-    request_body = NewLesson,
+    request_body = RawNewLesson,
     responses(
         (status = 201, body = LessonWithRelations, description = "Урок успешно создан"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 409, body = ProblemDetails, description = "Урок пересекается с другими уроками"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "StudentGroup"
 )]
 async fn create_lesson_for_student_group(
     State(lesson_service): State<LessonService>,
     Path(student_group_id): Path<i32>,
-    Json(mut new_lesson): Json<NewLesson>,
+    ValidatedJson(mut new_lesson): ValidatedJson<RawNewLesson>,
 ) -> Result<Json<LessonWithRelations>, AppError> {
     info!("Creating new lesson for student group");
     new_lesson.student_group_id = Some(student_group_id);
@@ -105,36 +169,95 @@ async fn create_lesson_for_student_group(
     Ok(Json(new_lesson))
 }
 
+/// Получение списка учебных групп
+///
+/// Этот эндпоинт возвращает постраничный список учебных групп. Для каждой группы
+/// возвращается текущее количество учеников (`enrolled`) и общее количество мест (`capacity`).
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список учебных групп успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, DeletedParams),
+    responses(
+        (status = 200, body = PaginatedResponse<StudentGroupWithRelations>, description = "Список учебных групп успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "StudentGroup"
+)]
+async fn get_student_groups(
+    State(student_group_service): State<StudentGroupService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(deleted): Query<DeletedParams>,
+) -> Result<Json<PaginatedResponse<StudentGroupWithRelations>>, AppError> {
+    info!("Getting student groups");
+    let student_groups =
+        student_group_service.get_all(&pagination, deleted.include_deleted(&auth_session)?)?;
+    Ok(Json(student_groups))
+}
+
 /// Получение учебной группы по ID
 ///
-/// Этот эндпоинт позволяет получить данные конкретной учебной группы по ее идентификатору.
+/// Этот эндпоинт позволяет получить данные конкретной учебной группы по ее идентификатору,
+/// включая текущее количество учеников (`enrolled`) и общее количество мест (`capacity`).
 ///
 /// ### Параметры:
 /// - `id`: ID группы (обязательный путь)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные группы успешно получены.
 /// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}",
     params(
-        ("id" = i32, Path, description = "ID запрашиваемой группы учеников")
+        ("id" = i32, Path, description = "ID запрашиваемой группы учеников"),
+        DeletedParams
     ),
     responses(
         (status = 200, body = StudentGroupWithRelations, description = "Данные группы успешно получены"),
         (status = 404, description = "Группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["User"])),
     tag = "StudentGroup"
 )]
 async fn get_student_group(
     State(student_group_service): State<StudentGroupService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(student_group_id): Path<i32>,
+    Query(deleted): Query<DeletedParams>,
 ) -> Result<Json<StudentGroupWithRelations>, AppError> {
     info!("Getting student group");
-    let student_group = student_group_service.get(student_group_id)?;
+    let student_group =
+        student_group_service.get(student_group_id, deleted.include_deleted(&auth_session)?)?;
     Ok(Json(student_group))
 }
 
@@ -145,9 +268,14 @@ async fn get_student_group(
 /// ### Параметры:
 /// - `id`: ID группы (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
 /// ### Ответы:
 /// - **200 OK**: Список уроков успешно получен.
 /// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
@@ -158,8 +286,11 @@ async fn get_student_group(
     responses(
         (status = 200, body = Vec<Lesson>, description = "Список уроков успешно получен"),
         (status = 404, description = "Группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["User"])),
     tag = "StudentGroup"
 )]
 async fn get_lessons_for_student_group(
@@ -171,42 +302,174 @@ async fn get_lessons_for_student_group(
     Ok(Json(lessons))
 }
 
+/// Загрузка документа для учебной группы
+///
+/// Этот эндпоинт позволяет прикрепить документ к учебной группе, например учебные материалы курса.
+///
+/// Принимаются файлы png, jpeg, webp и pdf не больше `DOCUMENT_MAX_SIZE_MB` мегабайт. Тип
+/// файла определяется по его содержимому. Имя файла очищается от пути и недопустимых
+/// символов, а его расширение приводится в соответствие с типом файла.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+///
+/// ### Входные данные (multipart/form-data):
+/// - `document`: Файл документа (обязательное поле)
+/// - `category`: Вид документа: `certificate`, `medical_check`, `contract` или `other`
+///   (необязательное поле, по умолчанию `other`)
+/// - `description`: Описание документа (необязательное поле)
+/// - `valid_until`: Последний день действия документа в формате `YYYY-MM-DD` (необязательное
+///   поле, без него документ считается бессрочным)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может загружать документы только для групп, которые он ведет
+///
+/// ### Ответы:
+/// - **201 Created**: Документ успешно загружен. Возвращает данные документа.
+/// - **400 Bad Request**: Неверные входные данные, отсутствует файл или тип файла не
+///   поддерживается.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **409 Conflict**: К группе уже прикреплен документ с таким же содержимым.
+/// - **413 Payload Too Large**: Файл больше допустимого размера.
+/// - **422 Unprocessable Entity**: Описание пустое или слишком длинное.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID группы к которой загружаем документ")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = DocumentFileForm,
+        description = "Загружаемый документ"
+    ),
+    responses(
+        (status = 201, body = Document, description = "Документ успешно загружен"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Группа не найдена"),
+        (status = 409, description = "Такой документ уже загружен"),
+        (status = 413, description = "Файл слишком большой"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "StudentGroup"
+)]
+async fn upload_student_group_document(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_group_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<Json<Document>, AppError> {
+    let owner = DocumentOwner::StudentGroup(student_group_id);
+    upload_owner_document(document_service, auth_session, owner, multipart).await
+}
+
+/// Получение всех документов учебной группы
+///
+/// Этот эндпоинт позволяет получить список всех документов, прикрепленных к учебной группе.
+/// Срок действия документов сравнивается с текущей датой в часовом поясе школы.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+/// - `category`: Вид документа (необязательный)
+/// - `expired`: `true` — только документы с истекшим сроком действия, `false` — только
+///   действующие и бессрочные (необязательный)
+/// - `expires_within_days`: Только действующие документы, срок действия которых истекает в
+///   ближайшие N дней (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список документов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID группы у которой запрашиваются документы"),
+        DocumentFilter
+    ),
+    responses(
+        (status = 200, body = Vec<Document>, description = "Список документов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 404, description = "Группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "StudentGroup"
+)]
+async fn get_student_group_documents(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_group_id): Path<i32>,
+    Query(filter): Query<DocumentFilter>,
+) -> Result<Json<Vec<Document>>, AppError> {
+    let owner = DocumentOwner::StudentGroup(student_group_id);
+    get_owner_documents(document_service, auth_session, owner, filter)
+}
+
 /// Обновление существующей учебной группы
 ///
-/// Этот эндпоинт позволяет обновить данные учебной группы по ее идентификатору.
+/// Этот эндпоинт позволяет обновить данные учебной группы по ее идентификатору. Меняются
+/// только переданные поля, запрос доступен методами `PUT` и `PATCH`.
 ///
 /// ### Параметры:
 /// - `id`: ID группы (обязательный путь)
 ///
 /// ### Входные данные:
 /// - `direction`: Новое направление обучения (необязательное поле)
-/// - `free_spots`: Новое количество свободных мест (необязательное поле)
-/// - `teacher_id`: Новый ID преподавателя (необязательное поле)
+/// - `free_spots`: Новое количество свободных мест, не меньше нуля (необязательное поле).
+///   Освободившиеся места сразу занимают ученики из очереди группы
+/// - `teacher_id`: Новый ID преподавателя (необязательное поле, `null` отвязывает преподавателя)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные группы успешно обновлены.
 /// - **404 Not Found**: Группа с указанным ID не найдена.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID группы учеников которую требуется обновить")
     ),
-    request_body = UpdateStudentGroup,
+    request_body = RawUpdateStudentGroup,
     responses(
         (status = 200, body = StudentGroupWithRelations, description = "Данные группы успешно обновлены"),
         (status = 404, description = "Группа не найдена"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "StudentGroup"
 )]
 async fn update_student_group(
     State(student_group_service): State<StudentGroupService>,
     Path(student_group_id): Path<i32>,
-    Json(update_student_group): Json<UpdateStudentGroup>,
+    ValidatedJson(update_student_group): ValidatedJson<RawUpdateStudentGroup>,
 ) -> Result<Json<StudentGroupWithRelations>, AppError> {
     info!("Updating student group");
     let updated_student_group =
@@ -218,12 +481,21 @@ async fn update_student_group(
 ///
 /// Этот эндпоинт позволяет удалить существующую учебную группу по ее идентификатору.
 ///
+/// Группа помечается удаленной: ее ученики отчисляются, а очередь на зачисление очищается.
+/// Уроки группы и история зачислений сохраняются. Администратор может восстановить группу,
+/// пока не истек срок хранения удаленных записей.
+///
 /// ### Параметры:
 /// - `id`: ID группы (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Группа успешно удалена.
 /// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -234,8 +506,11 @@ async fn update_student_group(
     responses(
         (status = 200, body = String, description = "Группа успешно удалена"),
         (status = 404, description = "Группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "StudentGroup"
 )]
 async fn delete_student_group(
@@ -250,3 +525,238 @@ async fn delete_student_group(
         Ok(Json("Student group not found".to_string()))
     }
 }
+
+/// Восстановление удаленной учебной группы
+///
+/// Этот эндпоинт снимает с учебной группы отметку об удалении. Группа восстанавливается без
+/// учеников.
+///
+/// ### Параметры:
+/// - `id`: ID учебной группы (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Группа успешно восстановлена. Возвращает данные учебной группы.
+/// - **404 Not Found**: Удаленная группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/restore",
+    params(
+        ("id" = i32, Path, description = "ID восстанавливаемого учебной группы")
+    ),
+    responses(
+        (status = 200, body = StudentGroupWithRelations, description = "Группа успешно восстановлена"),
+        (status = 404, description = "Удаленная группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "StudentGroup"
+)]
+async fn restore_student_group(
+    State(student_group_service): State<StudentGroupService>,
+    Path(student_group_id): Path<i32>,
+) -> Result<Json<StudentGroupWithRelations>, AppError> {
+    info!("Restoring student group with ID {}", student_group_id);
+    let student_group = student_group_service.restore(student_group_id)?;
+    Ok(Json(student_group))
+}
+
+/// Получение очереди на зачисление в группу
+///
+/// Этот эндпоинт возвращает учеников, ожидающих места в группе, в порядке очереди.
+/// Когда в группе освобождается место, в нее автоматически зачисляется первый ученик
+/// из очереди.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Очередь успешно получена.
+/// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/waitlist",
+    params(
+        ("id" = i32, Path, description = "ID группы, очередь которой запрашивается")
+    ),
+    responses(
+        (status = 200, body = Vec<WaitlistEntryWithStudent>, description = "Очередь успешно получена"),
+        (status = 404, description = "Группа не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "StudentGroup"
+)]
+async fn get_waitlist(
+    State(waitlist_service): State<WaitlistService>,
+    Path(student_group_id): Path<i32>,
+) -> Result<Json<Vec<WaitlistEntryWithStudent>>, AppError> {
+    info!("Getting waitlist for student group");
+    let entries = waitlist_service.get_by_group_id(student_group_id)?;
+    Ok(Json(entries))
+}
+
+/// Постановка ученика в очередь на зачисление
+///
+/// Этот эндпоинт ставит ученика в конец очереди заполненной группы.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+///
+/// ### Входные данные:
+/// - `student_id`: ID ученика (обязательное поле)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **201 Created**: Ученик поставлен в очередь. Возвращает запись очереди с позицией.
+/// - **400 Bad Request**: В группе есть свободные места, ученик уже в группе или уже в очереди.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **404 Not Found**: Группа или ученик не найдены.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/waitlist",
+    params(
+        ("id" = i32, Path, description = "ID группы, в очередь которой ставится ученик")
+    ),
+    request_body = RawNewWaitlistEntry,
+    responses(
+        (status = 201, body = WaitlistEntryWithStudent, description = "Ученик поставлен в очередь"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 404, description = "Группа или ученик не найдены"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "StudentGroup"
+)]
+async fn add_to_waitlist(
+    State(waitlist_service): State<WaitlistService>,
+    Path(student_group_id): Path<i32>,
+    ValidatedJson(new_entry): ValidatedJson<RawNewWaitlistEntry>,
+) -> Result<Json<WaitlistEntryWithStudent>, AppError> {
+    info!("Adding student to waitlist of student group");
+    let entry = waitlist_service.add(student_group_id, new_entry)?;
+    Ok(Json(entry))
+}
+
+/// Изменение позиции в очереди
+///
+/// Этот эндпоинт переносит запись на новую позицию в очереди группы. Остальные записи
+/// сдвигаются, порядок между ними не меняется.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+/// - `entry_id`: ID записи очереди (обязательный путь)
+///
+/// ### Входные данные:
+/// - `position`: Новая позиция, начиная с 1 (обязательное поле)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Позиция изменена. Возвращает очередь группы целиком.
+/// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **404 Not Found**: Запись не найдена в очереди этой группы.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    method(put, patch),
+    path = "/{id}/waitlist/{entry_id}",
+    params(
+        ("id" = i32, Path, description = "ID группы"),
+        ("entry_id" = i32, Path, description = "ID записи очереди, которую требуется переместить")
+    ),
+    request_body = UpdateWaitlistEntry,
+    responses(
+        (status = 200, body = Vec<WaitlistEntryWithStudent>, description = "Позиция изменена"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 404, description = "Запись не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "StudentGroup"
+)]
+async fn update_waitlist_entry(
+    State(waitlist_service): State<WaitlistService>,
+    Path((student_group_id, entry_id)): Path<(i32, i32)>,
+    ValidatedJson(update_entry): ValidatedJson<UpdateWaitlistEntry>,
+) -> Result<Json<Vec<WaitlistEntryWithStudent>>, AppError> {
+    info!("Moving waitlist entry with ID {}", entry_id);
+    let entries = waitlist_service.update(student_group_id, entry_id, update_entry)?;
+    Ok(Json(entries))
+}
+
+/// Удаление ученика из очереди
+///
+/// Этот эндпоинт удаляет запись из очереди группы. Следующие записи сдвигаются вперед.
+///
+/// ### Параметры:
+/// - `id`: ID группы (обязательный путь)
+/// - `entry_id`: ID записи очереди (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Запись успешно удалена.
+/// - **404 Not Found**: Запись не найдена в очереди этой группы.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    delete,
+    path = "/{id}/waitlist/{entry_id}",
+    params(
+        ("id" = i32, Path, description = "ID группы"),
+        ("entry_id" = i32, Path, description = "ID записи очереди, которую требуется удалить")
+    ),
+    responses(
+        (status = 200, body = String, description = "Запись успешно удалена"),
+        (status = 404, description = "Запись не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "StudentGroup"
+)]
+async fn delete_waitlist_entry(
+    State(waitlist_service): State<WaitlistService>,
+    Path((student_group_id, entry_id)): Path<(i32, i32)>,
+) -> Result<Json<String>, AppError> {
+    info!("Deleting waitlist entry with ID {}", entry_id);
+    let deleted = waitlist_service.delete(student_group_id, entry_id)?;
+    if deleted {
+        Ok(Json("Successfully deleted".to_string()))
+    } else {
+        Ok(Json("Waitlist entry not found".to_string()))
+    }
+}
@@ -1,25 +1,57 @@
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Multipart, State},
+    middleware::from_fn_with_state,
 };
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{backend::AuthBackend, permission::require_role},
     error::AppError,
-    logic::services::student_service::StudentService,
-    models::student::{NewStudent, StudentWithRelations, UpdateStudent},
+    handlers::{
+        document_handler::{get_owner_documents, upload_owner_document, upload_router},
+        utils::{
+            deleted_params::DeletedParams,
+            extract::{Path, Query},
+            validated_json::ValidatedJson,
+        },
+    },
+    logic::services::{document_service::DocumentService, student_service::StudentService},
+    models::{
+        document::{Document, DocumentFileForm, DocumentFilter, DocumentOwner},
+        enrollment::EnrollmentWithGroup,
+        pagination::{PaginatedResponse, PaginationParams},
+        student::{NewStudent, RawUpdateStudent, StudentFilter, StudentWithRelations},
+        user::PermissionRole,
+    },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new().routes(routes!(
-        create_student,
-        get_student,
-        update_student,
-        delete_student
-    ));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(get_students))
+        .routes(routes!(get_student))
+        .routes(routes!(get_student_enrollments))
+        .routes(routes!(get_student_documents))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(create_student))
+        .routes(routes!(update_student, delete_student))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    let need_teacher_upload = upload_router(
+        OpenApiRouter::new().routes(routes!(upload_student_document)),
+        PermissionRole::Teacher,
+    );
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(restore_student))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new()
+        .merge(need_teacher)
+        .merge(need_teacher_upload)
+        .merge(need_director)
+        .merge(need_admin)
 }
 
 /// Создание нового ученика
@@ -30,11 +62,20 @@ pub fn router() -> OpenApiRouter<AppState> {
 /// - `name`: Имя ученика (обязательное поле)
 /// - `birth_date`: Дата рождения (обязательное поле, формат: YYYY-MM-DD)
 /// - `parent_id`: ID родителя (необязательное поле)
-/// - `student_group_id`: ID учебной группы (необязательное поле)
+/// - `student_group_id`: ID учебной группы (необязательное поле). Ученик занимает одно
+///   свободное место в группе
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
 ///
 /// ### Ответы:
 /// - **201 Created**: Ученик успешно создан. Возвращает данные ученика с родителем и группой.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствуют обязательные поля).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **404 Not Found**: Группа с указанным ID не найдена.
+/// - **409 Conflict**: В группе нет свободных мест.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -43,55 +84,287 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 201, body = StudentWithRelations, description = "Ученик успешно создан"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 404, description = "Группа не найдена"),
+        (status = 409, description = "В группе нет свободных мест"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Student"
 )]
 async fn create_student(
     State(student_service): State<StudentService>,
-    Json(new_student): Json<NewStudent>,
+    ValidatedJson(new_student): ValidatedJson<NewStudent>,
 ) -> Result<Json<StudentWithRelations>, AppError> {
     info!("Creating new student");
     let new_student = student_service.create(new_student)?;
     Ok(Json(new_student))
 }
 
+/// Получение списка учеников
+///
+/// Этот эндпоинт возвращает постраничный список учеников с возможностью фильтрации.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+/// - `student_group_id`: ID учебной группы (необязательный)
+/// - `parent_id`: ID родителя (необязательный)
+/// - `name`: Подстрока имени ученика без учета регистра (необязательный)
+/// - `birth_date_from`: Дата рождения не раньше указанной, формат: YYYY-MM-DD (необязательный)
+/// - `birth_date_to`: Дата рождения не позже указанной, формат: YYYY-MM-DD (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: Список учеников успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, StudentFilter, DeletedParams),
+    responses(
+        (status = 200, body = PaginatedResponse<StudentWithRelations>, description = "Список учеников успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Student"
+)]
+async fn get_students(
+    State(student_service): State<StudentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(deleted): Query<DeletedParams>,
+    Query(filter): Query<StudentFilter>,
+) -> Result<Json<PaginatedResponse<StudentWithRelations>>, AppError> {
+    info!("Getting students");
+    let students =
+        student_service.get_all(filter, &pagination, deleted.include_deleted(&auth_session)?)?;
+    Ok(Json(students))
+}
+
 /// Получение ученика по ID
 ///
 /// Этот эндпоинт позволяет получить данные конкретного ученика по его идентификатору.
 ///
 /// ### Параметры:
 /// - `id`: ID ученика (обязательный путь)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные ученика успешно получены.
 /// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}",
     params(
-        ("id" = i32, Path, description = "ID запрашиваемого ученика")
+        ("id" = i32, Path, description = "ID запрашиваемого ученика"),
+        DeletedParams
     ),
     responses(
         (status = 200, body = StudentWithRelations, description = "Данные ученика успешно получены"),
         (status = 404, description = "Ученик не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Student"
 )]
 async fn get_student(
     State(student_service): State<StudentService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(student_id): Path<i32>,
+    Query(deleted): Query<DeletedParams>,
 ) -> Result<Json<StudentWithRelations>, AppError> {
     info!("Getting student");
-    let student = student_service.get(student_id)?;
+    let student = student_service.get(student_id, deleted.include_deleted(&auth_session)?)?;
     Ok(Json(student))
 }
 
+/// Получение истории зачислений ученика
+///
+/// Этот эндпоинт возвращает все группы, в которых состоял ученик, с датами зачисления
+/// и отчисления, начиная с самых ранних. У текущей группы дата отчисления отсутствует.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+///
+/// ### Ответы:
+/// - **200 OK**: История зачислений успешно получена.
+/// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/enrollments",
+    params(
+        ("id" = i32, Path, description = "ID ученика")
+    ),
+    responses(
+        (status = 200, body = Vec<EnrollmentWithGroup>, description = "История зачислений успешно получена"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Student"
+)]
+async fn get_student_enrollments(
+    State(student_service): State<StudentService>,
+    Path(student_id): Path<i32>,
+) -> Result<Json<Vec<EnrollmentWithGroup>>, AppError> {
+    info!("Getting student enrollments");
+    let enrollments = student_service.get_enrollments(student_id)?;
+    Ok(Json(enrollments))
+}
+
+/// Загрузка документа для ученика
+///
+/// Этот эндпоинт позволяет прикрепить документ к ученику, например медицинскую справку.
+///
+/// Принимаются файлы png, jpeg, webp и pdf не больше `DOCUMENT_MAX_SIZE_MB` мегабайт. Тип
+/// файла определяется по его содержимому. Имя файла очищается от пути и недопустимых
+/// символов, а его расширение приводится в соответствие с типом файла.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+///
+/// ### Входные данные (multipart/form-data):
+/// - `document`: Файл документа (обязательное поле)
+/// - `category`: Вид документа: `certificate`, `medical_check`, `contract` или `other`
+///   (необязательное поле, по умолчанию `other`)
+/// - `description`: Описание документа (необязательное поле)
+/// - `valid_until`: Последний день действия документа в формате `YYYY-MM-DD` (необязательное
+///   поле, без него документ считается бессрочным)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может загружать документы только для учеников групп, которые он ведет
+///
+/// ### Ответы:
+/// - **201 Created**: Документ успешно загружен. Возвращает данные документа.
+/// - **400 Bad Request**: Неверные входные данные, отсутствует файл или тип файла не
+///   поддерживается.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **409 Conflict**: К ученику уже прикреплен документ с таким же содержимым.
+/// - **413 Payload Too Large**: Файл больше допустимого размера.
+/// - **422 Unprocessable Entity**: Описание пустое или слишком длинное.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID ученика к которому загружаем документ")
+    ),
+    request_body(
+        content_type = "multipart/form-data",
+        content = DocumentFileForm,
+        description = "Загружаемый документ"
+    ),
+    responses(
+        (status = 201, body = Document, description = "Документ успешно загружен"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 409, description = "Такой документ уже загружен"),
+        (status = 413, description = "Файл слишком большой"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Student"
+)]
+async fn upload_student_document(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+    multipart: Multipart,
+) -> Result<Json<Document>, AppError> {
+    let owner = DocumentOwner::Student(student_id);
+    upload_owner_document(document_service, auth_session, owner, multipart).await
+}
+
+/// Получение всех документов ученика
+///
+/// Этот эндпоинт позволяет получить список всех документов, прикрепленных к ученику.
+/// Срок действия документов сравнивается с текущей датой в часовом поясе школы.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+/// - `category`: Вид документа (необязательный)
+/// - `expired`: `true` — только документы с истекшим сроком действия, `false` — только
+///   действующие и бессрочные (необязательный)
+/// - `expires_within_days`: Только действующие документы, срок действия которых истекает в
+///   ближайшие N дней (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может получать документы только учеников групп, которые он ведет
+///
+/// ### Ответы:
+/// - **200 OK**: Список документов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}/documents",
+    params(
+        ("id" = i32, Path, description = "ID ученика у которого запрашиваются документы"),
+        DocumentFilter
+    ),
+    responses(
+        (status = 200, body = Vec<Document>, description = "Список документов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 404, description = "Ученик не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Teacher"])),
+    tag = "Student"
+)]
+async fn get_student_documents(
+    State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(student_id): Path<i32>,
+    Query(filter): Query<DocumentFilter>,
+) -> Result<Json<Vec<Document>>, AppError> {
+    let owner = DocumentOwner::Student(student_id);
+    get_owner_documents(document_service, auth_session, owner, filter)
+}
+
 /// Обновление существующего ученика
 ///
-/// Этот эндпоинт позволяет обновить данные ученика по его идентификатору.
+/// Этот эндпоинт позволяет обновить данные ученика по его идентификатору. Меняются только
+/// переданные поля, запрос доступен методами `PUT` и `PATCH`.
 ///
 /// ### Параметры:
 /// - `id`: ID ученика (обязательный путь)
@@ -99,33 +372,48 @@ async fn get_student(
 /// ### Входные данные:
 /// - `name`: Новое имя ученика (необязательное поле)
 /// - `birth_date`: Новая дата рождения (необязательное поле, формат: YYYY-MM-DD)
-/// - `parent_id`: Новый ID родителя (необязательное поле)
-/// - `student_group_id`: Новый ID учебной группы (необязательное поле)
+/// - `parent_id`: Новый ID родителя (необязательное поле, `null` отвязывает родителя)
+/// - `student_group_id`: Новый ID учебной группы (необязательное поле, `null` отчисляет
+///   ученика из группы). При переводе ученик занимает место в новой группе и освобождает
+///   место в старой, на которое зачисляется первый ученик из ее очереди. Зачисление
+///   в старую группу закрывается текущей датой и остается в истории
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные ученика успешно обновлены.
-/// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **404 Not Found**: Ученик или группа с указанным ID не найдены.
+/// - **409 Conflict**: В новой группе нет свободных мест.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID ученика которого требуется обновить")
     ),
-    request_body = UpdateStudent,
+    request_body = RawUpdateStudent,
     responses(
         (status = 200, body = StudentWithRelations, description = "Данные ученика успешно обновлены"),
-        (status = 404, description = "Ученик не найден"),
+        (status = 404, description = "Ученик или группа не найдены"),
+        (status = 409, description = "В группе нет свободных мест"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Student"
 )]
 async fn update_student(
     State(student_service): State<StudentService>,
     Path(student_id): Path<i32>,
-    Json(update_student): Json<UpdateStudent>,
+    ValidatedJson(update_student): ValidatedJson<RawUpdateStudent>,
 ) -> Result<Json<StudentWithRelations>, AppError> {
     info!("Updating student");
     let updated_student = student_service.update(student_id, update_student)?;
@@ -134,14 +422,24 @@ async fn update_student(
 
 /// Удаление ученика
 ///
-/// Этот эндпоинт позволяет удалить существующего ученика по его идентификатору.
+/// Этот эндпоинт позволяет удалить существующего ученика по его идентификатору. Место
+/// ученика в группе освобождается и занимается первым учеником из очереди группы.
+///
+/// Ученик помечается удаленным и отчисляется из группы, история зачислений и посещаемость
+/// сохраняются. Администратор может восстановить ученика, пока не истек срок хранения
+/// удаленных записей.
 ///
 /// ### Параметры:
 /// - `id`: ID ученика (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Ученик успешно удален.
 /// - **404 Not Found**: Ученик с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -152,8 +450,11 @@ async fn update_student(
     responses(
         (status = 200, body = String, description = "Ученик успешно удален"),
         (status = 404, description = "Ученик не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Student"
 )]
 async fn delete_student(
@@ -168,3 +469,45 @@ async fn delete_student(
         Ok(Json("Student not found".to_string()))
     }
 }
+
+/// Восстановление удаленного ученика
+///
+/// Этот эндпоинт снимает с ученика отметку об удалении. Ученик восстанавливается без
+/// группы, зачислить его можно обновлением `student_group_id`.
+///
+/// ### Параметры:
+/// - `id`: ID ученика (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Ученик успешно восстановлен. Возвращает данные ученика.
+/// - **404 Not Found**: Удаленный ученик с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/restore",
+    params(
+        ("id" = i32, Path, description = "ID восстанавливаемого ученика")
+    ),
+    responses(
+        (status = 200, body = StudentWithRelations, description = "Ученик успешно восстановлен"),
+        (status = 404, description = "Удаленный ученик не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "Student"
+)]
+async fn restore_student(
+    State(student_service): State<StudentService>,
+    Path(student_id): Path<i32>,
+) -> Result<Json<StudentWithRelations>, AppError> {
+    info!("Restoring student with ID {}", student_id);
+    let student = student_service.restore(student_id)?;
+    Ok(Json(student))
+}
@@ -1,35 +1,60 @@
 use axum::{
     Json,
-    extract::{Multipart, Path, State},
+    extract::{Multipart, State},
+    middleware::from_fn_with_state,
 };
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 use uuid::Uuid;
 
 use crate::{
     AppState,
+    auth::{backend::AuthBackend, permission::require_role},
     error::AppError,
+    handlers::{
+        document_handler::{get_owner_documents, upload_owner_document, upload_router},
+        utils::{
+            deleted_params::DeletedParams,
+            extract::{Path, Query},
+            validated_json::ValidatedJson,
+        },
+    },
     logic::services::{document_service::DocumentService, teacher_service::TeacherService},
     models::{
-        document::{Document, DocumentFileForm},
+        document::{Document, DocumentFileForm, DocumentFilter, DocumentOwner},
+        pagination::{PaginatedResponse, PaginationParams},
         teacher::{NewTeacher, Teacher, UpdateTeacher},
+        user::PermissionRole,
     },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions = OpenApiRouter::new()
-        .routes(routes!(
-            create_teacher,
-            get_teacher,
-            update_teacher,
-            delete_teacher
-        ))
-        .routes(routes!(
-            get_teacher_documents,
-            upload_document,
-            delete_document
-        ));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_user = OpenApiRouter::new()
+        .routes(routes!(get_teachers))
+        .routes(routes!(get_teacher))
+        .route_layer(from_fn_with_state(PermissionRole::User, require_role));
+    let need_teacher = OpenApiRouter::new()
+        .routes(routes!(get_teacher_documents))
+        .route_layer(from_fn_with_state(PermissionRole::Teacher, require_role));
+    let need_director = OpenApiRouter::new()
+        .routes(routes!(create_teacher))
+        .routes(routes!(update_teacher, delete_teacher))
+        .routes(routes!(delete_document))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role));
+    let need_director_upload = upload_router(
+        OpenApiRouter::new().routes(routes!(upload_document)),
+        PermissionRole::Director,
+    );
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(restore_teacher))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new()
+        .merge(need_user)
+        .merge(need_teacher)
+        .merge(need_director)
+        .merge(need_director_upload)
+        .merge(need_admin)
 }
 
 /// Создание нового преподавателя
@@ -39,9 +64,15 @@ pub fn router() -> OpenApiRouter<AppState> {
 /// ### Входные данные:
 /// - `name`: Имя преподавателя (обязательное поле)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **201 Created**: Преподаватель успешно создан. Возвращает данные созданного преподавателя.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствует обязательное поле).
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -50,13 +81,17 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 201, body = Teacher, description = "Преподаватель успешно создан"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Teacher"
 )]
 async fn create_teacher(
     State(teacher_service): State<TeacherService>,
-    Json(new_teacher): Json<NewTeacher>,
+    ValidatedJson(new_teacher): ValidatedJson<NewTeacher>,
 ) -> Result<Json<Teacher>, AppError> {
     info!("Creating new teacher");
     let new_teacher = teacher_service.create(new_teacher)?;
@@ -67,15 +102,34 @@ async fn create_teacher(
 ///
 /// Этот эндпоинт позволяет загрузить документ для конкретного преподавателя.
 ///
+/// Принимаются файлы png, jpeg, webp и pdf не больше `DOCUMENT_MAX_SIZE_MB` мегабайт. Тип
+/// файла определяется по его содержимому. Имя файла очищается от пути и недопустимых
+/// символов, а его расширение приводится в соответствие с типом файла.
+///
 /// ### Параметры:
 /// - `id`: ID преподавателя (обязательный путь)
 ///
-/// ### Входные данные:
-/// - `document`: Файл документа в формате multipart/form-data
+/// ### Входные данные (multipart/form-data):
+/// - `document`: Файл документа (обязательное поле)
+/// - `category`: Вид документа: `certificate`, `medical_check`, `contract` или `other`
+///   (необязательное поле, по умолчанию `other`)
+/// - `description`: Описание документа (необязательное поле)
+/// - `valid_until`: Последний день действия документа в формате `YYYY-MM-DD` (необязательное
+///   поле, без него документ считается бессрочным)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
 ///
 /// ### Ответы:
 /// - **201 Created**: Документ успешно загружен. Возвращает данные документа.
-/// - **400 Bad Request**: Неверные входные данные или отсутствует файл.
+/// - **400 Bad Request**: Неверные входные данные, отсутствует файл или тип файла не
+///   поддерживается.
+/// - **422 Unprocessable Entity**: Описание пустое или слишком длинное.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **404 Not Found**: Преподаватель с указанным ID не найден.
+/// - **409 Conflict**: У преподавателя уже есть документ с таким же содержимым.
+/// - **413 Payload Too Large**: Файл больше допустимого размера.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     post,
@@ -91,18 +145,68 @@ async fn create_teacher(
     responses(
         (status = 201, body = Document, description = "Документ успешно загружен"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Преподаватель не найден"),
+        (status = 409, description = "Такой документ уже загружен"),
+        (status = 413, description = "Файл слишком большой"),
+        (status = 422, description = "Входные данные не прошли проверку"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Teacher"
 )]
 async fn upload_document(
     State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(teacher_id): Path<i32>,
     multipart: Multipart,
 ) -> Result<Json<Document>, AppError> {
-    info!("Uploading document to teacher with ID {}", teacher_id);
-    let document = document_service.create(multipart, teacher_id).await?;
-    Ok(Json(document))
+    let owner = DocumentOwner::Teacher(teacher_id);
+    upload_owner_document(document_service, auth_session, owner, multipart).await
+}
+
+/// Получение списка преподавателей
+///
+/// Этот эндпоинт возвращает постраничный список преподавателей.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
+///
+/// ### Ответы:
+/// - **200 OK**: Список преподавателей успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams, DeletedParams),
+    responses(
+        (status = 200, body = PaginatedResponse<Teacher>, description = "Список преподавателей успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["User"])),
+    tag = "Teacher"
+)]
+async fn get_teachers(
+    State(teacher_service): State<TeacherService>,
+    auth_session: AuthSession<AuthBackend>,
+    Query(pagination): Query<PaginationParams>,
+    Query(deleted): Query<DeletedParams>,
+) -> Result<Json<PaginatedResponse<Teacher>>, AppError> {
+    info!("Getting teachers");
+    let teachers = teacher_service.get_all(&pagination, deleted.include_deleted(&auth_session)?)?;
+    Ok(Json(teachers))
 }
 
 /// Получение преподавателя по ID
@@ -111,64 +215,95 @@ async fn upload_document(
 ///
 /// ### Параметры:
 /// - `id`: ID преподавателя (обязательный путь)
+/// - `include_deleted`: Включать удаленные записи (необязательный, по умолчанию false, только для роли `Admin`)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `User`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные преподавателя успешно получены.
 /// - **404 Not Found**: Преподаватель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}",
     params(
-        ("id" = i32, Path, description = "ID запрашиваемого преподавателя")
+        ("id" = i32, Path, description = "ID запрашиваемого преподавателя"),
+        DeletedParams
     ),
     responses(
         (status = 200, body = Teacher, description = "Данные преподавателя успешно получены"),
         (status = 404, description = "Преподаватель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["User"])),
     tag = "Teacher"
 )]
 async fn get_teacher(
     State(teacher_service): State<TeacherService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(teacher_id): Path<i32>,
+    Query(deleted): Query<DeletedParams>,
 ) -> Result<Json<Teacher>, AppError> {
     info!("Getting teacher with ID {}", teacher_id);
-    let teacher = teacher_service.get(teacher_id)?;
+    let teacher = teacher_service.get(teacher_id, deleted.include_deleted(&auth_session)?)?;
     Ok(Json(teacher))
 }
 
 /// Получение всех документов преподавателя
 ///
 /// Этот эндпоинт позволяет получить список всех документов, связанных с конкретным преподавателем.
+/// Срок действия документов сравнивается с текущей датой в часовом поясе школы.
 ///
 /// ### Параметры:
 /// - `id`: ID преподавателя (обязательный путь)
+/// - `category`: Вид документа (необязательный)
+/// - `expired`: `true` — только документы с истекшим сроком действия, `false` — только
+///   действующие и бессрочные (необязательный)
+/// - `expires_within_days`: Только действующие документы, срок действия которых истекает в
+///   ближайшие N дней (необязательный)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Teacher`
+/// - Преподаватель может получать только свои документы
 ///
 /// ### Ответы:
 /// - **200 OK**: Список документов успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
 /// - **404 Not Found**: Преподаватель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     get,
     path = "/{id}/documents",
     params(
-        ("id" = i32, Path, description = "ID преподавателя у которого запрашиваются документы")
+        ("id" = i32, Path, description = "ID преподавателя у которого запрашиваются документы"),
+        DocumentFilter
     ),
     responses(
         (status = 200, body = Vec<Document>, description = "Список документов успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
         (status = 404, description = "Преподаватель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Teacher"])),
     tag = "Teacher"
 )]
 async fn get_teacher_documents(
     State(document_service): State<DocumentService>,
+    auth_session: AuthSession<AuthBackend>,
     Path(teacher_id): Path<i32>,
+    Query(filter): Query<DocumentFilter>,
 ) -> Result<Json<Vec<Document>>, AppError> {
-    info!("Getting documents for teacher with ID {}", teacher_id);
-    let documents = document_service.get_by_teacher_id(teacher_id)?;
-    Ok(Json(documents))
+    let owner = DocumentOwner::Teacher(teacher_id);
+    get_owner_documents(document_service, auth_session, owner, filter)
 }
 
 /// Обновление существующего преподавателя
@@ -181,13 +316,19 @@ async fn get_teacher_documents(
 /// ### Входные данные:
 /// - `name`: Новое имя преподавателя (необязательное поле)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Данные преподавателя успешно обновлены.
 /// - **404 Not Found**: Преподаватель с указанным ID не найден.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID преподавателя который требуется обновить")
@@ -197,14 +338,18 @@ async fn get_teacher_documents(
         (status = 200, body = Teacher, description = "Данные преподавателя успешно обновлены"),
         (status = 404, description = "Преподаватель не найден"),
         (status = 400, description = "Неверные входные данные"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Teacher"
 )]
 async fn update_teacher(
     State(teacher_service): State<TeacherService>,
     Path(teacher_id): Path<i32>,
-    Json(update_teacher): Json<UpdateTeacher>,
+    ValidatedJson(update_teacher): ValidatedJson<UpdateTeacher>,
 ) -> Result<Json<Teacher>, AppError> {
     info!("Updating teacher with ID {}", teacher_id);
     let updated_teacher = teacher_service.update(teacher_id, update_teacher)?;
@@ -215,12 +360,20 @@ async fn update_teacher(
 ///
 /// Этот эндпоинт позволяет удалить существующего преподавателя по его идентификатору.
 ///
+/// Преподаватель помечается удаленным, его документы и связи с группами сохраняются.
+/// Администратор может восстановить преподавателя, пока не истек срок хранения удаленных записей.
+///
 /// ### Параметры:
 /// - `id`: ID преподавателя (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Преподаватель успешно удален.
 /// - **404 Not Found**: Преподаватель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -231,8 +384,11 @@ async fn update_teacher(
     responses(
         (status = 200, body = String, description = "Преподаватель успешно удален"),
         (status = 404, description = "Преподаватель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Teacher"
 )]
 async fn delete_teacher(
@@ -248,6 +404,48 @@ async fn delete_teacher(
     }
 }
 
+/// Восстановление удаленного преподавателя
+///
+/// Этот эндпоинт снимает с преподавателя отметку об удалении. Документы и связи с группами
+/// остаются прежними.
+///
+/// ### Параметры:
+/// - `id`: ID преподавателя (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Преподаватель успешно восстановлен. Возвращает данные преподавателя.
+/// - **404 Not Found**: Удаленный преподаватель с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/restore",
+    params(
+        ("id" = i32, Path, description = "ID восстанавливаемого преподавателя")
+    ),
+    responses(
+        (status = 200, body = Teacher, description = "Преподаватель успешно восстановлен"),
+        (status = 404, description = "Удаленный преподаватель не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "Teacher"
+)]
+async fn restore_teacher(
+    State(teacher_service): State<TeacherService>,
+    Path(teacher_id): Path<i32>,
+) -> Result<Json<Teacher>, AppError> {
+    info!("Restoring teacher with ID {}", teacher_id);
+    let teacher = teacher_service.restore(teacher_id)?;
+    Ok(Json(teacher))
+}
+
 /// Удаление документа преподавателя
 ///
 /// Этот эндпоинт позволяет удалить конкретный документ преподавателя по его идентификатору.
@@ -256,9 +454,14 @@ async fn delete_teacher(
 /// - `id`: ID преподавателя (обязательный путь)
 /// - `document_id`: ID документа (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
 /// ### Ответы:
 /// - **200 OK**: Документ успешно удален.
-/// - **404 Not Found**: Документ или преподаватель с указанными ID не найдены.
+/// - **404 Not Found**: Документ не найден или прикреплен к другому преподавателю.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Внутренняя ошибка сервера.
 #[utoipa::path(
     delete,
@@ -270,8 +473,11 @@ async fn delete_teacher(
     responses(
         (status = 200, body = String, description = "Документ удален"),
         (status = 404, description = "Документ или преподаватель не найдены"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Внутренняя ошибка сервера")
     ),
+    security(("session" = ["Director"])),
     tag = "Teacher"
 )]
 async fn delete_document(
@@ -282,7 +488,9 @@ async fn delete_document(
         "Deleting document {} from teacher with ID {}",
         document_id, teacher_id
     );
-    let deleted = document_service.delete(document_id)?;
+    let deleted = document_service
+        .delete(DocumentOwner::Teacher(teacher_id), document_id)
+        .await?;
     if deleted {
         Ok(Json("Successfully deleted".to_string()))
     } else {
@@ -0,0 +1,212 @@
+use axum::{Json, extract::State, http::StatusCode, middleware::from_fn_with_state};
+use tracing::info;
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+use crate::{
+    AppState,
+    auth::permission::require_role,
+    error::AppError,
+    handlers::utils::extract::{Path, Query},
+    logic::services::timetable_service::TimetableService,
+    models::{
+        lesson::LessonWithRelations,
+        pagination::{PaginatedResponse, PaginationParams},
+        problem_details::ProblemDetails,
+        timetable::{TimetableJob, TimetableJobWithProposal, TimetableRequest},
+        user::PermissionRole,
+    },
+};
+
+pub fn router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::new()
+        .routes(routes!(start_timetable_job, get_timetable_jobs))
+        .routes(routes!(get_timetable_job))
+        .routes(routes!(accept_timetable_job))
+        .route_layer(from_fn_with_state(PermissionRole::Director, require_role))
+}
+
+/// Запуск составления расписания
+///
+/// Этот эндпоинт запускает составление расписания на неделю в фоне и сразу возвращает
+/// созданную задачу. Готовое расписание можно получить по ID задачи, когда она перейдет
+/// в статус `completed`.
+///
+/// Жесткие ограничения: у группы и преподавателя не может быть двух уроков одновременно,
+/// урок не ставится на время, когда преподаватель недоступен или у группы, преподавателя
+/// или кабинета уже есть урок, в кабинете должно хватать мест для всей группы, у группы не
+/// больше `max_lessons_per_day` уроков в день. Мягкие ограничения: предпочтительные
+/// номера уроков и не больше одного урока предмета у группы в день.
+///
+/// ### Входные данные:
+/// - `week_start`: Понедельник недели, на которую составляется расписание (обязательное поле)
+/// - `seed`: Зерно генератора, с одним зерном получается одно и то же расписание (необязательное поле)
+/// - `weekdays`: Учебные дни, 1 - понедельник (необязательное поле, по умолчанию с 1 по 5)
+/// - `periods`: Время начала и продолжительность уроков внутри дня (обязательное поле)
+/// - `requirements`: Группа, предмет, количество уроков в неделю и предпочтительные номера уроков (обязательное поле)
+/// - `teacher_availability`: Когда преподаватели могут вести уроки (необязательное поле)
+/// - `room_ids`: Кабинеты, которые можно использовать (необязательное поле, по умолчанию все)
+/// - `max_lessons_per_day`: Максимум уроков у группы в день (необязательное поле)
+/// - `iterations`: Количество итераций улучшения расписания (необязательное поле, не больше 200000)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **202 Accepted**: Задача создана. Возвращает данные задачи.
+/// - **400 Bad Request**: Неверные входные данные.
+/// - **404 Not Found**: Группа или кабинет не найдены.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/",
+    request_body = TimetableRequest,
+    responses(
+        (status = 202, body = TimetableJob, description = "Задача составления расписания создана"),
+        (status = 400, description = "Неверные входные данные"),
+        (status = 404, description = "Группа или кабинет не найдены"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Timetable"
+)]
+async fn start_timetable_job(
+    State(timetable_service): State<TimetableService>,
+    Json(request): Json<TimetableRequest>,
+) -> Result<(StatusCode, Json<TimetableJob>), AppError> {
+    info!("Starting timetable job");
+    let job = timetable_service.start(request)?;
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// Получение списка задач составления расписания
+///
+/// Этот эндпоинт возвращает постраничный список задач, начиная с самых новых.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Список задач успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams),
+    responses(
+        (status = 200, body = PaginatedResponse<TimetableJob>, description = "Список задач успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Timetable"
+)]
+async fn get_timetable_jobs(
+    State(timetable_service): State<TimetableService>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<TimetableJob>>, AppError> {
+    info!("Getting timetable jobs");
+    let jobs = timetable_service.get_all(&pagination)?;
+    Ok(Json(jobs))
+}
+
+/// Получение задачи составления расписания по ID
+///
+/// Этот эндпоинт возвращает статус задачи, а для завершенной задачи - предложенные уроки,
+/// итоговую оценку (`score`, чем меньше, тем лучше) и список нарушенных ограничений.
+///
+/// ### Параметры:
+/// - `id`: ID задачи (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Данные задачи успешно получены.
+/// - **404 Not Found**: Задача с указанным ID не найдена.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/{id}",
+    params(
+        ("id" = i32, Path, description = "ID запрашиваемой задачи")
+    ),
+    responses(
+        (status = 200, body = TimetableJobWithProposal, description = "Данные задачи успешно получены"),
+        (status = 404, description = "Задача не найдена"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Timetable"
+)]
+async fn get_timetable_job(
+    State(timetable_service): State<TimetableService>,
+    Path(job_id): Path<i32>,
+) -> Result<Json<TimetableJobWithProposal>, AppError> {
+    info!("Getting timetable job with ID {}", job_id);
+    let job = timetable_service.get(job_id)?;
+    Ok(Json(job))
+}
+
+/// Принятие составленного расписания
+///
+/// Этот эндпоинт создает уроки по расписанию завершенной задачи. Перед созданием все
+/// предложенные уроки заново проверяются на пересечения с существующими уроками: если
+/// хотя бы одно пересечение найдено, ни один урок не создается.
+///
+/// ### Параметры:
+/// - `id`: ID задачи (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Director`
+///
+/// ### Ответы:
+/// - **200 OK**: Расписание принято. Возвращает созданные уроки.
+/// - **400 Bad Request**: Задача еще не завершена или уже принята.
+/// - **404 Not Found**: Задача с указанным ID не найдена.
+/// - **409 Conflict**: Предложенные уроки пересекаются с существующими. Возвращает список пересечений.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/accept",
+    params(
+        ("id" = i32, Path, description = "ID задачи, расписание которой принимается")
+    ),
+    responses(
+        (status = 200, body = Vec<LessonWithRelations>, description = "Расписание принято"),
+        (status = 400, description = "Задача еще не завершена или уже принята"),
+        (status = 404, description = "Задача не найдена"),
+        (status = 409, body = ProblemDetails, description = "Уроки пересекаются с существующими"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Director"])),
+    tag = "Timetable"
+)]
+async fn accept_timetable_job(
+    State(timetable_service): State<TimetableService>,
+    Path(job_id): Path<i32>,
+) -> Result<Json<Vec<LessonWithRelations>>, AppError> {
+    info!("Accepting timetable job with ID {}", job_id);
+    let lessons = timetable_service.accept(job_id)?;
+    Ok(Json(lessons))
+}
@@ -1,21 +1,35 @@
-use axum::{
-    Json,
-    extract::{Path, State},
-};
+use axum::{Json, extract::State, middleware::from_fn_with_state};
+use axum_login::AuthSession;
 use tracing::info;
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use crate::{
     AppState,
+    auth::{
+        backend::AuthBackend,
+        permission::{authenticated_user, require_role},
+    },
     error::AppError,
-    logic::services::user_service::UserService,
-    models::user::{RawNewUser, UpdateUser, User},
+    handlers::utils::{
+        extract::{Path, Query},
+        validated_json::ValidatedJson,
+    },
+    logic::services::{login_throttle_service::LoginThrottleService, user_service::UserService},
+    models::{
+        pagination::{PaginatedResponse, PaginationParams},
+        password_reset::IssuedPasswordResetToken,
+        user::{PermissionRole, RawNewUser, RawUpdateUser, User},
+    },
 };
 
 pub fn router() -> OpenApiRouter<AppState> {
-    let dont_need_permissions =
-        OpenApiRouter::new().routes(routes!(create_user, get_user, update_user, delete_user));
-    OpenApiRouter::new().merge(dont_need_permissions)
+    let need_admin = OpenApiRouter::new()
+        .routes(routes!(create_user, get_users))
+        .routes(routes!(get_user, update_user, delete_user))
+        .routes(routes!(issue_password_reset))
+        .routes(routes!(unlock_user))
+        .route_layer(from_fn_with_state(PermissionRole::Admin, require_role));
+    OpenApiRouter::new().merge(need_admin)
 }
 
 /// Создание нового пользователя
@@ -24,12 +38,23 @@ pub fn router() -> OpenApiRouter<AppState> {
 ///
 /// ### Входные данные:
 /// - `username`: Имя пользователя (обязательное поле)
-/// - `password`: Пароль (обязательное поле)
+/// - `password`: Пароль (обязательное поле). Должен соответствовать требованиям к паролям
 /// - `full_name`: Полное имя пользователя (необязательное поле)
+/// - `teacher_id`: ID преподавателя, профилем которого является пользователь (необязательное
+///   поле)
+/// - `parent_id`: ID родителя, профилем которого является пользователь (необязательное поле)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
 ///
 /// ### Ответы:
 /// - **201 Created**: Пользователь успешно создан. Возвращает данные пользователя.
 /// - **400 Bad Request**: Неверные входные данные (например, отсутствуют обязательные поля).
+/// - **409 Conflict**: Пользователь с таким именем уже существует или преподаватель либо
+///   родитель уже связан с другим пользователем.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Ошибка сервера при регистрации.
 #[utoipa::path(
     post,
@@ -38,20 +63,65 @@ pub fn router() -> OpenApiRouter<AppState> {
     responses(
         (status = 200, body = User, description = "Пользователь успешно создан"),
         (status = 400, description = "Неверные данные"),
+        (status = 409, description = "Имя пользователя или профиль уже заняты"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Ошибка сервера")
     ),
+    security(("session" = ["Admin"])),
     tag = "User"
 )]
 #[axum::debug_handler]
 async fn create_user(
     State(user_service): State<UserService>,
-    Json(new_user): Json<RawNewUser>,
+    ValidatedJson(new_user): ValidatedJson<RawNewUser>,
 ) -> Result<Json<User>, AppError> {
     info!("Creating new user");
     let new_user = user_service.create(new_user)?;
     Ok(Json(new_user))
 }
 
+/// Получение списка пользователей
+///
+/// Этот эндпоинт возвращает постраничный список пользователей.
+///
+/// ### Параметры:
+/// - `page`: Номер страницы (необязательный, по умолчанию 1)
+/// - `page_size`: Количество записей на странице (необязательный, по умолчанию 25, не больше 100)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Список пользователей успешно получен.
+/// - **400 Bad Request**: Неверные параметры запроса.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(PaginationParams),
+    responses(
+        (status = 200, body = PaginatedResponse<User>, description = "Список пользователей успешно получен"),
+        (status = 400, description = "Неверные параметры запроса"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "User"
+)]
+async fn get_users(
+    State(user_service): State<UserService>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<PaginatedResponse<User>>, AppError> {
+    info!("Getting users");
+    let users = user_service.get_all(&pagination)?;
+    Ok(Json(users))
+}
+
 /// Получение пользователя по ID
 ///
 /// Этот эндпоинт позволяет получить данные конкретного пользователя по его идентификатору.
@@ -59,9 +129,14 @@ async fn create_user(
 /// ### Параметры:
 /// - `id`: ID пользователя (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
 /// ### Ответы:
 /// - **200 OK**: Данные пользователя успешно получены.
 /// - **404 Not Found**: Пользователь с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Ошибка сервера при получении данных.
 #[utoipa::path(
     get,
@@ -72,8 +147,11 @@ async fn create_user(
     responses(
         (status = 200, body = User, description = "Данные пользователя успешно получены"),
         (status = 404, description = "Пользователь не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Ошибка сервера")
     ),
+    security(("session" = ["Admin"])),
     tag = "User"
 )]
 async fn get_user(
@@ -96,31 +174,47 @@ async fn get_user(
 /// - `username`: Новое имя пользователя (необязательное поле)
 /// - `role`: Новая роль пользователя (необязательное поле)
 /// - `full_name`: Новое полное имя пользователя (необязательное поле)
+/// - `teacher_id`: Новый ID преподавателя (необязательное поле, `null` отвязывает
+///   преподавателя)
+/// - `parent_id`: Новый ID родителя (необязательное поле, `null` отвязывает родителя)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
 ///
 /// ### Ответы:
 /// - **200 OK**: Данные пользователя успешно обновлены.
 /// - **404 Not Found**: Пользователь с указанным ID не найден.
 /// - **400 Bad Request**: Неверные входные данные.
+/// - **409 Conflict**: Пользователь с таким именем уже существует или преподаватель либо
+///   родитель уже связан с другим пользователем.
+/// - **422 Unprocessable Entity**: Входные данные не прошли проверку. Возвращает ошибки по полям.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Ошибка сервера при обновлении.
 #[utoipa::path(
-    put,
+    method(put, patch),
     path = "/{id}",
     params(
         ("id" = i32, Path, description = "ID пользователя который требуется обновить")
     ),
-    request_body = UpdateUser,
+    request_body = RawUpdateUser,
     responses(
         (status = 200, body = User, description = "Данные пользователя успешно обновлены"),
         (status = 404, description = "Пользователь не найден"),
         (status = 400, description = "Неверные данные"),
+        (status = 409, description = "Имя пользователя или профиль уже заняты"),
+        (status = 422, description = "Входные данные не прошли проверку"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Ошибка сервера")
     ),
+    security(("session" = ["Admin"])),
     tag = "User"
 )]
 async fn update_user(
     State(user_service): State<UserService>,
     Path(user_id): Path<i32>,
-    Json(updated_user): Json<UpdateUser>,
+    ValidatedJson(updated_user): ValidatedJson<RawUpdateUser>,
 ) -> Result<Json<User>, AppError> {
     info!("Updating user by id");
     let user = user_service.update(user_id, updated_user)?;
@@ -134,9 +228,14 @@ async fn update_user(
 /// ### Параметры:
 /// - `id`: ID пользователя (обязательный путь)
 ///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
 /// ### Ответы:
 /// - **200 OK**: Пользователь успешно удален.
 /// - **404 Not Found**: Пользователь с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
 /// - **500 Internal Server Error**: Ошибка сервера при удалении.
 #[utoipa::path(
     delete,
@@ -147,8 +246,11 @@ async fn update_user(
     responses(
         (status = 200, body = String, description = "Пользователь успешно удален"),
         (status = 404, description = "Пользователь не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
         (status = 500, description = "Ошибка сервера")
     ),
+    security(("session" = ["Admin"])),
     tag = "User"
 )]
 async fn delete_user(
@@ -163,3 +265,93 @@ async fn delete_user(
         Ok(Json("User not found".to_string()))
     }
 }
+
+/// Выдача токена сброса пароля
+///
+/// Этот эндпоинт выдает одноразовый токен, по которому пользователь может задать новый
+/// пароль через `POST /api/v1/auth/password/reset`. Токен показывается только в этом
+/// ответе, ранее выданные неиспользованные токены пользователя перестают действовать.
+///
+/// ### Параметры:
+/// - `id`: ID пользователя (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Токен успешно выдан. Возвращает токен и время его истечения.
+/// - **404 Not Found**: Пользователь с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/password_reset",
+    params(
+        ("id" = i32, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, body = IssuedPasswordResetToken, description = "Токен успешно выдан"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "User"
+)]
+async fn issue_password_reset(
+    State(user_service): State<UserService>,
+    auth_session: AuthSession<AuthBackend>,
+    Path(user_id): Path<i32>,
+) -> Result<Json<IssuedPasswordResetToken>, AppError> {
+    info!("Issuing password reset token for user with ID {}", user_id);
+    let admin = authenticated_user(auth_session.user.as_ref())?;
+    let reset_token = user_service.issue_password_reset(user_id, admin)?;
+    Ok(Json(reset_token))
+}
+
+/// Снятие блокировки входа
+///
+/// Этот эндпоинт снимает блокировку входа по логину пользователя, наступившую после
+/// неудачных попыток входа, и сбрасывает счетчик этих попыток. Блокировки по IP-адресу
+/// не снимаются и истекают сами.
+///
+/// ### Параметры:
+/// - `id`: ID пользователя (обязательный путь)
+///
+/// ### Права доступа:
+/// - Минимальная роль: `Admin`
+///
+/// ### Ответы:
+/// - **200 OK**: Блокировка снята. Возвращает `true`, если вход был заблокирован.
+/// - **404 Not Found**: Пользователь с указанным ID не найден.
+/// - **401 Unauthorized**: Пользователь не авторизован.
+/// - **403 Forbidden**: Недостаточно прав.
+/// - **500 Internal Server Error**: Внутренняя ошибка сервера.
+#[utoipa::path(
+    post,
+    path = "/{id}/unlock",
+    params(
+        ("id" = i32, Path, description = "ID пользователя")
+    ),
+    responses(
+        (status = 200, body = bool, description = "Блокировка снята"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 401, description = "Требуется авторизация"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 500, description = "Внутренняя ошибка сервера")
+    ),
+    security(("session" = ["Admin"])),
+    tag = "User"
+)]
+async fn unlock_user(
+    State(user_service): State<UserService>,
+    State(login_throttle_service): State<LoginThrottleService>,
+    Path(user_id): Path<i32>,
+) -> Result<Json<bool>, AppError> {
+    info!("Unlocking login of user with ID {}", user_id);
+    let user = user_service.get(user_id)?;
+    let was_locked = login_throttle_service.unlock(&user.username).await?;
+    Ok(Json(was_locked))
+}
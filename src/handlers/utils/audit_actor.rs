@@ -0,0 +1,27 @@
+use axum::{extract::Request, middleware::Next, response::Response};
+use axum_login::AuthSession;
+
+use crate::{auth::backend::AuthBackend, models::user::User};
+
+tokio::task_local! {
+    static AUDIT_ACTOR: Option<User>;
+}
+
+/// Middleware, запоминающий пользователя текущей сессии на время обработки запроса.
+/// Сервисы не получают сессию напрямую, поэтому журнал изменений узнает автора изменения
+/// через [`current_actor`].
+pub async fn audit_actor(
+    auth_session: AuthSession<AuthBackend>,
+    request: Request,
+    next: Next,
+) -> Response {
+    AUDIT_ACTOR
+        .scope(auth_session.user, next.run(request))
+        .await
+}
+
+/// Пользователь, выполняющий текущий запрос. `None`, если пользователь не авторизован или
+/// код выполняется вне запроса, например в фоновой задаче.
+pub fn current_actor() -> Option<User> {
+    AUDIT_ACTOR.try_with(Clone::clone).ok().flatten()
+}
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+use crate::error::AppError;
+
+/// Разбирает заголовок `Range` запроса к файлу размером `size` байт и возвращает
+/// запрошенный диапазон.
+///
+/// Поддерживается один диапазон в формате `bytes=начало-конец`, `bytes=начало-` или
+/// `bytes=-длина`. Заголовок с несколькими диапазонами или в неверном формате
+/// игнорируется, и файл отдается целиком, как разрешает RFC 9110. Если диапазон не
+/// пересекается с файлом, возвращается 416.
+pub fn parse_range(header: &str, size: u64) -> Result<Option<Range<u64>>, AppError> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // Последние `end` байт файла
+        let Ok(length) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if length == 0 || size == 0 {
+            return Err(AppError::RangeNotSatisfiable(size));
+        }
+        return Ok(Some(size.saturating_sub(length)..size));
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return Ok(None);
+    };
+    let end = if end.is_empty() {
+        size
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end.saturating_add(1).min(size),
+            _ => return Ok(None),
+        }
+    };
+    if start >= size {
+        return Err(AppError::RangeNotSatisfiable(size));
+    }
+    Ok(Some(start..end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_returns_requested_bytes() {
+        let cases = [
+            ("bytes=0-9", 100, 0..10),
+            ("bytes=10-", 100, 10..100),
+            ("bytes=90-200", 100, 90..100),
+            ("bytes=-10", 100, 90..100),
+            ("bytes=-500", 100, 0..100),
+            ("bytes=99-99", 100, 99..100),
+            (" bytes= 5 - 6 ", 100, 5..7),
+        ];
+        for (header, size, expected) in cases {
+            let range = parse_range(header, size).unwrap();
+            assert_eq!(range, Some(expected), "{}", header);
+        }
+    }
+
+    #[test]
+    fn parse_range_ignores_unsupported_headers() {
+        let cases = [
+            "bytes=0-1,5-6",
+            "bytes=-1, -2",
+            "bytes=9-5",
+            "bytes=abc-",
+            "bytes=-abc",
+            "bytes=5",
+            "items=0-1",
+            "",
+        ];
+        for header in cases {
+            let range = parse_range(header, 100).unwrap();
+            assert_eq!(range, None, "{}", header);
+        }
+    }
+
+    #[test]
+    fn parse_range_rejects_unsatisfiable_ranges() {
+        let cases = [
+            ("bytes=-0", 100),
+            ("bytes=100-", 100),
+            ("bytes=150-200", 100),
+            ("bytes=5-", 0),
+            ("bytes=0-0", 0),
+            ("bytes=-5", 0),
+        ];
+        for (header, size) in cases {
+            let error = parse_range(header, size).unwrap_err();
+            assert!(
+                matches!(error, AppError::RangeNotSatisfiable(s) if s == size),
+                "{}",
+                header
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use axum_login::AuthSession;
+use serde::Deserialize;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    auth::{backend::AuthBackend, permission::check_role},
+    error::AppError,
+    models::user::PermissionRole,
+};
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeletedParams {
+    /// Включать удаленные записи (только для роли `Admin`)
+    #[serde(default)]
+    #[param(default = false)]
+    pub include_deleted: bool,
+}
+
+impl DeletedParams {
+    /// Нужно ли возвращать удаленные записи. Запрос удаленных записей от пользователя
+    /// с ролью ниже `Admin` отклоняется.
+    pub fn include_deleted(
+        &self,
+        auth_session: &AuthSession<AuthBackend>,
+    ) -> Result<bool, AppError> {
+        if self.include_deleted {
+            check_role(auth_session.user.as_ref(), PermissionRole::Admin)?;
+        }
+        Ok(self.include_deleted)
+    }
+}
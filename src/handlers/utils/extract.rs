@@ -0,0 +1,35 @@
+use axum::extract::{
+    FromRequestParts,
+    rejection::{PathRejection, QueryRejection},
+};
+
+use crate::error::AppError;
+
+/// Параметры строки запроса. В отличие от [`axum::extract::Query`] ошибка разбора
+/// возвращается в формате `application/problem+json` с кодом 400.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(AppError))]
+pub struct Query<T>(pub T);
+
+/// Параметры пути. В отличие от [`axum::extract::Path`] ошибка разбора возвращается
+/// в формате `application/problem+json` с кодом 400.
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Path), rejection(AppError))]
+pub struct Path<T>(pub T);
+
+impl From<QueryRejection> for AppError {
+    fn from(rejection: QueryRejection) -> Self {
+        AppError::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<PathRejection> for AppError {
+    fn from(rejection: PathRejection) -> Self {
+        match rejection {
+            PathRejection::FailedToDeserializePathParams(error) => {
+                AppError::BadRequest(error.body_text())
+            }
+            rejection => AppError::InternalServerError(rejection.body_text()),
+        }
+    }
+}
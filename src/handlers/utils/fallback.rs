@@ -0,0 +1,8 @@
+use axum::http::{Method, Uri};
+
+use crate::error::AppError;
+
+/// Ответ на запрос к несуществующему маршруту в формате `application/problem+json`.
+pub async fn route_not_found(method: Method, uri: Uri) -> AppError {
+    AppError::NotFound(format!("No route for {} {}", method, uri.path()))
+}
@@ -1 +1,7 @@
-pub mod paginated_response;
+pub mod audit_actor;
+pub mod byte_range;
+pub mod deleted_params;
+pub mod extract;
+pub mod fallback;
+pub mod request_id;
+pub mod validated_json;
@@ -1,25 +0,0 @@
-use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
-
-#[derive(Serialize, ToSchema)]
-pub struct PaginatedResponse<T> {
-    pub items: Vec<T>,
-    pub total: i64,
-    pub page: i64,
-    pub page_size: i64,
-}
-#[derive(Deserialize, ToSchema)]
-pub struct PaginationParams {
-    #[serde(default = "default_page")]
-    pub page: i64,
-    #[serde(default = "default_page_size")]
-    pub page_size: i64,
-}
-
-fn default_page() -> i64 {
-    1
-}
-
-fn default_page_size() -> i64 {
-    25
-}
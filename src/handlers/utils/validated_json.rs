@@ -0,0 +1,62 @@
+use std::error::Error;
+
+use axum::{
+    Json,
+    extract::{FromRequest, Request, rejection::JsonRejection},
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::AppError,
+    models::{problem_details::FieldError, validation::Validate},
+};
+
+/// JSON тело запроса, прошедшее проверку [`Validate`].
+///
+/// Ошибки в типах полей и нарушения ограничений возвращаются с кодом 422 и списком
+/// ошибок по полям, синтаксически неверный JSON - с кодом 400.
+pub struct ValidatedJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(rejection_to_error)?;
+        value.validated()?;
+        Ok(Self(value))
+    }
+}
+
+fn rejection_to_error(rejection: JsonRejection) -> AppError {
+    match rejection {
+        JsonRejection::JsonDataError(error) => {
+            let detail = error
+                .source()
+                .map_or_else(|| error.body_text(), ToString::to_string);
+            AppError::Validation(vec![deserialize_error_to_field_error(&detail)])
+        }
+        rejection => AppError::BadRequest(rejection.body_text()),
+    }
+}
+
+/// Ошибка serde имеет вид `path.to.field: message` или `missing field `name``, из нее
+/// извлекается имя поля.
+fn deserialize_error_to_field_error(detail: &str) -> FieldError {
+    if let Some(rest) = detail.strip_prefix("missing field `")
+        && let Some((field, _)) = rest.split_once('`')
+    {
+        return FieldError::new(field, "required", "Value is required");
+    }
+    match detail.split_once(": ") {
+        Some((path, message)) if !path.contains(' ') => {
+            FieldError::new(path, "invalid_format", message)
+        }
+        _ => FieldError::new("body", "invalid_format", detail),
+    }
+}
@@ -1,13 +1,20 @@
 use logic::services::{
-    attendance_service::AttendanceService, document_service::DocumentService,
-    lesson_service::LessonService, parent_service::ParentService,
+    api_token_service::ApiTokenService, attendance_service::AttendanceService,
+    document_service::DocumentService, lesson_series_service::LessonSeriesService,
+    lesson_service::LessonService, login_throttle_service::LoginThrottleService,
+    parent_portal_service::ParentPortalService, parent_service::ParentService,
     student_group_service::StudentGroupService, student_service::StudentService,
     teacher_service::TeacherService,
 };
 
-use crate::logic::services::user_service::UserService;
+use crate::logic::services::{
+    audit_service::AuditService, retention_service::RetentionService, room_service::RoomService,
+    timetable_service::TimetableService, user_service::UserService,
+    waitlist_service::WaitlistService,
+};
 
 pub mod auth;
+pub mod config;
 pub mod db;
 pub mod error;
 pub mod handlers;
@@ -24,11 +31,20 @@ pub struct AppState {
 #[derive(Clone)]
 pub struct AppServices {
     pub lesson_service: LessonService,
+    pub lesson_series_service: LessonSeriesService,
     pub parent_service: ParentService,
+    pub parent_portal_service: ParentPortalService,
     pub student_service: StudentService,
     pub student_group_service: StudentGroupService,
     pub teacher_service: TeacherService,
     pub attendance_service: AttendanceService,
     pub document_service: DocumentService,
     pub user_service: UserService,
+    pub login_throttle_service: LoginThrottleService,
+    pub api_token_service: ApiTokenService,
+    pub room_service: RoomService,
+    pub timetable_service: TimetableService,
+    pub waitlist_service: WaitlistService,
+    pub retention_service: RetentionService,
+    pub audit_service: AuditService,
 }
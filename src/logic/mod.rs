@@ -1,3 +1,5 @@
 mod repositories;
 pub mod services;
+pub mod storage;
+pub mod timetable;
 
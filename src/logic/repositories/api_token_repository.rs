@@ -0,0 +1,71 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::api_token::{ApiToken, NewApiToken},
+    schema::api_tokens,
+};
+
+use super::single_result;
+
+#[derive(Clone)]
+pub struct ApiTokenRepository {
+    pool: PostgresPool,
+}
+
+impl ApiTokenRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn create(&self, new_api_token: NewApiToken) -> Result<ApiToken, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::insert_into(api_tokens::table)
+            .values(&new_api_token)
+            .returning(ApiToken::as_returning())
+            .get_result(&mut connection)?)
+    }
+
+    pub fn get(&self, api_token_id: i32) -> Result<ApiToken, AppError> {
+        let mut connection = self.pool.get()?;
+        single_result(
+            api_tokens::table
+                .find(api_token_id)
+                .select(ApiToken::as_select())
+                .load(&mut connection)?,
+        )
+    }
+
+    pub fn get_all(
+        &self,
+        user_id: i32,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<ApiToken>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = api_tokens::table
+            .filter(api_tokens::user_id.eq(user_id))
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let api_tokens = api_tokens::table
+            .filter(api_tokens::user_id.eq(user_id))
+            .order(api_tokens::id.desc())
+            .limit(limit)
+            .offset(offset)
+            .select(ApiToken::as_select())
+            .load(&mut connection)?;
+
+        Ok((api_tokens, total))
+    }
+
+    pub fn revoke(&self, api_token_id: i32) -> Result<ApiToken, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(api_tokens::table.find(api_token_id))
+            .set(api_tokens::revoked_at.eq(Utc::now()))
+            .returning(ApiToken::as_returning())
+            .get_result(&mut connection)?)
+    }
+}
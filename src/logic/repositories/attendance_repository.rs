@@ -4,7 +4,9 @@ use crate::{
     db::PostgresPool,
     error::AppError,
     models::{
-        attendance::{Attendance, AttendanceWithRelations, NewAttendance, UpdateAttendance},
+        attendance::{
+            Attendance, AttendanceFilter, AttendanceWithRelations, NewAttendance, UpdateAttendance,
+        },
         lesson::Lesson,
         student::Student,
     },
@@ -41,11 +43,7 @@ impl AttendanceRepository {
         new_attendances: Vec<NewAttendance>,
     ) -> Result<Vec<AttendanceWithRelations>, AppError> {
         let mut connection = self.pool.get()?;
-
-        let inserted_ids: Vec<i32> = diesel::insert_into(attendances::table)
-            .values(&new_attendances)
-            .returning(attendances::id)
-            .get_results(&mut connection)?;
+        let inserted_ids = Self::insert_all(&mut connection, &new_attendances)?;
 
         let query = attendances::table
             .filter(attendances::id.eq_any(inserted_ids))
@@ -54,6 +52,17 @@ impl AttendanceRepository {
         self.load_with_relations(query)
     }
 
+    /// Сохраняет посещения в рамках транзакции `connection` и возвращает их ID.
+    pub fn insert_all(
+        connection: &mut PgConnection,
+        new_attendances: &[NewAttendance],
+    ) -> Result<Vec<i32>, AppError> {
+        Ok(diesel::insert_into(attendances::table)
+            .values(new_attendances)
+            .returning(attendances::id)
+            .get_results(connection)?)
+    }
+
     pub fn get(&self, attendance_id: i32) -> Result<AttendanceWithRelations, AppError> {
         let query = attendances::table
             .filter(attendances::id.eq(attendance_id))
@@ -73,11 +82,47 @@ impl AttendanceRepository {
         self.load_with_relations(query)
     }
 
+    /// Отметка о посещении учеником урока, если она есть.
+    pub fn find_by_lesson_and_student(
+        &self,
+        lesson_id: i32,
+        student_id: i32,
+    ) -> Result<Option<AttendanceWithRelations>, AppError> {
+        let query = attendances::table
+            .filter(attendances::lesson_id.eq(lesson_id))
+            .filter(attendances::student_id.eq(student_id))
+            .into_boxed();
+
+        Ok(self.load_with_relations(query)?.into_iter().next())
+    }
+
+    pub fn get_all(
+        &self,
+        filter: &AttendanceFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AttendanceWithRelations>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::filtered(filter)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let query = Self::filtered(filter)
+            .order(attendances::id)
+            .limit(limit)
+            .offset(offset);
+
+        Ok((self.load_with_relations(query)?, total))
+    }
+
     pub fn update(
         &self,
         attendance_id: i32,
         updated_attendance: UpdateAttendance,
     ) -> Result<AttendanceWithRelations, AppError> {
+        if updated_attendance.is_empty() {
+            return self.get(attendance_id);
+        }
         let mut connection = self.pool.get()?;
         diesel::update(attendances::table.find(attendance_id))
             .set(&updated_attendance)
@@ -91,12 +136,34 @@ impl AttendanceRepository {
         Ok(diesel::delete(attendances::table.find(attendance_id)).execute(&mut connection)?)
     }
 
-    pub fn delete_by_lesson_id(&self, lesson_id: i32) -> Result<usize, AppError> {
-        let mut connection = self.pool.get()?;
-        Ok(
-            diesel::delete(attendances::table.filter(attendances::lesson_id.eq(lesson_id)))
-                .execute(&mut connection)?,
-        )
+    /// Удаляет посещения урока в рамках транзакции `connection` и возвращает их.
+    pub fn delete_by_lesson_id(
+        connection: &mut PgConnection,
+        lesson_id: i32,
+    ) -> Result<Vec<AttendanceWithRelations>, AppError> {
+        let query = attendances::table
+            .filter(attendances::lesson_id.eq(lesson_id))
+            .into_boxed();
+        let attendances = Self::load_all(connection, query)?;
+        diesel::delete(attendances::table.filter(attendances::lesson_id.eq(lesson_id)))
+            .execute(connection)?;
+        Ok(attendances)
+    }
+
+    fn filtered(filter: &AttendanceFilter) -> attendances::BoxedQuery<'_, Pg> {
+        let mut query = attendances::table.into_boxed();
+
+        if let Some(student_id) = filter.student_id {
+            query = query.filter(attendances::student_id.eq(student_id));
+        }
+        if let Some(lesson_id) = filter.lesson_id {
+            query = query.filter(attendances::lesson_id.eq(lesson_id));
+        }
+        if let Some(is_present) = filter.is_present {
+            query = query.filter(attendances::is_present.eq(is_present));
+        }
+
+        query
     }
 
     fn load_with_relations(
@@ -104,6 +171,13 @@ impl AttendanceRepository {
         query: attendances::BoxedQuery<'_, Pg>,
     ) -> Result<Vec<AttendanceWithRelations>, AppError> {
         let mut connection = self.pool.get()?;
+        Self::load_all(&mut connection, query)
+    }
+
+    fn load_all(
+        connection: &mut PgConnection,
+        query: attendances::BoxedQuery<'_, Pg>,
+    ) -> Result<Vec<AttendanceWithRelations>, AppError> {
         let results = query
             .inner_join(students::table)
             .inner_join(lessons::table)
@@ -112,7 +186,7 @@ impl AttendanceRepository {
                 Student::as_select(),
                 Lesson::as_select(),
             ))
-            .load::<(Attendance, Student, Lesson)>(&mut connection)?
+            .load::<(Attendance, Student, Lesson)>(connection)?
             .into_iter()
             .map(|(a, s, l)| AttendanceWithRelations {
                 attendance: a,
@@ -0,0 +1,68 @@
+use diesel::{pg::Pg, prelude::*};
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::audit::{AuditEntry, AuditFilter, NewAuditEntry},
+    schema::audit_log,
+};
+
+#[derive(Clone)]
+pub struct AuditRepository {
+    pool: PostgresPool,
+}
+
+impl AuditRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn create(&self, new_entry: NewAuditEntry) -> Result<AuditEntry, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::insert_into(audit_log::table)
+            .values(&new_entry)
+            .returning(AuditEntry::as_returning())
+            .get_result(&mut connection)?)
+    }
+
+    pub fn get_all(
+        &self,
+        filter: &AuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AuditEntry>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::filtered(filter)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let entries = Self::filtered(filter)
+            .order((audit_log::created_at.desc(), audit_log::id.desc()))
+            .limit(limit)
+            .offset(offset)
+            .select(AuditEntry::as_select())
+            .load(&mut connection)?;
+
+        Ok((entries, total))
+    }
+
+    fn filtered(filter: &AuditFilter) -> audit_log::BoxedQuery<'_, Pg> {
+        let mut query = audit_log::table.into_boxed();
+        if let Some(entity_type) = filter.entity_type {
+            query = query.filter(audit_log::entity_type.eq(entity_type));
+        }
+        if let Some(entity_id) = &filter.entity_id {
+            query = query.filter(audit_log::entity_id.eq(entity_id));
+        }
+        if let Some(user_id) = filter.user_id {
+            query = query.filter(audit_log::user_id.eq(user_id));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(audit_log::created_at.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(audit_log::created_at.lt(to));
+        }
+        query
+    }
+}
@@ -1,11 +1,21 @@
-use diesel::prelude::*;
+use chrono::{Days, NaiveDate};
+use diesel::{pg::Pg, prelude::*};
 use uuid::Uuid;
 
+use super::single_result;
 use crate::{
     db::PostgresPool,
     error::AppError,
-    models::document::{Document, NewDocument},
-    schema::documents::{self, teacher_id},
+    models::{
+        document::{
+            Document, DocumentCategory, DocumentFilter, DocumentOwner, NewDocument, UpdateDocument,
+        },
+        teacher::Teacher,
+    },
+    schema::{
+        documents::{self, category, checksum, teacher_id, valid_until},
+        lessons, student_groups, students, teachers,
+    },
 };
 
 #[derive(Clone)]
@@ -32,13 +42,121 @@ impl DocumentRepository {
             .first::<Document>(&mut connection)?)
     }
 
-    pub fn get_by_teacher_id(&self, id: i32) -> Result<Vec<Document>, AppError> {
+    /// Документы записи `owner`. Срок действия в `filter` отсчитывается от даты `today`.
+    pub fn get_by_owner(
+        &self,
+        owner: DocumentOwner,
+        filter: &DocumentFilter,
+        today: NaiveDate,
+    ) -> Result<Vec<Document>, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(documents::table
-            .filter(teacher_id.eq(id))
+        Ok(Self::owned_by(Self::filtered(filter, today), owner)
+            .order((documents::uploaded_at, documents::id))
             .load::<Document>(&mut connection)?)
     }
 
+    fn owned_by(
+        query: documents::BoxedQuery<'_, Pg>,
+        owner: DocumentOwner,
+    ) -> documents::BoxedQuery<'_, Pg> {
+        match owner {
+            DocumentOwner::Teacher(id) => query.filter(teacher_id.eq(id)),
+            DocumentOwner::Student(id) => query.filter(documents::student_id.eq(id)),
+            DocumentOwner::StudentGroup(id) => query.filter(documents::student_group_id.eq(id)),
+            DocumentOwner::Lesson(id) => query.filter(documents::lesson_id.eq(id)),
+        }
+    }
+
+    /// ID преподавателя, ответственного за неудаленную запись `owner`: самого преподавателя,
+    /// преподавателя группы ученика, группы или урока. Если записи нет, возвращается 404.
+    pub fn get_owner_teacher_id(&self, owner: DocumentOwner) -> Result<Option<i32>, AppError> {
+        let mut connection = self.pool.get()?;
+        let teacher_ids: Vec<Option<i32>> = match owner {
+            DocumentOwner::Teacher(id) => teachers::table
+                .find(id)
+                .filter(teachers::deleted_at.is_null())
+                .select(teachers::id.nullable())
+                .load(&mut connection)?,
+            DocumentOwner::Student(id) => students::table
+                .find(id)
+                .filter(students::deleted_at.is_null())
+                .left_join(student_groups::table)
+                .select(student_groups::teacher_id.nullable())
+                .load(&mut connection)?,
+            DocumentOwner::StudentGroup(id) => student_groups::table
+                .find(id)
+                .filter(student_groups::deleted_at.is_null())
+                .select(student_groups::teacher_id)
+                .load(&mut connection)?,
+            DocumentOwner::Lesson(id) => lessons::table
+                .find(id)
+                .filter(lessons::deleted_at.is_null())
+                .left_join(student_groups::table)
+                .select(student_groups::teacher_id.nullable())
+                .load(&mut connection)?,
+        };
+        single_result(teacher_ids)
+    }
+
+    fn filtered(filter: &DocumentFilter, today: NaiveDate) -> documents::BoxedQuery<'_, Pg> {
+        let mut query = documents::table.into_boxed();
+        if let Some(document_category) = filter.category {
+            query = query.filter(category.eq(document_category));
+        }
+        match filter.expired {
+            Some(true) => query = query.filter(valid_until.lt(today)),
+            Some(false) => {
+                query = query.filter(valid_until.is_null().or(valid_until.ge(today)));
+            }
+            None => {}
+        }
+        if let Some(days) = filter.expires_within_days {
+            let last_day = today
+                .checked_add_days(Days::new(days as u64))
+                .unwrap_or(NaiveDate::MAX);
+            query = query.filter(valid_until.between(today, last_day));
+        }
+        query
+    }
+
+    /// Неудаленные преподаватели вместе с их документами указанных видов. Преподаватель без
+    /// таких документов возвращается один раз с `None`.
+    pub fn get_teachers_with_documents(
+        &self,
+        categories: &[DocumentCategory],
+    ) -> Result<Vec<(Teacher, Option<Document>)>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(teachers::table
+            .left_join(
+                documents::table.on(teacher_id
+                    .eq(teachers::id.nullable())
+                    .and(category.eq_any(categories))),
+            )
+            .filter(teachers::deleted_at.is_null())
+            .order((teachers::name, teachers::id))
+            .select((Teacher::as_select(), Option::<Document>::as_select()))
+            .load::<(Teacher, Option<Document>)>(&mut connection)?)
+    }
+
+    pub fn update(&self, document_id: Uuid, changes: UpdateDocument) -> Result<Document, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(documents::table.find(document_id))
+            .set(changes)
+            .get_result::<Document>(&mut connection)?)
+    }
+
+    pub fn find_by_checksum(
+        &self,
+        owner: DocumentOwner,
+        document_checksum: &str,
+    ) -> Result<Option<Document>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(Self::owned_by(documents::table.into_boxed(), owner)
+            .filter(checksum.eq(document_checksum))
+            .first::<Document>(&mut connection)
+            .optional()?)
+    }
+
     pub fn delete(&self, document_id: Uuid) -> Result<usize, AppError> {
         let mut connection = self.pool.get()?;
         Ok(diesel::delete(documents::table.find(document_id)).execute(&mut connection)?)
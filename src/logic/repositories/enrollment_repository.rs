@@ -0,0 +1,108 @@
+use chrono::NaiveDate;
+use diesel::{pg::Pg, prelude::*, sql_types::Integer};
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::{
+        enrollment::{Enrollment, EnrollmentWithGroup, NewEnrollment},
+        student_group::StudentGroup,
+    },
+    schema::{enrollments, student_groups},
+};
+
+#[derive(Clone)]
+pub struct EnrollmentRepository {
+    pool: PostgresPool,
+}
+
+impl EnrollmentRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn get_by_student_id(&self, student_id: i32) -> Result<Vec<EnrollmentWithGroup>, AppError> {
+        let mut connection = self.pool.get()?;
+        let results = enrollments::table
+            .inner_join(student_groups::table)
+            .filter(enrollments::student_id.eq(student_id))
+            .order((enrollments::start_date, enrollments::id))
+            .select((Enrollment::as_select(), StudentGroup::as_select()))
+            .load::<(Enrollment, StudentGroup)>(&mut connection)?
+            .into_iter()
+            .map(|(enrollment, student_group)| EnrollmentWithGroup {
+                enrollment,
+                student_group,
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Переводит ученика в группу `student_group_id` с даты `date` в рамках транзакции
+    /// `connection`: текущее зачисление закрывается этой датой, новое открывается с нее же.
+    /// Без группы ученик только отчисляется.
+    pub(super) fn transfer(
+        connection: &mut PgConnection,
+        student_id: i32,
+        student_group_id: Option<i32>,
+        date: NaiveDate,
+    ) -> Result<(), AppError> {
+        // Зачисление, начавшееся в тот же день, не оставляет истории и просто удаляется
+        diesel::delete(
+            enrollments::table
+                .filter(enrollments::student_id.eq(student_id))
+                .filter(enrollments::end_date.is_null())
+                .filter(enrollments::start_date.ge(date)),
+        )
+        .execute(connection)?;
+        diesel::update(
+            enrollments::table
+                .filter(enrollments::student_id.eq(student_id))
+                .filter(enrollments::end_date.is_null()),
+        )
+        .set(enrollments::end_date.eq(Some(date)))
+        .execute(connection)?;
+
+        if let Some(student_group_id) = student_group_id {
+            diesel::insert_into(enrollments::table)
+                .values(NewEnrollment {
+                    student_id,
+                    student_group_id,
+                    start_date: date,
+                })
+                .execute(connection)?;
+        }
+        Ok(())
+    }
+
+    /// Числился ли ученик в группе `student_group_id` на дату `date`.
+    pub fn is_enrolled(
+        &self,
+        student_id: i32,
+        student_group_id: i32,
+        date: NaiveDate,
+    ) -> Result<bool, AppError> {
+        let mut connection = self.pool.get()?;
+        let query = Self::enrolled_student_ids(student_group_id, date)
+            .filter(enrollments::student_id.eq(student_id));
+        Ok(diesel::select(diesel::dsl::exists(query)).get_result(&mut connection)?)
+    }
+
+    /// Подзапрос ID учеников, которые числились в группе `student_group_id` на дату `date`.
+    pub(super) fn enrolled_student_ids<'a>(
+        student_group_id: i32,
+        date: NaiveDate,
+    ) -> enrollments::BoxedQuery<'a, Pg, Integer> {
+        enrollments::table
+            .filter(enrollments::student_group_id.eq(student_group_id))
+            .filter(enrollments::start_date.le(date))
+            .filter(
+                enrollments::end_date
+                    .is_null()
+                    .or(enrollments::end_date.gt(date)),
+            )
+            .select(enrollments::student_id)
+            .into_boxed()
+    }
+}
@@ -1,14 +1,16 @@
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{pg::Pg, prelude::*};
 
 use crate::{
     db::PostgresPool,
     error::AppError,
     models::{
-        lesson::{Lesson, LessonWithRelations, NewLesson, UpdateLesson},
+        lesson::{Lesson, LessonFilter, LessonWithRelations, NewLesson, UpdateLesson},
+        room::Room,
         student_group::StudentGroup,
     },
     schema::lessons::{self},
-    schema::student_groups,
+    schema::{rooms, student_groups},
 };
 
 use super::single_result;
@@ -25,46 +27,315 @@ impl LessonRepository {
 
     pub fn create(&self, new_lesson: NewLesson) -> Result<LessonWithRelations, AppError> {
         let mut connection = self.pool.get()?;
-        let lesson_id = diesel::insert_into(lessons::table)
-            .values(&new_lesson)
-            .returning(lessons::id)
-            .get_result::<i32>(&mut connection)?;
+        let lesson_id = Self::insert(&mut connection, &new_lesson)?;
+
+        self.get(lesson_id, false)
+    }
+
+    /// Выполняет `f` в одной транзакции: если `f` возвращает ошибку, все ее изменения
+    /// отменяются.
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce(&mut PgConnection) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction(|connection| f(connection))
+    }
 
-        self.get(lesson_id)
+    /// Сохраняет урок в рамках транзакции `connection` и возвращает его ID.
+    pub fn insert(connection: &mut PgConnection, new_lesson: &NewLesson) -> Result<i32, AppError> {
+        Ok(diesel::insert_into(lessons::table)
+            .values(new_lesson)
+            .returning(lessons::id)
+            .get_result(connection)?)
     }
 
-    pub fn get(&self, lesson_id: i32) -> Result<LessonWithRelations, AppError> {
-        let query = lessons::table
-            .filter(lessons::id.eq(lesson_id))
-            .into_boxed();
+    pub fn get(
+        &self,
+        lesson_id: i32,
+        include_deleted: bool,
+    ) -> Result<LessonWithRelations, AppError> {
+        let query = Self::visible(include_deleted).filter(lessons::id.eq(lesson_id));
 
         single_result(self.load_with_relations(query)?)
     }
 
+    /// ID преподавателя группы, у которой проходит урок. `None`, если у урока нет группы
+    /// или у группы нет преподавателя.
+    pub fn get_teacher_id(&self, lesson_id: i32) -> Result<Option<i32>, AppError> {
+        let mut connection = self.pool.get()?;
+        let teacher_ids: Vec<Option<i32>> = lessons::table
+            .find(lesson_id)
+            .filter(lessons::deleted_at.is_null())
+            .left_join(student_groups::table)
+            .select(student_groups::teacher_id.nullable())
+            .load(&mut connection)?;
+        single_result(teacher_ids)
+    }
+
     pub fn get_lessons_by_group_id(&self, id: i32) -> Result<Vec<Lesson>, AppError> {
         let mut connection = self.pool.get()?;
         Ok(lessons::table
             .filter(lessons::student_group_id.eq(id))
+            .filter(lessons::deleted_at.is_null())
+            .order(lessons::starts_at)
             .select(Lesson::as_select())
             .load(&mut connection)?)
     }
 
-    pub fn update(
+    pub fn get_lessons_by_series_id(&self, series_id: i32) -> Result<Vec<Lesson>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(lessons::table
+            .filter(lessons::lesson_series_id.eq(series_id))
+            .filter(lessons::deleted_at.is_null())
+            .order((lessons::series_date, lessons::starts_at))
+            .select(Lesson::as_select())
+            .load(&mut connection)?)
+    }
+
+    /// Уроки группы и других групп ее преподавателя, пересекающиеся с промежутком
+    /// `[from, to)`. Уроки из `exclude_lesson_ids` не учитываются.
+    pub fn get_overlapping_for_group(
         &self,
-        lesson_id: i32,
-        updated_lesson: UpdateLesson,
-    ) -> Result<LessonWithRelations, AppError> {
+        student_group_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<Vec<LessonWithRelations>, AppError> {
         let mut connection = self.pool.get()?;
-        diesel::update(lessons::table.find(lesson_id))
-            .set(&updated_lesson)
-            .execute(&mut connection)?;
+        let group_ids = Self::teacher_group_ids(&mut connection, student_group_id)?;
+        let query = Self::overlapping(from, to, exclude_lesson_ids)
+            .filter(lessons::student_group_id.eq_any(group_ids));
+
+        self.load_with_relations(query)
+    }
+
+    /// Уроки в кабинете, пересекающиеся с промежутком `[from, to)`.
+    pub fn get_overlapping_in_room(
+        &self,
+        room_id: i32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<Vec<LessonWithRelations>, AppError> {
+        let query =
+            Self::overlapping(from, to, exclude_lesson_ids).filter(lessons::room_id.eq(room_id));
+
+        self.load_with_relations(query)
+    }
 
-        self.get(lesson_id)
+    /// Есть ли в рамках транзакции `connection` уроки, пересекающиеся с промежутком
+    /// `[from, to)`: уроки той же группы, других групп ее преподавателя или в том же
+    /// кабинете. Уроки из `exclude_lesson_ids` не учитываются.
+    ///
+    /// Группы преподавателя блокируются до конца транзакции: ограничения базы не видят
+    /// уроки разных групп одного преподавателя, поэтому такие уроки сохраняются по очереди.
+    pub fn has_overlapping(
+        connection: &mut PgConnection,
+        (from, to): (DateTime<Utc>, DateTime<Utc>),
+        student_group_id: Option<i32>,
+        room_id: Option<i32>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<bool, AppError> {
+        let group_ids = match student_group_id {
+            Some(student_group_id) => Self::lock_teacher_group_ids(connection, student_group_id)?,
+            None => Vec::new(),
+        };
+        let query = Self::overlapping(from, to, exclude_lesson_ids).filter(
+            lessons::student_group_id
+                .eq_any(group_ids)
+                .or(lessons::room_id.eq(room_id)),
+        );
+        Ok(diesel::select(diesel::dsl::exists(query)).get_result(connection)?)
     }
 
-    pub fn delete(&self, lesson_id: i32) -> Result<usize, AppError> {
+    /// Все уроки, пересекающиеся с промежутком `[from, to)`.
+    pub fn get_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<LessonWithRelations>, AppError> {
+        let query = Self::visible(false)
+            .filter(lessons::starts_at.lt(to))
+            .filter(lessons::ends_at.gt(from))
+            .order((lessons::starts_at, lessons::id));
+
+        self.load_with_relations(query)
+    }
+
+    pub fn get_all(
+        &self,
+        filter: &LessonFilter,
+        limit: i64,
+        offset: i64,
+        include_deleted: bool,
+    ) -> Result<(Vec<LessonWithRelations>, i64), AppError> {
         let mut connection = self.pool.get()?;
-        Ok(diesel::delete(lessons::table.find(lesson_id)).execute(&mut connection)?)
+        let total = Self::filtered(filter, include_deleted)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let query = Self::filtered(filter, include_deleted)
+            .order((lessons::starts_at, lessons::id))
+            .limit(limit)
+            .offset(offset);
+
+        Ok((self.load_with_relations(query)?, total))
+    }
+
+    /// Изменяет урок в рамках транзакции `connection`.
+    pub fn update(
+        connection: &mut PgConnection,
+        lesson_id: i32,
+        updated_lesson: &UpdateLesson,
+    ) -> Result<(), AppError> {
+        if updated_lesson.is_empty() {
+            return Ok(());
+        }
+        diesel::update(
+            lessons::table
+                .find(lesson_id)
+                .filter(lessons::deleted_at.is_null()),
+        )
+        .set(updated_lesson)
+        .execute(connection)?;
+        Ok(())
+    }
+
+    /// Помечает урок удаленным в рамках транзакции `connection`. Отметки посещаемости
+    /// урока сохраняются.
+    pub fn delete(connection: &mut PgConnection, lesson_id: i32) -> Result<usize, AppError> {
+        Ok(diesel::update(
+            lessons::table
+                .find(lesson_id)
+                .filter(lessons::deleted_at.is_null()),
+        )
+        .set(lessons::deleted_at.eq(Utc::now()))
+        .execute(connection)?)
+    }
+
+    /// Помечает удаленными уроки серии с датой по правилу серии не раньше `from` в рамках
+    /// транзакции `connection` и возвращает их.
+    pub fn delete_series_lessons_from(
+        connection: &mut PgConnection,
+        series_id: i32,
+        from: NaiveDate,
+    ) -> Result<Vec<Lesson>, AppError> {
+        Ok(diesel::update(
+            lessons::table
+                .filter(lessons::lesson_series_id.eq(series_id))
+                .filter(lessons::series_date.ge(from))
+                .filter(lessons::deleted_at.is_null()),
+        )
+        .set(lessons::deleted_at.eq(Utc::now()))
+        .returning(Lesson::as_returning())
+        .get_results(connection)?)
+    }
+
+    /// Восстанавливает удаленный урок в рамках транзакции `connection`.
+    pub fn restore(connection: &mut PgConnection, lesson_id: i32) -> Result<(), AppError> {
+        diesel::update(
+            lessons::table
+                .find(lesson_id)
+                .filter(lessons::deleted_at.is_not_null()),
+        )
+        .set(lessons::deleted_at.eq(None::<DateTime<Utc>>))
+        .returning(lessons::id)
+        .get_result::<i32>(connection)?;
+        Ok(())
+    }
+
+    /// ID группы и других групп ее преподавателя.
+    fn teacher_group_ids(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+    ) -> Result<Vec<i32>, AppError> {
+        match Self::group_teacher_id(connection, student_group_id)? {
+            Some(teacher_id) => Ok(student_groups::table
+                .filter(student_groups::teacher_id.eq(teacher_id))
+                .filter(student_groups::deleted_at.is_null())
+                .select(student_groups::id)
+                .load(connection)?),
+            None => Ok(vec![student_group_id]),
+        }
+    }
+
+    /// То же, что [`Self::teacher_group_ids`], но строки групп преподавателя блокируются
+    /// (`SELECT ... FOR UPDATE`) до конца транзакции `connection`.
+    fn lock_teacher_group_ids(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+    ) -> Result<Vec<i32>, AppError> {
+        match Self::group_teacher_id(connection, student_group_id)? {
+            Some(teacher_id) => Ok(student_groups::table
+                .filter(student_groups::teacher_id.eq(teacher_id))
+                .filter(student_groups::deleted_at.is_null())
+                .order(student_groups::id)
+                .select(student_groups::id)
+                .for_update()
+                .load(connection)?),
+            None => Ok(vec![student_group_id]),
+        }
+    }
+
+    fn group_teacher_id(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+    ) -> Result<Option<i32>, AppError> {
+        Ok(student_groups::table
+            .find(student_group_id)
+            .select(student_groups::teacher_id)
+            .first::<Option<i32>>(connection)
+            .optional()?
+            .flatten())
+    }
+
+    /// Неудаленные уроки, пересекающиеся с промежутком `[from, to)`, кроме
+    /// `exclude_lesson_ids`.
+    fn overlapping(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        exclude_lesson_ids: &[i32],
+    ) -> lessons::BoxedQuery<'_, Pg> {
+        let mut query = Self::visible(false)
+            .filter(lessons::starts_at.lt(to))
+            .filter(lessons::ends_at.gt(from))
+            .order((lessons::starts_at, lessons::id));
+        if !exclude_lesson_ids.is_empty() {
+            query = query.filter(lessons::id.ne_all(exclude_lesson_ids));
+        }
+        query
+    }
+
+    fn visible<'a>(include_deleted: bool) -> lessons::BoxedQuery<'a, Pg> {
+        let query = lessons::table.into_boxed();
+        if include_deleted {
+            query
+        } else {
+            query.filter(lessons::deleted_at.is_null())
+        }
+    }
+
+    fn filtered(filter: &LessonFilter, include_deleted: bool) -> lessons::BoxedQuery<'_, Pg> {
+        let mut query = Self::visible(include_deleted);
+
+        if let Some(group_id) = filter.student_group_id {
+            query = query.filter(lessons::student_group_id.eq(group_id));
+        }
+        if let Some(series_id) = filter.lesson_series_id {
+            query = query.filter(lessons::lesson_series_id.eq(series_id));
+        }
+        if let Some(room_id) = filter.room_id {
+            query = query.filter(lessons::room_id.eq(room_id));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(lessons::ends_at.gt(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(lessons::starts_at.lt(to));
+        }
+
+        query
     }
 
     fn load_with_relations(
@@ -74,12 +345,18 @@ impl LessonRepository {
         let mut connection = self.pool.get()?;
         let results = query
             .left_join(student_groups::table)
-            .select((Lesson::as_select(), Option::<StudentGroup>::as_select()))
-            .load::<(Lesson, Option<StudentGroup>)>(&mut connection)?
+            .left_join(rooms::table)
+            .select((
+                Lesson::as_select(),
+                Option::<StudentGroup>::as_select(),
+                Option::<Room>::as_select(),
+            ))
+            .load::<(Lesson, Option<StudentGroup>, Option<Room>)>(&mut connection)?
             .into_iter()
-            .map(|(lesson, student_group)| LessonWithRelations {
+            .map(|(lesson, student_group, room)| LessonWithRelations {
                 lesson,
                 student_group,
+                room,
             })
             .collect();
 
@@ -0,0 +1,109 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::lesson_series::{LessonSeries, NewLessonSeries},
+    schema::{lesson_series, lesson_series_cancellations},
+};
+
+use super::single_result;
+
+#[derive(Clone)]
+pub struct LessonSeriesRepository {
+    pool: PostgresPool,
+}
+
+impl LessonSeriesRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    /// Сохраняет серию в рамках транзакции `connection`.
+    pub fn insert(
+        connection: &mut PgConnection,
+        new_series: &NewLessonSeries,
+    ) -> Result<LessonSeries, AppError> {
+        Ok(diesel::insert_into(lesson_series::table)
+            .values(new_series)
+            .returning(LessonSeries::as_returning())
+            .get_result(connection)?)
+    }
+
+    pub fn get(&self, series_id: i32) -> Result<LessonSeries, AppError> {
+        let mut connection = self.pool.get()?;
+        single_result(
+            lesson_series::table
+                .find(series_id)
+                .select(LessonSeries::as_select())
+                .load(&mut connection)?,
+        )
+    }
+
+    /// Обрезает серию в рамках транзакции `connection` так, чтобы ее последнее занятие было
+    /// не позже `until_date`.
+    pub fn truncate(
+        connection: &mut PgConnection,
+        series_id: i32,
+        until_date: NaiveDate,
+    ) -> Result<LessonSeries, AppError> {
+        Ok(diesel::update(lesson_series::table.find(series_id))
+            .set((
+                lesson_series::until_date.eq(Some(until_date)),
+                lesson_series::occurrence_count.eq(None::<i32>),
+            ))
+            .returning(LessonSeries::as_returning())
+            .get_result(connection)?)
+    }
+
+    /// Удаляет серию в рамках транзакции `connection`. Ее уроки остаются без серии.
+    pub fn delete(connection: &mut PgConnection, series_id: i32) -> Result<usize, AppError> {
+        Ok(diesel::delete(lesson_series::table.find(series_id)).execute(connection)?)
+    }
+
+    pub fn get_cancelled_dates(&self, series_id: i32) -> Result<Vec<NaiveDate>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(lesson_series_cancellations::table
+            .filter(lesson_series_cancellations::lesson_series_id.eq(series_id))
+            .select(lesson_series_cancellations::occurrence_date)
+            .order(lesson_series_cancellations::occurrence_date)
+            .load(&mut connection)?)
+    }
+
+    /// Снимает отмену с даты `date` в рамках транзакции `connection`.
+    pub fn remove_cancelled_date(
+        connection: &mut PgConnection,
+        series_id: i32,
+        date: NaiveDate,
+    ) -> Result<usize, AppError> {
+        Ok(diesel::delete(
+            lesson_series_cancellations::table
+                .filter(lesson_series_cancellations::lesson_series_id.eq(series_id))
+                .filter(lesson_series_cancellations::occurrence_date.eq(date)),
+        )
+        .execute(connection)?)
+    }
+
+    /// Запоминает даты `dates` как отмененные в рамках транзакции `connection`.
+    pub fn add_cancelled_dates(
+        connection: &mut PgConnection,
+        series_id: i32,
+        dates: &[NaiveDate],
+    ) -> Result<usize, AppError> {
+        let rows: Vec<_> = dates
+            .iter()
+            .map(|date| {
+                (
+                    lesson_series_cancellations::lesson_series_id.eq(series_id),
+                    lesson_series_cancellations::occurrence_date.eq(*date),
+                )
+            })
+            .collect();
+
+        Ok(diesel::insert_into(lesson_series_cancellations::table)
+            .values(&rows)
+            .on_conflict_do_nothing()
+            .execute(connection)?)
+    }
+}
@@ -0,0 +1,105 @@
+use chrono::TimeDelta;
+use tower_sessions_redis_store::fred::prelude::{Expiration, KeysInterface};
+
+use crate::{db::RedisPool, error::AppError, models::login_lockout::LockoutTarget};
+
+/// Счетчики неудачных попыток входа и блокировки в Redis. Ключи живут ограниченное
+/// время и удаляются самим Redis по истечении срока.
+#[derive(Clone)]
+pub struct LoginAttemptRepository {
+    redis_pool: RedisPool,
+}
+
+impl LoginAttemptRepository {
+    pub fn new(redis_pool: RedisPool) -> Self {
+        Self { redis_pool }
+    }
+
+    /// Увеличивает счетчик неудачных попыток и продлевает его жизнь на `ttl`.
+    /// Возвращает новое значение счетчика.
+    pub async fn register_failure(
+        &self,
+        target: LockoutTarget,
+        subject: &str,
+        ttl: TimeDelta,
+    ) -> Result<i64, AppError> {
+        let key = Self::failures_key(target, subject);
+        let failures = self.redis_pool.incr::<i64, _>(&key).await?;
+        self.redis_pool
+            .expire::<(), _>(&key, ttl.num_seconds(), None)
+            .await?;
+        Ok(failures)
+    }
+
+    pub async fn get_failures(
+        &self,
+        target: LockoutTarget,
+        subject: &str,
+    ) -> Result<i64, AppError> {
+        Ok(self
+            .redis_pool
+            .get::<Option<i64>, _>(Self::failures_key(target, subject))
+            .await?
+            .unwrap_or(0))
+    }
+
+    /// Блокирует вход на `duration` и продлевает жизнь счетчика попыток на `failures_ttl`.
+    pub async fn lock(
+        &self,
+        target: LockoutTarget,
+        subject: &str,
+        duration: TimeDelta,
+        failures_ttl: TimeDelta,
+    ) -> Result<(), AppError> {
+        self.redis_pool
+            .expire::<(), _>(
+                Self::failures_key(target, subject),
+                failures_ttl.num_seconds(),
+                None,
+            )
+            .await?;
+        self.redis_pool
+            .set::<(), _, _>(
+                Self::lock_key(target, subject),
+                1,
+                Some(Expiration::EX(duration.num_seconds())),
+                None,
+                false,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Оставшийся срок блокировки или `None`, если блокировки нет.
+    pub async fn get_lock_ttl(
+        &self,
+        target: LockoutTarget,
+        subject: &str,
+    ) -> Result<Option<TimeDelta>, AppError> {
+        let ttl = self
+            .redis_pool
+            .ttl::<i64, _>(Self::lock_key(target, subject))
+            .await?;
+        // TTL возвращает -2 для отсутствующего ключа и -1 для ключа без срока жизни
+        Ok((ttl >= 0).then(|| TimeDelta::seconds(ttl)))
+    }
+
+    /// Удаляет счетчик и блокировку.
+    pub async fn clear(&self, target: LockoutTarget, subject: &str) -> Result<(), AppError> {
+        self.redis_pool
+            .del::<(), _>(vec![
+                Self::failures_key(target, subject),
+                Self::lock_key(target, subject),
+            ])
+            .await?;
+        Ok(())
+    }
+
+    fn failures_key(target: LockoutTarget, subject: &str) -> String {
+        format!("login:failures:{}:{}", target, subject)
+    }
+
+    fn lock_key(target: LockoutTarget, subject: &str) -> String {
+        format!("login:lock:{}:{}", target, subject)
+    }
+}
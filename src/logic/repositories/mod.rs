@@ -1,13 +1,32 @@
 use crate::error::AppError;
 
+pub(super) mod api_token_repository;
 pub(super) mod attendance_repository;
+pub(super) mod audit_repository;
 pub(super) mod document_repository;
+pub(super) mod enrollment_repository;
 pub(super) mod lesson_repository;
+pub(super) mod lesson_series_repository;
+pub(super) mod login_attempt_repository;
 pub(super) mod parent_repository;
+pub(super) mod password_reset_repository;
+pub(super) mod retention_repository;
+pub(super) mod room_repository;
 pub(super) mod student_group_repository;
 pub(super) mod student_repository;
 pub(super) mod teacher_repository;
+pub(super) mod timetable_repository;
 pub(super) mod user_repository;
+pub(super) mod waitlist_repository;
+
+/// Шаблон для `ILIKE`, ищущий подстроку буквально: спецсимволы `%` и `_` экранируются.
+pub(super) fn contains_pattern(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
 
 pub(super) fn single_result<T>(vec: Vec<T>) -> Result<T, AppError> {
     vec.into_iter()
@@ -1,4 +1,5 @@
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
 
 use crate::{
     db::PostgresPool,
@@ -24,22 +25,75 @@ impl ParentRepository {
             .get_result::<Parent>(&mut connection)?)
     }
 
-    pub fn get(&self, parent_id: i32) -> Result<Parent, AppError> {
+    pub fn get(&self, parent_id: i32, include_deleted: bool) -> Result<Parent, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(parents::table
-            .find(parent_id)
+        Ok(Self::visible(include_deleted)
+            .filter(parents::id.eq(parent_id))
             .first::<Parent>(&mut connection)?)
     }
 
+    pub fn get_all(
+        &self,
+        limit: i64,
+        offset: i64,
+        include_deleted: bool,
+    ) -> Result<(Vec<Parent>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::visible(include_deleted)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+        let parents = Self::visible(include_deleted)
+            .order(parents::id)
+            .limit(limit)
+            .offset(offset)
+            .load::<Parent>(&mut connection)?;
+
+        Ok((parents, total))
+    }
+
     pub fn update(&self, parent_id: i32, updated_parent: UpdateParent) -> Result<Parent, AppError> {
+        if updated_parent.is_empty() {
+            return self.get(parent_id, false);
+        }
         let mut connection = self.pool.get()?;
-        Ok(diesel::update(parents::table.find(parent_id))
-            .set(&updated_parent)
-            .get_result::<Parent>(&mut connection)?)
+        Ok(diesel::update(
+            parents::table
+                .find(parent_id)
+                .filter(parents::deleted_at.is_null()),
+        )
+        .set(&updated_parent)
+        .get_result::<Parent>(&mut connection)?)
     }
 
+    /// Помечает родителя удаленным. Связи с учениками сохраняются.
     pub fn delete(&self, parent_id: i32) -> Result<usize, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(diesel::delete(parents::table.find(parent_id)).execute(&mut connection)?)
+        Ok(diesel::update(
+            parents::table
+                .find(parent_id)
+                .filter(parents::deleted_at.is_null()),
+        )
+        .set(parents::deleted_at.eq(Utc::now()))
+        .execute(&mut connection)?)
+    }
+
+    pub fn restore(&self, parent_id: i32) -> Result<Parent, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(
+            parents::table
+                .find(parent_id)
+                .filter(parents::deleted_at.is_not_null()),
+        )
+        .set(parents::deleted_at.eq(None::<DateTime<Utc>>))
+        .get_result::<Parent>(&mut connection)?)
+    }
+
+    fn visible<'a>(include_deleted: bool) -> parents::BoxedQuery<'a, Pg> {
+        let query = parents::table.into_boxed();
+        if include_deleted {
+            query
+        } else {
+            query.filter(parents::deleted_at.is_null())
+        }
     }
 }
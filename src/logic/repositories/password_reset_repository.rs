@@ -0,0 +1,72 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::{
+        password_reset::{NewPasswordResetToken, PasswordResetToken},
+        user::User,
+    },
+    schema::{password_reset_tokens, users},
+};
+
+#[derive(Clone)]
+pub struct PasswordResetRepository {
+    pool: PostgresPool,
+}
+
+impl PasswordResetRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    /// Сохраняет новый токен пользователя. Неиспользованные токены, выданные ему раньше,
+    /// удаляются, чтобы действовал только последний.
+    pub fn create(&self, new_token: NewPasswordResetToken) -> Result<PasswordResetToken, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            diesel::delete(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::user_id.eq(new_token.user_id))
+                    .filter(password_reset_tokens::used_at.is_null()),
+            )
+            .execute(connection)?;
+            Ok(diesel::insert_into(password_reset_tokens::table)
+                .values(&new_token)
+                .returning(PasswordResetToken::as_returning())
+                .get_result(connection)?)
+        })
+    }
+
+    /// Отмечает токен использованным и устанавливает пользователю новый пароль. Если
+    /// токена нет, он уже использован или истек, возвращает `None`.
+    pub fn reset_password(
+        &self,
+        token_hash: &str,
+        password_hash: &str,
+    ) -> Result<Option<(User, User)>, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            let now = Utc::now();
+            let user_id = diesel::update(
+                password_reset_tokens::table
+                    .filter(password_reset_tokens::token_hash.eq(token_hash))
+                    .filter(password_reset_tokens::used_at.is_null())
+                    .filter(password_reset_tokens::expires_at.gt(now)),
+            )
+            .set(password_reset_tokens::used_at.eq(now))
+            .returning(password_reset_tokens::user_id)
+            .get_result::<i32>(connection)
+            .optional()?;
+            let Some(user_id) = user_id else {
+                return Ok(None);
+            };
+            let user = users::table.find(user_id).get_result::<User>(connection)?;
+            let updated_user = diesel::update(users::table.find(user_id))
+                .set(users::password.eq(password_hash))
+                .get_result::<User>(connection)?;
+            Ok(Some((user, updated_user)))
+        })
+    }
+}
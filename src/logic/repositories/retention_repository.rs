@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    schema::{attendances, enrollments, lessons, parents, student_groups, students, teachers},
+};
+
+#[derive(Clone)]
+pub struct RetentionRepository {
+    pool: PostgresPool,
+}
+
+/// Окончательно удаленные записи. Для записей, к которым прикрепляются документы,
+/// возвращаются ID: их документы удалены из базы каскадно, а файлы нужно удалить из
+/// хранилища.
+pub struct PurgedRecords {
+    pub lesson_ids: Vec<i32>,
+    pub student_ids: Vec<i32>,
+    pub student_group_ids: Vec<i32>,
+    pub parents: usize,
+    pub teacher_ids: Vec<i32>,
+}
+
+impl PurgedRecords {
+    pub fn total(&self) -> usize {
+        self.lesson_ids.len()
+            + self.student_ids.len()
+            + self.student_group_ids.len()
+            + self.parents
+            + self.teacher_ids.len()
+    }
+}
+
+impl RetentionRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    /// Окончательно удаляет записи, помеченные удаленными раньше `cutoff`, вместе с
+    /// посещаемостью удаляемых уроков и учеников. Группы, в которых учились оставшиеся
+    /// ученики, не удаляются, пока есть эти ученики.
+    pub fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> Result<PurgedRecords, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            let lesson_ids = lessons::table
+                .filter(lessons::deleted_at.lt(cutoff))
+                .select(lessons::id)
+                .load::<i32>(connection)?;
+            diesel::delete(attendances::table.filter(attendances::lesson_id.eq_any(&lesson_ids)))
+                .execute(connection)?;
+            diesel::delete(lessons::table.filter(lessons::id.eq_any(&lesson_ids)))
+                .execute(connection)?;
+
+            let student_ids = students::table
+                .filter(students::deleted_at.lt(cutoff))
+                .select(students::id)
+                .load::<i32>(connection)?;
+            diesel::delete(attendances::table.filter(attendances::student_id.eq_any(&student_ids)))
+                .execute(connection)?;
+            diesel::delete(students::table.filter(students::id.eq_any(&student_ids)))
+                .execute(connection)?;
+
+            // Удаление группы каскадно удалило бы зачисления в нее, то есть историю
+            // обучения учеников, которые остаются в базе
+            let student_group_ids = diesel::delete(
+                student_groups::table
+                    .filter(student_groups::deleted_at.lt(cutoff))
+                    .filter(diesel::dsl::not(student_groups::id.eq_any(
+                        enrollments::table.select(enrollments::student_group_id),
+                    ))),
+            )
+            .returning(student_groups::id)
+            .get_results::<i32>(connection)?;
+            let parents = diesel::delete(parents::table.filter(parents::deleted_at.lt(cutoff)))
+                .execute(connection)?;
+            let teacher_ids =
+                diesel::delete(teachers::table.filter(teachers::deleted_at.lt(cutoff)))
+                    .returning(teachers::id)
+                    .get_results::<i32>(connection)?;
+
+            Ok(PurgedRecords {
+                lesson_ids,
+                student_ids,
+                student_group_ids,
+                parents,
+                teacher_ids,
+            })
+        })
+    }
+}
@@ -0,0 +1,134 @@
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::room::{NewRoom, Room, RoomFilter, UpdateRoom},
+    schema::{lessons, rooms},
+};
+
+use super::{contains_pattern, single_result};
+
+#[derive(Clone)]
+pub struct RoomRepository {
+    pool: PostgresPool,
+}
+
+impl RoomRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn create(&self, new_room: NewRoom) -> Result<Room, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::insert_into(rooms::table)
+            .values(&new_room)
+            .returning(Room::as_returning())
+            .get_result(&mut connection)?)
+    }
+
+    pub fn get(&self, room_id: i32) -> Result<Room, AppError> {
+        let mut connection = self.pool.get()?;
+        single_result(
+            rooms::table
+                .find(room_id)
+                .select(Room::as_select())
+                .load(&mut connection)?,
+        )
+    }
+
+    pub fn get_all(
+        &self,
+        filter: &RoomFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Room>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::filtered(filter)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let rooms = Self::filtered(filter)
+            .order(rooms::name)
+            .limit(limit)
+            .offset(offset)
+            .select(Room::as_select())
+            .load(&mut connection)?;
+
+        Ok((rooms, total))
+    }
+
+    /// Кабинеты с указанными ID или все кабинеты, если `room_ids` не задан.
+    pub fn get_many(&self, room_ids: Option<&[i32]>) -> Result<Vec<Room>, AppError> {
+        let mut connection = self.pool.get()?;
+        let mut query = rooms::table.into_boxed();
+        if let Some(room_ids) = room_ids {
+            query = query.filter(rooms::id.eq_any(room_ids));
+        }
+        Ok(query
+            .order(rooms::id)
+            .select(Room::as_select())
+            .load(&mut connection)?)
+    }
+
+    /// Кабинеты, подходящие под фильтр, в которых нет уроков, пересекающихся с `[from, to)`.
+    pub fn get_available(
+        &self,
+        filter: &RoomFilter,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<Room>, AppError> {
+        let mut connection = self.pool.get()?;
+        let busy_room_ids: Vec<i32> = lessons::table
+            .filter(lessons::deleted_at.is_null())
+            .filter(lessons::starts_at.lt(to))
+            .filter(lessons::ends_at.gt(from))
+            .filter(lessons::room_id.is_not_null())
+            .select(lessons::room_id.assume_not_null())
+            .distinct()
+            .load(&mut connection)?;
+
+        Ok(Self::filtered(filter)
+            .filter(rooms::id.ne_all(busy_room_ids))
+            .order(rooms::name)
+            .select(Room::as_select())
+            .load(&mut connection)?)
+    }
+
+    pub fn update(&self, room_id: i32, updated_room: UpdateRoom) -> Result<Room, AppError> {
+        if updated_room.is_empty() {
+            return self.get(room_id);
+        }
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(rooms::table.find(room_id))
+            .set(&updated_room)
+            .returning(Room::as_returning())
+            .get_result(&mut connection)?)
+    }
+
+    pub fn delete(&self, room_id: i32) -> Result<usize, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::delete(rooms::table.find(room_id)).execute(&mut connection)?)
+    }
+
+    fn filtered(filter: &RoomFilter) -> rooms::BoxedQuery<'_, Pg> {
+        let mut query = rooms::table.into_boxed();
+
+        if let Some(name) = &filter.name {
+            query = query.filter(rooms::name.ilike(contains_pattern(name)));
+        }
+        if let Some(building) = &filter.building {
+            query = query.filter(rooms::building.eq(building));
+        }
+        if let Some(min_capacity) = filter.min_capacity {
+            query = query.filter(rooms::capacity.ge(min_capacity));
+        }
+        let equipment = filter.equipment_tags();
+        if !equipment.is_empty() {
+            query = query.filter(rooms::equipment.contains(equipment));
+        }
+
+        query
+    }
+}
@@ -1,9 +1,11 @@
-use diesel::prelude::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{dsl::count_star, pg::Pg, prelude::*};
 
 use crate::{
     db::PostgresPool,
     error::AppError,
     models::{
+        student::StudentWithRelations,
         student_group::{
             NewStudentGroup, StudentGroup, StudentGroupWithRelations, UpdateStudentGroup,
         },
@@ -11,11 +13,14 @@ use crate::{
     },
     schema::{
         student_groups::{self},
-        teachers,
+        students, teachers,
     },
 };
 
-use super::single_result;
+use super::{
+    enrollment_repository::EnrollmentRepository, single_result,
+    student_repository::StudentRepository, waitlist_repository::WaitlistRepository,
+};
 
 #[derive(Clone)]
 pub struct StudentGroupRepository {
@@ -37,51 +42,223 @@ impl StudentGroupRepository {
             .returning(student_groups::id)
             .get_result::<i32>(&mut connection)?;
 
-        self.get(student_group_id)
+        self.get(student_group_id, false)
     }
 
-    pub fn get(&self, student_group_id: i32) -> Result<StudentGroupWithRelations, AppError> {
-        let query = student_groups::table
-            .filter(student_groups::id.eq(student_group_id))
-            .into_boxed();
+    pub fn get(
+        &self,
+        student_group_id: i32,
+        include_deleted: bool,
+    ) -> Result<StudentGroupWithRelations, AppError> {
+        let query = Self::visible(include_deleted).filter(student_groups::id.eq(student_group_id));
 
         single_result(self.load_with_relations(query)?)
     }
 
+    pub fn get_all(
+        &self,
+        limit: i64,
+        offset: i64,
+        include_deleted: bool,
+    ) -> Result<(Vec<StudentGroupWithRelations>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::visible(include_deleted)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let query = Self::visible(include_deleted)
+            .order(student_groups::id)
+            .limit(limit)
+            .offset(offset);
+
+        Ok((self.load_with_relations(query)?, total))
+    }
+
+    /// Обновляет группу. Если мест стало больше, на них зачисляются ученики из очереди
+    /// с даты `today`. Вместе с группой возвращаются зачисленные ученики в состоянии до
+    /// зачисления.
     pub fn update(
         &self,
         student_group_id: i32,
         updated_student_group: UpdateStudentGroup,
-    ) -> Result<StudentGroupWithRelations, AppError> {
+        today: NaiveDate,
+    ) -> Result<(StudentGroupWithRelations, Vec<StudentWithRelations>), AppError> {
+        if updated_student_group.is_empty() {
+            return Ok((self.get(student_group_id, false)?, Vec::new()));
+        }
         let mut connection = self.pool.get()?;
-        diesel::update(student_groups::table.find(student_group_id))
+        let promoted = connection.transaction::<_, AppError, _>(|connection| {
+            let updated = diesel::update(
+                student_groups::table
+                    .find(student_group_id)
+                    .filter(student_groups::deleted_at.is_null()),
+            )
             .set(&updated_student_group)
-            .execute(&mut connection)?;
+            .execute(connection)?;
+            if updated == 0 {
+                return Err(AppError::NotFound(format!(
+                    "Student group with ID {} not found",
+                    student_group_id
+                )));
+            }
+            if updated_student_group.free_spots.is_none() {
+                return Ok(Vec::new());
+            }
+            WaitlistRepository::promote(connection, student_group_id, today)
+        })?;
+
+        Ok((self.get(student_group_id, false)?, promoted))
+    }
+
+    /// Помечает группу удаленной. Ученики группы отчисляются с даты `today`, их места
+    /// возвращаются в `free_spots`, а очередь на зачисление удаляется. Возвращает
+    /// отчисленных учеников в состоянии до отчисления или `None`, если группа не найдена.
+    pub fn delete(
+        &self,
+        student_group_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<Vec<StudentWithRelations>>, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            let deleted = diesel::update(
+                student_groups::table
+                    .find(student_group_id)
+                    .filter(student_groups::deleted_at.is_null()),
+            )
+            .set(student_groups::deleted_at.eq(Utc::now()))
+            .execute(connection)?;
+            if deleted == 0 {
+                return Ok(None);
+            }
 
-        self.get(student_group_id)
+            WaitlistRepository::clear_group(connection, student_group_id)?;
+            let student_ids = students::table
+                .filter(students::student_group_id.eq(student_group_id))
+                .select(students::id)
+                .for_update()
+                .load::<i32>(connection)?;
+            let unenrolled = StudentRepository::get_by_ids(connection, &student_ids)?;
+            diesel::update(students::table.filter(students::id.eq_any(&student_ids)))
+                .set(students::student_group_id.eq(None::<i32>))
+                .execute(connection)?;
+            for student_id in &student_ids {
+                EnrollmentRepository::transfer(connection, *student_id, None, today)?;
+            }
+            diesel::update(student_groups::table.find(student_group_id))
+                .set(
+                    student_groups::free_spots
+                        .eq(student_groups::free_spots + student_ids.len() as i32),
+                )
+                .execute(connection)?;
+            Ok(Some(unenrolled))
+        })
     }
 
-    pub fn delete(&self, student_group_id: i32) -> Result<usize, AppError> {
+    pub fn restore(&self, student_group_id: i32) -> Result<StudentGroupWithRelations, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(
-            diesel::delete(student_groups::table.find(student_group_id))
-                .execute(&mut connection)?,
+        diesel::update(
+            student_groups::table
+                .find(student_group_id)
+                .filter(student_groups::deleted_at.is_not_null()),
+        )
+        .set(student_groups::deleted_at.eq(None::<DateTime<Utc>>))
+        .returning(student_groups::id)
+        .get_result::<i32>(&mut connection)?;
+
+        self.get(student_group_id, false)
+    }
+
+    /// Занимает одно место в группе в рамках транзакции `connection`. Уменьшение счетчика
+    /// и проверка на ноль выполняются одним запросом, поэтому параллельные зачисления
+    /// не могут занять одно и то же место.
+    pub(super) fn reserve_spot(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+    ) -> Result<(), AppError> {
+        let reserved = diesel::update(
+            student_groups::table
+                .find(student_group_id)
+                .filter(student_groups::deleted_at.is_null())
+                .filter(student_groups::free_spots.gt(0)),
         )
+        .set(student_groups::free_spots.eq(student_groups::free_spots - 1))
+        .execute(connection)?;
+        if reserved > 0 {
+            return Ok(());
+        }
+
+        let exists = diesel::select(diesel::dsl::exists(
+            student_groups::table
+                .find(student_group_id)
+                .filter(student_groups::deleted_at.is_null()),
+        ))
+        .get_result::<bool>(connection)?;
+        if exists {
+            Err(AppError::GroupFull(format!(
+                "Student group with ID {} has no free spots",
+                student_group_id
+            )))
+        } else {
+            Err(AppError::NotFound(format!(
+                "Student group with ID {} not found",
+                student_group_id
+            )))
+        }
+    }
+
+    /// Освобождает место в группе в рамках транзакции `connection` и зачисляет на него
+    /// первого ученика из очереди с даты `today`, если очередь не пуста. Возвращает
+    /// зачисленных учеников в состоянии до зачисления, см. [`WaitlistRepository::promote`].
+    pub(super) fn release_spot(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+        today: NaiveDate,
+    ) -> Result<Vec<StudentWithRelations>, AppError> {
+        diesel::update(student_groups::table.find(student_group_id))
+            .set(student_groups::free_spots.eq(student_groups::free_spots + 1))
+            .execute(connection)?;
+        WaitlistRepository::promote(connection, student_group_id, today)
+    }
+
+    fn visible<'a>(include_deleted: bool) -> student_groups::BoxedQuery<'a, Pg> {
+        let query = student_groups::table.into_boxed();
+        if include_deleted {
+            query
+        } else {
+            query.filter(student_groups::deleted_at.is_null())
+        }
     }
 
     fn load_with_relations(
         &self,
-        query: student_groups::BoxedQuery<'_, diesel::pg::Pg>,
+        query: student_groups::BoxedQuery<'_, Pg>,
     ) -> Result<Vec<StudentGroupWithRelations>, AppError> {
         let mut connection = self.pool.get()?;
-        let results = query
+        let groups = query
             .left_join(teachers::table)
             .select((StudentGroup::as_select(), Option::<Teacher>::as_select()))
-            .load::<(StudentGroup, Option<Teacher>)>(&mut connection)?
+            .load::<(StudentGroup, Option<Teacher>)>(&mut connection)?;
+
+        let group_ids: Vec<i32> = groups.iter().map(|(group, _)| group.id).collect();
+        let enrolled_counts = students::table
+            .filter(students::student_group_id.eq_any(&group_ids))
+            .group_by(students::student_group_id)
+            .select((students::student_group_id, count_star()))
+            .load::<(Option<i32>, i64)>(&mut connection)?;
+
+        let results = groups
             .into_iter()
-            .map(|(student_group, teacher)| StudentGroupWithRelations {
-                student_group,
-                teacher,
+            .map(|(student_group, teacher)| {
+                let enrolled = enrolled_counts
+                    .iter()
+                    .find(|(group_id, _)| *group_id == Some(student_group.id))
+                    .map_or(0, |(_, count)| *count);
+                StudentGroupWithRelations {
+                    capacity: i64::from(student_group.free_spots) + enrolled,
+                    enrolled,
+                    student_group,
+                    teacher,
+                }
             })
             .collect();
 
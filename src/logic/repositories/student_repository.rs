@@ -1,9 +1,11 @@
+use chrono::{DateTime, NaiveDate, Utc};
+
 use crate::{
     db::PostgresPool,
     error::AppError,
     models::{
         parent::Parent,
-        student::{NewStudent, Student, StudentWithRelations, UpdateStudent},
+        student::{NewStudent, Student, StudentFilter, StudentWithRelations, UpdateStudent},
         student_group::StudentGroup,
     },
     schema::{
@@ -13,7 +15,10 @@ use crate::{
 };
 use diesel::{pg::Pg, prelude::*};
 
-use super::single_result;
+use super::{
+    contains_pattern, enrollment_repository::EnrollmentRepository, single_result,
+    student_group_repository::StudentGroupRepository, waitlist_repository::WaitlistRepository,
+};
 
 #[derive(Clone)]
 pub struct StudentRepository {
@@ -25,51 +30,241 @@ impl StudentRepository {
         Self { pool }
     }
 
-    pub fn create(&self, new_student: NewStudent) -> Result<StudentWithRelations, AppError> {
+    /// Создает ученика. Зачисление в группу, если она указана, начинается с даты `today`.
+    pub fn create(
+        &self,
+        new_student: NewStudent,
+        today: NaiveDate,
+    ) -> Result<StudentWithRelations, AppError> {
         let mut connection = self.pool.get()?;
-        let student_id = diesel::insert_into(students::table)
-            .values(&new_student)
-            .returning(students::id)
-            .get_result::<i32>(&mut connection)?;
+        let student_id = connection.transaction::<_, AppError, _>(|connection| {
+            if let Some(student_group_id) = new_student.student_group_id {
+                StudentGroupRepository::reserve_spot(connection, student_group_id)?;
+            }
+            let student_id = diesel::insert_into(students::table)
+                .values(&new_student)
+                .returning(students::id)
+                .get_result::<i32>(connection)?;
+            if let Some(student_group_id) = new_student.student_group_id {
+                EnrollmentRepository::transfer(
+                    connection,
+                    student_id,
+                    Some(student_group_id),
+                    today,
+                )?;
+            }
+            Ok(student_id)
+        })?;
 
-        self.get(student_id)
+        self.get(student_id, false)
     }
 
-    pub fn get(&self, student_id: i32) -> Result<StudentWithRelations, AppError> {
-        let query = students::table
-            .filter(students::id.eq(student_id))
-            .into_boxed();
+    pub fn get(
+        &self,
+        student_id: i32,
+        include_deleted: bool,
+    ) -> Result<StudentWithRelations, AppError> {
+        let query = Self::visible(include_deleted).filter(students::id.eq(student_id));
 
         single_result(self.load_with_relations(query)?)
     }
 
-    pub fn get_students_by_group_id(
-        &self,
+    /// ID учеников, которые числились в группе на дату `date`, в рамках транзакции
+    /// `connection`.
+    pub fn get_ids_by_group_on_date(
+        connection: &mut PgConnection,
         group_id: i32,
+        date: NaiveDate,
+    ) -> Result<Vec<i32>, AppError> {
+        Ok(Self::visible(false)
+            .filter(students::id.eq_any(EnrollmentRepository::enrolled_student_ids(group_id, date)))
+            .order(students::id)
+            .select(students::id)
+            .load(connection)?)
+    }
+
+    /// Ученики с ID из `student_ids`, включая удаленных, в рамках транзакции `connection`.
+    pub(super) fn get_by_ids(
+        connection: &mut PgConnection,
+        student_ids: &[i32],
     ) -> Result<Vec<StudentWithRelations>, AppError> {
-        let query = students::table
+        let query = Self::visible(true)
+            .filter(students::id.eq_any(student_ids))
+            .order(students::id);
+        Self::load_all(connection, query)
+    }
+
+    pub fn count_by_group_id(&self, group_id: i32) -> Result<i64, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(students::table
             .filter(students::student_group_id.eq(group_id))
-            .into_boxed();
+            .count()
+            .get_result(&mut connection)?)
+    }
+
+    pub fn get_all(
+        &self,
+        filter: &StudentFilter,
+        limit: i64,
+        offset: i64,
+        include_deleted: bool,
+    ) -> Result<(Vec<StudentWithRelations>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::filtered(filter, include_deleted)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let query = Self::filtered(filter, include_deleted)
+            .order(students::id)
+            .limit(limit)
+            .offset(offset);
 
-        self.load_with_relations(query)
+        Ok((self.load_with_relations(query)?, total))
     }
 
+    /// Обновляет ученика. При смене группы или отчислении из нее старое зачисление
+    /// закрывается, а новое открывается датой `today`. Вместе с учеником возвращаются
+    /// ученики, зачисленные из очереди на освободившееся место, в состоянии до зачисления.
     pub fn update(
         &self,
         student_id: i32,
         updated_student: UpdateStudent,
-    ) -> Result<StudentWithRelations, AppError> {
+        today: NaiveDate,
+    ) -> Result<(StudentWithRelations, Vec<StudentWithRelations>), AppError> {
         let mut connection = self.pool.get()?;
-        diesel::update(students::table.find(student_id))
-            .set(&updated_student)
-            .execute(&mut connection)?;
+        let promoted = connection.transaction::<_, AppError, _>(|connection| {
+            if let Some(new_group_id) = updated_student.student_group_id {
+                let current_group_id = single_result(
+                    students::table
+                        .find(student_id)
+                        .filter(students::deleted_at.is_null())
+                        .select(students::student_group_id)
+                        .for_update()
+                        .load::<Option<i32>>(connection)?,
+                )?;
+                if current_group_id != new_group_id {
+                    if let Some(new_group_id) = new_group_id {
+                        StudentGroupRepository::reserve_spot(connection, new_group_id)?;
+                    }
+                    diesel::update(students::table.find(student_id))
+                        .set(&updated_student)
+                        .execute(connection)?;
+                    EnrollmentRepository::transfer(connection, student_id, new_group_id, today)?;
+                    if let Some(new_group_id) = new_group_id {
+                        WaitlistRepository::remove_student(
+                            connection,
+                            student_id,
+                            Some(new_group_id),
+                        )?;
+                    }
+                    return match current_group_id {
+                        Some(old_group_id) => {
+                            StudentGroupRepository::release_spot(connection, old_group_id, today)
+                        }
+                        None => Ok(Vec::new()),
+                    };
+                }
+            }
+            // Набор изменений может оказаться пустым, например при запросе без полей
+            if !updated_student.is_empty() {
+                diesel::update(
+                    students::table
+                        .find(student_id)
+                        .filter(students::deleted_at.is_null()),
+                )
+                .set(&updated_student)
+                .execute(connection)?;
+            }
+            Ok(Vec::new())
+        })?;
 
-        self.get(student_id)
+        Ok((self.get(student_id, false)?, promoted))
     }
 
-    pub fn delete(&self, student_id: i32) -> Result<usize, AppError> {
+    /// Помечает ученика удаленным: он отчисляется из группы с даты `today` и убирается из
+    /// очередей на зачисление. История зачислений и посещаемость сохраняются. Возвращает
+    /// учеников, зачисленных из очереди на освободившееся место, в состоянии до
+    /// зачисления, или `None`, если ученик не найден.
+    pub fn delete(
+        &self,
+        student_id: i32,
+        today: NaiveDate,
+    ) -> Result<Option<Vec<StudentWithRelations>>, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(diesel::delete(students::table.find(student_id)).execute(&mut connection)?)
+        connection.transaction::<_, AppError, _>(|connection| {
+            let Some(student_group_id) = students::table
+                .find(student_id)
+                .filter(students::deleted_at.is_null())
+                .select(students::student_group_id)
+                .for_update()
+                .first::<Option<i32>>(connection)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+
+            WaitlistRepository::remove_student(connection, student_id, None)?;
+            diesel::update(students::table.find(student_id))
+                .set((
+                    students::deleted_at.eq(Utc::now()),
+                    students::student_group_id.eq(None::<i32>),
+                ))
+                .execute(connection)?;
+            match student_group_id {
+                Some(student_group_id) => {
+                    EnrollmentRepository::transfer(connection, student_id, None, today)?;
+                    StudentGroupRepository::release_spot(connection, student_group_id, today)
+                        .map(Some)
+                }
+                None => Ok(Some(Vec::new())),
+            }
+        })
+    }
+
+    /// Снимает с ученика отметку об удалении. Ученик восстанавливается без группы.
+    pub fn restore(&self, student_id: i32) -> Result<StudentWithRelations, AppError> {
+        let mut connection = self.pool.get()?;
+        diesel::update(
+            students::table
+                .find(student_id)
+                .filter(students::deleted_at.is_not_null()),
+        )
+        .set(students::deleted_at.eq(None::<DateTime<Utc>>))
+        .returning(students::id)
+        .get_result::<i32>(&mut connection)?;
+
+        self.get(student_id, false)
+    }
+
+    fn visible<'a>(include_deleted: bool) -> students::BoxedQuery<'a, Pg> {
+        let query = students::table.into_boxed();
+        if include_deleted {
+            query
+        } else {
+            query.filter(students::deleted_at.is_null())
+        }
+    }
+
+    fn filtered(filter: &StudentFilter, include_deleted: bool) -> students::BoxedQuery<'_, Pg> {
+        let mut query = Self::visible(include_deleted);
+
+        if let Some(group_id) = filter.student_group_id {
+            query = query.filter(students::student_group_id.eq(group_id));
+        }
+        if let Some(parent_id) = filter.parent_id {
+            query = query.filter(students::parent_id.eq(parent_id));
+        }
+        if let Some(name) = &filter.name {
+            query = query.filter(students::name.ilike(contains_pattern(name)));
+        }
+        if let Some(birth_date_from) = filter.birth_date_from {
+            query = query.filter(students::birth_date.ge(birth_date_from));
+        }
+        if let Some(birth_date_to) = filter.birth_date_to {
+            query = query.filter(students::birth_date.le(birth_date_to));
+        }
+
+        query
     }
 
     fn load_with_relations(
@@ -77,6 +272,13 @@ impl StudentRepository {
         query: students::BoxedQuery<'_, Pg>,
     ) -> Result<Vec<StudentWithRelations>, AppError> {
         let mut connection = self.pool.get()?;
+        Self::load_all(&mut connection, query)
+    }
+
+    fn load_all(
+        connection: &mut PgConnection,
+        query: students::BoxedQuery<'_, Pg>,
+    ) -> Result<Vec<StudentWithRelations>, AppError> {
         let results = query
             .left_join(parents::table)
             .left_join(student_groups::table)
@@ -85,7 +287,7 @@ impl StudentRepository {
                 Option::<Parent>::as_select(),
                 Option::<StudentGroup>::as_select(),
             ))
-            .load::<(Student, Option<Parent>, Option<StudentGroup>)>(&mut connection)?
+            .load::<(Student, Option<Parent>, Option<StudentGroup>)>(connection)?
             .into_iter()
             .map(|(student, parent, student_group)| StudentWithRelations {
                 student,
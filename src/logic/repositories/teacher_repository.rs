@@ -1,4 +1,5 @@
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{pg::Pg, prelude::*};
 
 use crate::{
     db::PostgresPool,
@@ -24,26 +25,79 @@ impl TeacherRepository {
             .get_result::<Teacher>(&mut connection)?)
     }
 
-    pub fn get(&self, teacher_id: i32) -> Result<Teacher, AppError> {
+    pub fn get(&self, teacher_id: i32, include_deleted: bool) -> Result<Teacher, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(teachers::table
-            .find(teacher_id)
+        Ok(Self::visible(include_deleted)
+            .filter(teachers::id.eq(teacher_id))
             .first::<Teacher>(&mut connection)?)
     }
 
+    pub fn get_all(
+        &self,
+        limit: i64,
+        offset: i64,
+        include_deleted: bool,
+    ) -> Result<(Vec<Teacher>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = Self::visible(include_deleted)
+            .count()
+            .get_result::<i64>(&mut connection)?;
+        let teachers = Self::visible(include_deleted)
+            .order(teachers::id)
+            .limit(limit)
+            .offset(offset)
+            .load::<Teacher>(&mut connection)?;
+
+        Ok((teachers, total))
+    }
+
     pub fn update(
         &self,
         teacher_id: i32,
         updated_teacher: UpdateTeacher,
     ) -> Result<Teacher, AppError> {
+        if updated_teacher.is_empty() {
+            return self.get(teacher_id, false);
+        }
         let mut connection = self.pool.get()?;
-        Ok(diesel::update(teachers::table.find(teacher_id))
-            .set(&updated_teacher)
-            .get_result::<Teacher>(&mut connection)?)
+        Ok(diesel::update(
+            teachers::table
+                .find(teacher_id)
+                .filter(teachers::deleted_at.is_null()),
+        )
+        .set(&updated_teacher)
+        .get_result::<Teacher>(&mut connection)?)
     }
 
+    /// Помечает преподавателя удаленным. Его документы и связи с группами сохраняются.
     pub fn delete(&self, teacher_id: i32) -> Result<usize, AppError> {
         let mut connection = self.pool.get()?;
-        Ok(diesel::delete(teachers::table.find(teacher_id)).execute(&mut connection)?)
+        Ok(diesel::update(
+            teachers::table
+                .find(teacher_id)
+                .filter(teachers::deleted_at.is_null()),
+        )
+        .set(teachers::deleted_at.eq(Utc::now()))
+        .execute(&mut connection)?)
+    }
+
+    pub fn restore(&self, teacher_id: i32) -> Result<Teacher, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(
+            teachers::table
+                .find(teacher_id)
+                .filter(teachers::deleted_at.is_not_null()),
+        )
+        .set(teachers::deleted_at.eq(None::<DateTime<Utc>>))
+        .get_result::<Teacher>(&mut connection)?)
+    }
+
+    fn visible<'a>(include_deleted: bool) -> teachers::BoxedQuery<'a, Pg> {
+        let query = teachers::table.into_boxed();
+        if include_deleted {
+            query
+        } else {
+            query.filter(teachers::deleted_at.is_null())
+        }
     }
 }
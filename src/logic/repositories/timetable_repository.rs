@@ -0,0 +1,179 @@
+use chrono::Utc;
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::timetable::{
+        NewTimetableJob, NewTimetableProposedLesson, NewTimetableViolation, TimetableJob,
+        TimetableJobStatus, TimetableProposedLesson, TimetableViolation,
+    },
+    schema::{timetable_jobs, timetable_proposed_lessons, timetable_violations},
+};
+
+use super::single_result;
+
+#[derive(Clone)]
+pub struct TimetableRepository {
+    pool: PostgresPool,
+}
+
+/// Итоговые оценки составленного расписания.
+pub struct TimetableScore {
+    pub hard_violations: i32,
+    pub soft_violations: i32,
+    pub score: i32,
+}
+
+impl TimetableRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn create_job(&self, new_job: NewTimetableJob) -> Result<TimetableJob, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::insert_into(timetable_jobs::table)
+            .values(&new_job)
+            .returning(TimetableJob::as_returning())
+            .get_result(&mut connection)?)
+    }
+
+    pub fn get_job(&self, job_id: i32) -> Result<TimetableJob, AppError> {
+        let mut connection = self.pool.get()?;
+        single_result(
+            timetable_jobs::table
+                .find(job_id)
+                .select(TimetableJob::as_select())
+                .load(&mut connection)?,
+        )
+    }
+
+    pub fn get_all_jobs(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<TimetableJob>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = timetable_jobs::table
+            .count()
+            .get_result::<i64>(&mut connection)?;
+
+        let jobs = timetable_jobs::table
+            .order(timetable_jobs::id.desc())
+            .limit(limit)
+            .offset(offset)
+            .select(TimetableJob::as_select())
+            .load(&mut connection)?;
+
+        Ok((jobs, total))
+    }
+
+    pub fn set_status(&self, job_id: i32, status: TimetableJobStatus) -> Result<usize, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(timetable_jobs::table.find(job_id))
+            .set(timetable_jobs::status.eq(status))
+            .execute(&mut connection)?)
+    }
+
+    /// Переводит задачу из статуса `completed` в `accepted` в рамках транзакции
+    /// `connection`. Если задача уже в другом статусе, например ее параллельно принял
+    /// другой директор, возвращает `AppError::BadRequest`.
+    pub fn mark_accepted(connection: &mut PgConnection, job_id: i32) -> Result<(), AppError> {
+        let updated = diesel::update(
+            timetable_jobs::table
+                .find(job_id)
+                .filter(timetable_jobs::status.eq(TimetableJobStatus::Completed)),
+        )
+        .set(timetable_jobs::status.eq(TimetableJobStatus::Accepted))
+        .execute(connection)?;
+        if updated == 0 {
+            return Err(AppError::BadRequest(format!(
+                "Timetable job {} has already been accepted",
+                job_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Сохраняет предложенные уроки и нарушенные ограничения и завершает задачу.
+    pub fn save_result(
+        &self,
+        job_id: i32,
+        lessons: Vec<NewTimetableProposedLesson>,
+        violations: Vec<NewTimetableViolation>,
+        score: TimetableScore,
+    ) -> Result<(), AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            diesel::insert_into(timetable_proposed_lessons::table)
+                .values(&lessons)
+                .execute(connection)?;
+            diesel::insert_into(timetable_violations::table)
+                .values(&violations)
+                .execute(connection)?;
+            diesel::update(timetable_jobs::table.find(job_id))
+                .set((
+                    timetable_jobs::status.eq(TimetableJobStatus::Completed),
+                    timetable_jobs::hard_violations.eq(Some(score.hard_violations)),
+                    timetable_jobs::soft_violations.eq(Some(score.soft_violations)),
+                    timetable_jobs::score.eq(Some(score.score)),
+                    timetable_jobs::finished_at.eq(Some(Utc::now())),
+                ))
+                .execute(connection)?;
+            Ok(())
+        })
+    }
+
+    /// Помечает незавершенные задачи (`pending` и `running`) как завершившиеся ошибкой
+    /// `error`. Возвращает количество таких задач.
+    pub fn fail_unfinished(&self, error: String) -> Result<usize, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(
+            timetable_jobs::table.filter(
+                timetable_jobs::status
+                    .eq_any([TimetableJobStatus::Pending, TimetableJobStatus::Running]),
+            ),
+        )
+        .set((
+            timetable_jobs::status.eq(TimetableJobStatus::Failed),
+            timetable_jobs::error.eq(Some(error)),
+            timetable_jobs::finished_at.eq(Some(Utc::now())),
+        ))
+        .execute(&mut connection)?)
+    }
+
+    pub fn set_failed(&self, job_id: i32, error: String) -> Result<usize, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(timetable_jobs::table.find(job_id))
+            .set((
+                timetable_jobs::status.eq(TimetableJobStatus::Failed),
+                timetable_jobs::error.eq(Some(error)),
+                timetable_jobs::finished_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut connection)?)
+    }
+
+    pub fn get_proposed_lessons(
+        &self,
+        job_id: i32,
+    ) -> Result<Vec<TimetableProposedLesson>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(timetable_proposed_lessons::table
+            .filter(timetable_proposed_lessons::timetable_job_id.eq(job_id))
+            .order((
+                timetable_proposed_lessons::starts_at,
+                timetable_proposed_lessons::student_group_id,
+            ))
+            .select(TimetableProposedLesson::as_select())
+            .load(&mut connection)?)
+    }
+
+    pub fn get_violations(&self, job_id: i32) -> Result<Vec<TimetableViolation>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(timetable_violations::table
+            .filter(timetable_violations::timetable_job_id.eq(job_id))
+            .order(timetable_violations::id)
+            .select(TimetableViolation::as_select())
+            .load(&mut connection)?)
+    }
+}
@@ -1,4 +1,4 @@
-use diesel::{QueryDsl, RunQueryDsl};
+use diesel::{ExpressionMethods, QueryDsl, RunQueryDsl};
 
 use crate::{
     db::PostgresPool,
@@ -31,13 +31,35 @@ impl UserRepository {
             .get_result::<User>(&mut connection)?)
     }
 
+    pub fn get_all(&self, limit: i64, offset: i64) -> Result<(Vec<User>, i64), AppError> {
+        let mut connection = self.pool.get()?;
+        let total = users::table.count().get_result::<i64>(&mut connection)?;
+        let users = users::table
+            .order(users::id)
+            .limit(limit)
+            .offset(offset)
+            .load::<User>(&mut connection)?;
+
+        Ok((users, total))
+    }
+
     pub fn update(&self, user_id: i32, updated_user: UpdateUser) -> Result<User, AppError> {
+        if updated_user.is_empty() {
+            return self.get(user_id);
+        }
         let mut connection = self.pool.get()?;
         Ok(diesel::update(users::table.find(user_id))
             .set(&updated_user)
             .get_result::<User>(&mut connection)?)
     }
 
+    pub fn update_password(&self, user_id: i32, password_hash: &str) -> Result<User, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(diesel::update(users::table.find(user_id))
+            .set(users::password.eq(password_hash))
+            .get_result::<User>(&mut connection)?)
+    }
+
     pub fn delete(&self, user_id: i32) -> Result<usize, AppError> {
         let mut connection = self.pool.get()?;
         Ok(diesel::delete(users::table.find(user_id)).execute(&mut connection)?)
@@ -0,0 +1,260 @@
+use chrono::NaiveDate;
+use diesel::prelude::*;
+
+use crate::{
+    db::PostgresPool,
+    error::AppError,
+    models::{
+        student::{Student, StudentWithRelations},
+        waitlist::{NewWaitlistEntry, WaitlistEntry, WaitlistEntryWithStudent},
+    },
+    schema::{student_groups, students, waitlist_entries},
+};
+
+use super::{
+    enrollment_repository::EnrollmentRepository, single_result,
+    student_group_repository::StudentGroupRepository, student_repository::StudentRepository,
+};
+
+#[derive(Clone)]
+pub struct WaitlistRepository {
+    pool: PostgresPool,
+}
+
+impl WaitlistRepository {
+    pub fn new(pool: PostgresPool) -> Self {
+        Self { pool }
+    }
+
+    /// Ставит ученика в конец очереди группы.
+    pub fn add(&self, student_group_id: i32, student_id: i32) -> Result<WaitlistEntry, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            // Блокировка группы не дает двум параллельным запросам занять одну позицию
+            single_result(
+                student_groups::table
+                    .find(student_group_id)
+                    .select(student_groups::id)
+                    .for_update()
+                    .load::<i32>(connection)?,
+            )?;
+            let last_position = waitlist_entries::table
+                .filter(waitlist_entries::student_group_id.eq(student_group_id))
+                .select(diesel::dsl::max(waitlist_entries::position))
+                .first::<Option<i32>>(connection)?;
+
+            Ok(diesel::insert_into(waitlist_entries::table)
+                .values(NewWaitlistEntry {
+                    student_group_id,
+                    student_id,
+                    position: last_position.unwrap_or(0) + 1,
+                })
+                .returning(WaitlistEntry::as_returning())
+                .get_result(connection)?)
+        })
+    }
+
+    pub fn get(&self, entry_id: i32) -> Result<WaitlistEntry, AppError> {
+        let mut connection = self.pool.get()?;
+        single_result(
+            waitlist_entries::table
+                .find(entry_id)
+                .select(WaitlistEntry::as_select())
+                .load(&mut connection)?,
+        )
+    }
+
+    pub fn get_by_student(
+        &self,
+        student_group_id: i32,
+        student_id: i32,
+    ) -> Result<Option<WaitlistEntry>, AppError> {
+        let mut connection = self.pool.get()?;
+        Ok(waitlist_entries::table
+            .filter(waitlist_entries::student_group_id.eq(student_group_id))
+            .filter(waitlist_entries::student_id.eq(student_id))
+            .select(WaitlistEntry::as_select())
+            .first(&mut connection)
+            .optional()?)
+    }
+
+    pub fn get_by_group_id(
+        &self,
+        student_group_id: i32,
+    ) -> Result<Vec<WaitlistEntryWithStudent>, AppError> {
+        let mut connection = self.pool.get()?;
+        let results = waitlist_entries::table
+            .inner_join(students::table)
+            .filter(waitlist_entries::student_group_id.eq(student_group_id))
+            .order(waitlist_entries::position)
+            .select((WaitlistEntry::as_select(), Student::as_select()))
+            .load::<(WaitlistEntry, Student)>(&mut connection)?
+            .into_iter()
+            .map(|(entry, student)| WaitlistEntryWithStudent { entry, student })
+            .collect();
+
+        Ok(results)
+    }
+
+    /// Переносит запись на позицию `position`, сдвигая записи между старой и новой
+    /// позициями. Позиция ограничивается длиной очереди.
+    pub fn move_to(&self, entry_id: i32, position: i32) -> Result<WaitlistEntry, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            let entry = single_result(
+                waitlist_entries::table
+                    .find(entry_id)
+                    .select(WaitlistEntry::as_select())
+                    .for_update()
+                    .load(connection)?,
+            )?;
+            let in_group = waitlist_entries::table
+                .filter(waitlist_entries::student_group_id.eq(entry.student_group_id));
+            let queue_length = in_group.count().get_result::<i64>(connection)?;
+            let position = position.clamp(1, queue_length as i32);
+
+            if position < entry.position {
+                diesel::update(
+                    in_group
+                        .filter(waitlist_entries::position.ge(position))
+                        .filter(waitlist_entries::position.lt(entry.position)),
+                )
+                .set(waitlist_entries::position.eq(waitlist_entries::position + 1))
+                .execute(connection)?;
+            } else if position > entry.position {
+                diesel::update(
+                    in_group
+                        .filter(waitlist_entries::position.gt(entry.position))
+                        .filter(waitlist_entries::position.le(position)),
+                )
+                .set(waitlist_entries::position.eq(waitlist_entries::position - 1))
+                .execute(connection)?;
+            }
+
+            Ok(diesel::update(waitlist_entries::table.find(entry_id))
+                .set(waitlist_entries::position.eq(position))
+                .returning(WaitlistEntry::as_returning())
+                .get_result(connection)?)
+        })
+    }
+
+    pub fn delete(&self, entry_id: i32) -> Result<usize, AppError> {
+        let mut connection = self.pool.get()?;
+        connection.transaction::<_, AppError, _>(|connection| {
+            let entries = waitlist_entries::table
+                .find(entry_id)
+                .select(WaitlistEntry::as_select())
+                .for_update()
+                .load(connection)?;
+            for entry in &entries {
+                Self::remove_entry(connection, entry)?;
+            }
+            Ok(entries.len())
+        })
+    }
+
+    /// Удаляет записи ученика из очередей: из очереди `student_group_id` или из всех
+    /// очередей, если группа не указана. Следующие записи сдвигаются вперед.
+    pub(super) fn remove_student(
+        connection: &mut PgConnection,
+        student_id: i32,
+        student_group_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        let entries = waitlist_entries::table
+            .filter(waitlist_entries::student_id.eq(student_id))
+            .select(WaitlistEntry::as_select())
+            .for_update()
+            .load(connection)?;
+        for entry in entries.iter().filter(|entry| {
+            student_group_id
+                .is_none_or(|student_group_id| entry.student_group_id == student_group_id)
+        }) {
+            Self::remove_entry(connection, entry)?;
+        }
+        Ok(())
+    }
+
+    /// Удаляет всю очередь группы `student_group_id` в рамках транзакции `connection`.
+    pub(super) fn clear_group(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+    ) -> Result<usize, AppError> {
+        Ok(diesel::delete(
+            waitlist_entries::table.filter(waitlist_entries::student_group_id.eq(student_group_id)),
+        )
+        .execute(connection)?)
+    }
+
+    /// Зачисляет учеников из начала очереди, пока в группе есть свободные места. Если
+    /// зачисленный ученик был в другой группе, место в ней освобождается и очередь той
+    /// группы тоже продвигается. Зачисление начинается с даты `today`. Возвращает
+    /// зачисленных учеников в состоянии до зачисления.
+    pub(super) fn promote(
+        connection: &mut PgConnection,
+        student_group_id: i32,
+        today: NaiveDate,
+    ) -> Result<Vec<StudentWithRelations>, AppError> {
+        let mut promoted = Vec::new();
+        loop {
+            let Some(entry) = waitlist_entries::table
+                .filter(waitlist_entries::student_group_id.eq(student_group_id))
+                .order(waitlist_entries::position)
+                .select(WaitlistEntry::as_select())
+                .for_update()
+                .first(connection)
+                .optional()?
+            else {
+                return Ok(promoted);
+            };
+
+            let previous_group_id = students::table
+                .find(entry.student_id)
+                .select(students::student_group_id)
+                .for_update()
+                .first::<Option<i32>>(connection)?;
+            if previous_group_id != Some(student_group_id) {
+                match StudentGroupRepository::reserve_spot(connection, student_group_id) {
+                    Ok(()) => {}
+                    Err(AppError::GroupFull(_)) => return Ok(promoted),
+                    Err(err) => return Err(err),
+                }
+                promoted.extend(StudentRepository::get_by_ids(
+                    connection,
+                    &[entry.student_id],
+                )?);
+                diesel::update(students::table.find(entry.student_id))
+                    .set(students::student_group_id.eq(Some(student_group_id)))
+                    .execute(connection)?;
+                EnrollmentRepository::transfer(
+                    connection,
+                    entry.student_id,
+                    Some(student_group_id),
+                    today,
+                )?;
+            }
+            Self::remove_entry(connection, &entry)?;
+
+            if let Some(previous_group_id) = previous_group_id
+                && previous_group_id != student_group_id
+            {
+                promoted.extend(StudentGroupRepository::release_spot(
+                    connection,
+                    previous_group_id,
+                    today,
+                )?);
+            }
+        }
+    }
+
+    fn remove_entry(connection: &mut PgConnection, entry: &WaitlistEntry) -> Result<(), AppError> {
+        diesel::delete(waitlist_entries::table.find(entry.id)).execute(connection)?;
+        diesel::update(
+            waitlist_entries::table
+                .filter(waitlist_entries::student_group_id.eq(entry.student_group_id))
+                .filter(waitlist_entries::position.gt(entry.position)),
+        )
+        .set(waitlist_entries::position.eq(waitlist_entries::position - 1))
+        .execute(connection)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use axum::extract::FromRef;
+use tracing::info;
+
+use crate::{
+    AppState,
+    auth::{
+        permission::check_role,
+        token::{generate_token, hash_token},
+    },
+    error::AppError,
+    logic::{
+        repositories::api_token_repository::ApiTokenRepository,
+        services::{audit_service::AuditService, user_service::UserService},
+    },
+    models::{
+        api_token::{ApiToken, ApiTokenFilter, CreatedApiToken, NewApiToken, RawNewApiToken},
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        user::{PermissionRole, User},
+        validation::ValidationErrors,
+    },
+};
+
+/// API токены пользователей. Пользователь управляет своими токенами, администратор —
+/// токенами любых пользователей, в том числе служебных.
+#[derive(Clone)]
+pub struct ApiTokenService {
+    api_token_repository: ApiTokenRepository,
+    user_service: UserService,
+    audit_service: AuditService,
+}
+
+impl ApiTokenService {
+    pub fn new(
+        api_token_repository: ApiTokenRepository,
+        user_service: UserService,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            api_token_repository,
+            user_service,
+            audit_service,
+        }
+    }
+
+    pub fn create(
+        &self,
+        user: &User,
+        raw_new_api_token: RawNewApiToken,
+    ) -> Result<CreatedApiToken, AppError> {
+        let owner = self.owner(user, raw_new_api_token.user_id)?;
+        let role = raw_new_api_token.role.unwrap_or(owner.role);
+        let mut errors = ValidationErrors::default();
+        errors.check(
+            role <= owner.role,
+            "role",
+            "too_high",
+            format!("Token role must not exceed user role {:?}", owner.role),
+        );
+        errors.into_result()?;
+
+        let token = generate_token();
+        let api_token = self.api_token_repository.create(NewApiToken {
+            user_id: owner.id,
+            name: raw_new_api_token.name,
+            token_hash: hash_token(&token),
+            role,
+            expires_at: raw_new_api_token.expires_at,
+        })?;
+        self.audit_service
+            .record_create(AuditEntityType::ApiToken, api_token.id, &api_token);
+        info!(
+            "Successfully created API token with ID {} for user with ID {}",
+            api_token.id, owner.id
+        );
+        Ok(CreatedApiToken { api_token, token })
+    }
+
+    pub fn get_all(
+        &self,
+        user: &User,
+        filter: ApiTokenFilter,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<ApiToken>, AppError> {
+        let owner = self.owner(user, filter.user_id)?;
+        let (api_tokens, total) =
+            self.api_token_repository
+                .get_all(owner.id, pagination.limit(), pagination.offset())?;
+        info!(
+            "Got {} of {} API tokens of user with ID {}",
+            api_tokens.len(),
+            total,
+            owner.id
+        );
+        Ok(PaginatedResponse::new(api_tokens, total, pagination))
+    }
+
+    /// Отзывает токен. Повторный отзыв ничего не меняет.
+    pub fn revoke(&self, user: &User, api_token_id: i32) -> Result<ApiToken, AppError> {
+        let api_token = self.api_token_repository.get(api_token_id)?;
+        if api_token.user_id != user.id && user.role < PermissionRole::Admin {
+            return Err(AppError::NotFound(format!(
+                "API token with ID {} not found",
+                api_token_id
+            )));
+        }
+        if api_token.revoked_at.is_some() {
+            info!("API token with ID {} is already revoked", api_token_id);
+            return Ok(api_token);
+        }
+        let revoked_api_token = self.api_token_repository.revoke(api_token_id)?;
+        self.audit_service.record_update(
+            AuditEntityType::ApiToken,
+            api_token_id,
+            &api_token,
+            &revoked_api_token,
+        );
+        info!("Revoked API token with ID {}", api_token_id);
+        Ok(revoked_api_token)
+    }
+
+    /// Пользователь, токенами которого управляют. Чужими токенами может управлять только
+    /// администратор.
+    fn owner(&self, user: &User, user_id: Option<i32>) -> Result<User, AppError> {
+        match user_id {
+            Some(user_id) if user_id != user.id => {
+                check_role(Some(user), PermissionRole::Admin)?;
+                self.user_service.get(user_id)
+            }
+            _ => Ok(user.clone()),
+        }
+    }
+}
+
+impl FromRef<AppState> for ApiTokenService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.api_token_service.clone()
+    }
+}
@@ -1,38 +1,68 @@
 use axum::extract::FromRef;
+use chrono::NaiveDate;
+use diesel::PgConnection;
 use tracing::{info, warn};
 
 use crate::{
     AppState,
+    config::ScheduleConfig,
     error::AppError,
     logic::{
-        repositories::attendance_repository::AttendanceRepository,
-        services::student_service::StudentService,
+        repositories::{
+            attendance_repository::AttendanceRepository,
+            enrollment_repository::EnrollmentRepository, lesson_repository::LessonRepository,
+            student_repository::StudentRepository,
+        },
+        services::audit_service::AuditService,
+    },
+    models::{
+        attendance::{AttendanceFilter, AttendanceWithRelations, NewAttendance, UpdateAttendance},
+        audit::AuditEntityType,
+        lesson::Lesson,
+        pagination::{PaginatedResponse, PaginationParams},
+        student::Student,
+        user::{PermissionRole, User},
     },
-    models::attendance::{AttendanceWithRelations, NewAttendance, UpdateAttendance},
 };
 
 #[derive(Clone)]
 pub struct AttendanceService {
     attendance_repository: AttendanceRepository,
-    student_service: StudentService,
+    lesson_repository: LessonRepository,
+    enrollment_repository: EnrollmentRepository,
+    audit_service: AuditService,
+    schedule: ScheduleConfig,
 }
 
 impl AttendanceService {
     pub fn new(
         attendance_repository: AttendanceRepository,
-        student_service: StudentService,
+        lesson_repository: LessonRepository,
+        enrollment_repository: EnrollmentRepository,
+        audit_service: AuditService,
+        schedule: ScheduleConfig,
     ) -> Self {
         Self {
             attendance_repository,
-            student_service,
+            lesson_repository,
+            enrollment_repository,
+            audit_service,
+            schedule,
         }
     }
 
     pub fn create(
         &self,
         new_attendance: NewAttendance,
+        user: &User,
     ) -> Result<AttendanceWithRelations, AppError> {
+        self.ensure_can_edit(user, new_attendance.lesson_id)?;
         let attendance_full = self.attendance_repository.create(new_attendance)?;
+        self.audit_service.record_create(
+            AuditEntityType::Attendance,
+            attendance_full.attendance.id,
+            &attendance_full,
+        );
         info!(
             "Successfully created attendance with ID {}",
             attendance_full.attendance.id
@@ -40,33 +70,33 @@ impl AttendanceService {
         Ok(attendance_full)
     }
 
+    /// Создает пустые отметки посещаемости урока для учеников, которые по истории
+    /// зачислений состояли в группе в день урока `lesson_date`, в рамках транзакции
+    /// `connection`. Возвращает ID созданных отметок.
     pub fn create_attendances_for_group(
         &self,
+        connection: &mut PgConnection,
         lesson_id: i32,
         student_group_id: i32,
-    ) -> Result<Vec<AttendanceWithRelations>, AppError> {
-        let students = self
-            .student_service
-            .get_students_from_group(student_group_id)?;
-        let mut new_attendances = Vec::new();
-
-        for student_full in students {
-            let new_attendance = NewAttendance {
-                lesson_id,
-                student_id: student_full.student.id,
-                is_present: false,
-                skip_reason: None,
-            };
-
-            new_attendances.push(new_attendance);
-        }
+        lesson_date: NaiveDate,
+    ) -> Result<Vec<i32>, AppError> {
+        let new_attendances: Vec<NewAttendance> =
+            StudentRepository::get_ids_by_group_on_date(connection, student_group_id, lesson_date)?
+                .into_iter()
+                .map(|student_id| NewAttendance {
+                    lesson_id,
+                    student_id,
+                    is_present: false,
+                    skip_reason: None,
+                })
+                .collect();
 
-        let attendances = self.attendance_repository.batch_create(new_attendances)?;
+        let attendance_ids = AttendanceRepository::insert_all(connection, &new_attendances)?;
         info!(
             "Successfully created all attendances for lesson {}, for students in group {}",
             lesson_id, student_group_id
         );
-        Ok(attendances)
+        Ok(attendance_ids)
     }
 
     pub fn get(&self, attendance_id: i32) -> Result<AttendanceWithRelations, AppError> {
@@ -75,6 +105,18 @@ impl AttendanceService {
         Ok(attendance)
     }
 
+    pub fn get_all(
+        &self,
+        filter: AttendanceFilter,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<AttendanceWithRelations>, AppError> {
+        let (attendances, total) =
+            self.attendance_repository
+                .get_all(&filter, pagination.limit(), pagination.offset())?;
+        info!("Got {} of {} attendances", attendances.len(), total);
+        Ok(PaginatedResponse::new(attendances, total, pagination))
+    }
+
     pub fn get_by_lesson_id(
         &self,
         lesson_id: i32,
@@ -88,10 +130,19 @@ impl AttendanceService {
         &self,
         attendance_id: i32,
         update_attendance: UpdateAttendance,
+        user: &User,
     ) -> Result<AttendanceWithRelations, AppError> {
+        let attendance = self.attendance_repository.get(attendance_id)?;
+        self.ensure_can_edit(user, attendance.lesson.id)?;
         let updated_attendance = self
             .attendance_repository
             .update(attendance_id, update_attendance)?;
+        self.audit_service.record_update(
+            AuditEntityType::Attendance,
+            attendance_id,
+            &attendance,
+            &updated_attendance,
+        );
         info!(
             "Attendance with ID {} was successfully updated",
             attendance_id
@@ -99,10 +150,81 @@ impl AttendanceService {
         Ok(updated_attendance)
     }
 
-    pub fn delete(&self, attendance_id: i32) -> Result<bool, AppError> {
+    /// Отмечает ученика отсутствующим на уроке с причиной `skip_reason`. Если отметки
+    /// о посещении еще нет, она создается, но только для ученика, который по истории
+    /// зачислений состоял в группе урока в день урока.
+    pub fn excuse_absence(
+        &self,
+        student: &Student,
+        lesson: &Lesson,
+        skip_reason: String,
+    ) -> Result<AttendanceWithRelations, AppError> {
+        let attendance = self
+            .attendance_repository
+            .find_by_lesson_and_student(lesson.id, student.id)?;
+        let excused_attendance = match attendance {
+            Some(attendance) => {
+                let excused_attendance = self.attendance_repository.update(
+                    attendance.attendance.id,
+                    UpdateAttendance {
+                        is_present: Some(false),
+                        skip_reason: Some(skip_reason),
+                    },
+                )?;
+                self.audit_service.record_update(
+                    AuditEntityType::Attendance,
+                    attendance.attendance.id,
+                    &attendance,
+                    &excused_attendance,
+                );
+                excused_attendance
+            }
+            None if self.attends_lesson(student, lesson)? => {
+                let excused_attendance = self.attendance_repository.create(NewAttendance {
+                    lesson_id: lesson.id,
+                    student_id: student.id,
+                    is_present: false,
+                    skip_reason: Some(skip_reason),
+                })?;
+                self.audit_service.record_create(
+                    AuditEntityType::Attendance,
+                    excused_attendance.attendance.id,
+                    &excused_attendance,
+                );
+                excused_attendance
+            }
+            None => {
+                return Err(AppError::BadRequest(format!(
+                    "Student with ID {} does not attend lesson {}",
+                    student.id, lesson.id
+                )));
+            }
+        };
+        info!(
+            "Absence of student with ID {} from lesson {} was excused",
+            student.id, lesson.id
+        );
+        Ok(excused_attendance)
+    }
+
+    pub fn delete(&self, attendance_id: i32, user: &User) -> Result<bool, AppError> {
+        let attendance = match self.attendance_repository.get(attendance_id) {
+            Ok(attendance) => attendance,
+            Err(AppError::NotFound(_)) => {
+                warn!("Attendance with ID {} not found", attendance_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        self.ensure_can_edit(user, attendance.lesson.id)?;
         let deleted_count = self.attendance_repository.delete(attendance_id)?;
 
         if deleted_count > 0 {
+            self.audit_service.record_delete(
+                AuditEntityType::Attendance,
+                attendance_id,
+                &attendance,
+            );
             info!(
                 "Attendance with ID {} was successfully deleted",
                 attendance_id
@@ -114,19 +236,68 @@ impl AttendanceService {
         }
     }
 
-    pub fn delete_by_lesson_id(&self, lesson_id: i32) -> Result<bool, AppError> {
-        let deleted_count = self.attendance_repository.delete_by_lesson_id(lesson_id)?;
+    /// Удаляет посещения урока в рамках транзакции `connection` и возвращает их. Удаление
+    /// записывается в журнал после транзакции, см. [`Self::record_deleted`].
+    pub fn delete_by_lesson_id(
+        &self,
+        connection: &mut PgConnection,
+        lesson_id: i32,
+    ) -> Result<Vec<AttendanceWithRelations>, AppError> {
+        let deleted_attendances = AttendanceRepository::delete_by_lesson_id(connection, lesson_id)?;
 
-        if deleted_count > 0 {
+        if deleted_attendances.is_empty() {
+            warn!("Not found attendances for lesson {}", lesson_id);
+        } else {
             info!(
                 "Deleted {} attendances for lesson {}",
-                deleted_count, lesson_id
+                deleted_attendances.len(),
+                lesson_id
             );
-            Ok(true)
-        } else {
-            warn!("Not found attendances for lesson {}", lesson_id);
-            Ok(false)
         }
+        Ok(deleted_attendances)
+    }
+
+    /// Записывает в журнал удаление каждого из посещений `deleted_attendances`.
+    pub fn record_deleted(&self, deleted_attendances: &[AttendanceWithRelations]) {
+        for attendance in deleted_attendances {
+            self.audit_service.record_delete(
+                AuditEntityType::Attendance,
+                attendance.attendance.id,
+                attendance,
+            );
+        }
+    }
+
+    /// Состоял ли ученик в группе урока в день урока по местному времени школы.
+    fn attends_lesson(&self, student: &Student, lesson: &Lesson) -> Result<bool, AppError> {
+        match lesson.student_group_id {
+            Some(student_group_id) => self.enrollment_repository.is_enrolled(
+                student.id,
+                student_group_id,
+                self.schedule.local_date(lesson.starts_at),
+            ),
+            None => Ok(false),
+        }
+    }
+
+    /// Проверяет, что пользователь может изменять посещения урока. Директор может изменять
+    /// любые посещения, а преподаватель только посещения уроков групп, которые он ведет.
+    fn ensure_can_edit(&self, user: &User, lesson_id: i32) -> Result<(), AppError> {
+        if user.role >= PermissionRole::Director {
+            return Ok(());
+        }
+        let teacher_id = self.lesson_repository.get_teacher_id(lesson_id)?;
+        if user.teacher_id.is_some() && user.teacher_id == teacher_id {
+            return Ok(());
+        }
+        warn!(
+            "User with ID {} tried to edit attendance of lesson {} which they do not teach",
+            user.id, lesson_id
+        );
+        Err(AppError::Forbidden(format!(
+            "Only the teacher of the lesson group can edit attendance of lesson {}",
+            lesson_id
+        )))
     }
 }
 
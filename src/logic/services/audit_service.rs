@@ -0,0 +1,151 @@
+use axum::extract::FromRef;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::{error, info};
+
+use crate::{
+    AppState,
+    error::AppError,
+    handlers::utils::{audit_actor::current_actor, request_id::current_request_id},
+    logic::repositories::audit_repository::AuditRepository,
+    models::{
+        audit::{AuditAction, AuditEntityType, AuditEntry, AuditFilter, NewAuditEntry},
+        pagination::{PaginatedResponse, PaginationParams},
+    },
+};
+
+/// Журнал изменений данных. Сервисы записывают в него каждое создание, изменение,
+/// удаление и восстановление записи вместе с ее состоянием до и после действия.
+///
+/// Запись в журнал выполняется после того, как изменение уже сохранено, поэтому ошибка
+/// записи не отменяет изменение, а только логируется.
+#[derive(Clone)]
+pub struct AuditService {
+    audit_repository: AuditRepository,
+}
+
+impl AuditService {
+    pub fn new(audit_repository: AuditRepository) -> Self {
+        Self { audit_repository }
+    }
+
+    pub fn get_all(
+        &self,
+        filter: AuditFilter,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<AuditEntry>, AppError> {
+        if let (Some(from), Some(to)) = (filter.from, filter.to)
+            && to <= from
+        {
+            return Err(AppError::BadRequest(format!(
+                "Time range must end after it starts (from {}, to {})",
+                from, to
+            )));
+        }
+        let (entries, total) =
+            self.audit_repository
+                .get_all(&filter, pagination.limit(), pagination.offset())?;
+        info!("Got {} of {} audit entries", entries.len(), total);
+        Ok(PaginatedResponse::new(entries, total, pagination))
+    }
+
+    pub fn record_create<T: Serialize>(
+        &self,
+        entity_type: AuditEntityType,
+        entity_id: impl ToString,
+        after: &T,
+    ) {
+        self.record(
+            AuditAction::Create,
+            entity_type,
+            entity_id.to_string(),
+            None,
+            Self::snapshot(after),
+        );
+    }
+
+    pub fn record_update<T: Serialize>(
+        &self,
+        entity_type: AuditEntityType,
+        entity_id: impl ToString,
+        before: &T,
+        after: &T,
+    ) {
+        self.record(
+            AuditAction::Update,
+            entity_type,
+            entity_id.to_string(),
+            Self::snapshot(before),
+            Self::snapshot(after),
+        );
+    }
+
+    pub fn record_delete<T: Serialize>(
+        &self,
+        entity_type: AuditEntityType,
+        entity_id: impl ToString,
+        before: &T,
+    ) {
+        self.record(
+            AuditAction::Delete,
+            entity_type,
+            entity_id.to_string(),
+            Self::snapshot(before),
+            None,
+        );
+    }
+
+    pub fn record_restore<T: Serialize>(
+        &self,
+        entity_type: AuditEntityType,
+        entity_id: impl ToString,
+        after: &T,
+    ) {
+        self.record(
+            AuditAction::Restore,
+            entity_type,
+            entity_id.to_string(),
+            None,
+            Self::snapshot(after),
+        );
+    }
+
+    fn record(
+        &self,
+        action: AuditAction,
+        entity_type: AuditEntityType,
+        entity_id: String,
+        before: Option<Value>,
+        after: Option<Value>,
+    ) {
+        let actor = current_actor();
+        let new_entry = NewAuditEntry {
+            user_id: actor.as_ref().map(|user| user.id),
+            username: actor.map(|user| user.username),
+            action,
+            entity_type,
+            entity_id: entity_id.clone(),
+            before,
+            after,
+            request_id: current_request_id(),
+        };
+        if let Err(err) = self.audit_repository.create(new_entry) {
+            error!(
+                "Failed to write audit entry for {:?} of {:?} with ID {}: {}",
+                action, entity_type, entity_id, err
+            );
+        }
+    }
+
+    fn snapshot<T: Serialize>(value: &T) -> Option<Value> {
+        serde_json::to_value(value)
+            .inspect_err(|err| error!("Failed to serialize audit snapshot: {}", err))
+            .ok()
+    }
+}
+
+impl FromRef<AppState> for AuditService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.audit_service.clone()
+    }
+}
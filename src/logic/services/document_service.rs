@@ -1,132 +1,555 @@
 use std::{
-    fs::{self, File},
-    io::Write,
-    path::Path,
+    collections::HashMap,
+    io,
+    ops::Range,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, ready},
 };
 
-use axum::extract::{FromRef, Multipart};
-use tracing::info;
+use axum::extract::{FromRef, Multipart, multipart::Field};
+use bytes::{Bytes, BytesMut};
+use chrono::{Days, NaiveDate};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use futures_util::{Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::{
     AppState,
+    config::{DocumentConfig, ScheduleConfig},
     error::AppError,
-    logic::repositories::document_repository::DocumentRepository,
-    models::document::{Document, NewDocument},
+    logic::{
+        repositories::document_repository::DocumentRepository,
+        services::audit_service::AuditService,
+        storage::{ByteStream, Storage},
+    },
+    models::{
+        audit::AuditEntityType,
+        document::{
+            Document, DocumentCategory, DocumentDetails, DocumentFileType, DocumentFilter,
+            DocumentIssue, DocumentIssueKind, DocumentOwner, DocumentReportParams, NewDocument,
+            RawUpdateDocument, TeacherDocumentReport, UpdateDocument,
+        },
+        user::{PermissionRole, User},
+        validation::Validate,
+    },
 };
 
+/// Максимальный срок, на который можно построить отчет по документам.
+const MAX_REPORT_DAYS: i64 = 366;
+
 #[derive(Clone)]
 pub struct DocumentService {
     document_repository: DocumentRepository,
+    storage: Arc<dyn Storage>,
+    audit_service: AuditService,
+    config: DocumentConfig,
+    schedule: ScheduleConfig,
+}
+
+/// Файл из запроса, сохраненный в хранилище.
+struct StoredFile {
+    id: Uuid,
+    raw_name: String,
+    storage_key: String,
+    file_type: DocumentFileType,
+    size: u64,
+    checksum: String,
+}
+
+/// Файл из запроса, который передается в хранилище по частям. По мере чтения считается
+/// контрольная сумма файла и проверяется его размер.
+struct FileUpload<'a, 'b> {
+    /// Уже прочитанное начало файла, по которому определялся его тип.
+    head: Option<Bytes>,
+    field: &'a mut Field<'b>,
+    hasher: Sha256,
+    size: u64,
+    max_size: u64,
+    /// Причина, по которой чтение было прервано. Хранилище получает только `io::Error`.
+    error: Option<AppError>,
+}
+
+impl FileUpload<'_, '_> {
+    fn fail(&mut self, error: AppError) -> Poll<Option<Result<Bytes, io::Error>>> {
+        let io_error = io::Error::other(error.to_string());
+        self.error = Some(error);
+        Poll::Ready(Some(Err(io_error)))
+    }
+}
+
+impl Stream for FileUpload<'_, '_> {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let chunk = match self.head.take() {
+            Some(head) => head,
+            None => match ready!(self.field.poll_next_unpin(cx)) {
+                Some(Ok(chunk)) => chunk,
+                Some(Err(e)) => return self.fail(e.into()),
+                None => return Poll::Ready(None),
+            },
+        };
+        self.size += chunk.len() as u64;
+        if self.size > self.max_size {
+            let error = AppError::PayloadTooLarge(format!(
+                "File exceeds maximum size of {} bytes",
+                self.max_size
+            ));
+            return self.fail(error);
+        }
+        self.hasher.update(&chunk);
+        Poll::Ready(Some(Ok(chunk)))
+    }
 }
 
 impl DocumentService {
-    pub fn new(document_repository: DocumentRepository) -> Self {
+    pub fn new(
+        document_repository: DocumentRepository,
+        storage: Arc<dyn Storage>,
+        audit_service: AuditService,
+        config: DocumentConfig,
+        schedule: ScheduleConfig,
+    ) -> Self {
         Self {
             document_repository,
+            storage,
+            audit_service,
+            config,
+            schedule,
         }
     }
 
+    /// Загружает документ и прикрепляет его к записи `owner`. Тип файла определяется по его
+    /// содержимому, заявленный клиентом тип не учитывается. Если к записи уже прикреплен
+    /// документ с таким же содержимым, возвращается 409.
+    ///
+    /// Кроме файла запрос может содержать текстовые поля `category`, `description` и
+    /// `valid_until` в любом порядке.
     pub async fn create(
         &self,
+        user: &User,
         mut multipart: Multipart,
-        teacher_id: i32,
+        owner: DocumentOwner,
+    ) -> Result<Document, AppError> {
+        self.ensure_can_attach(user, owner)?;
+        let mut stored = None;
+        let result = self.save(&mut multipart, owner, &mut stored).await;
+        if let (Err(_), Some(file)) = (&result, &stored) {
+            // Файл без записи в базе никто не найдет и не удалит
+            if let Err(delete_error) = self.storage.delete(&file.storage_key).await {
+                error!(
+                    "Failed to delete file of document with ID {} after failed save: {}",
+                    file.id, delete_error
+                );
+            }
+        }
+        let database_entry = result?;
+
+        self.audit_service.record_create(
+            AuditEntityType::Document,
+            database_entry.id,
+            &database_entry,
+        );
+        info!("Successfully saved document");
+        Ok(database_entry)
+    }
+
+    /// Читает поля запроса, сохраняет файл в хранилище и создает запись о документе.
+    /// Сохраненный файл записывается в `stored`, чтобы при ошибке его можно было удалить.
+    async fn save(
+        &self,
+        multipart: &mut Multipart,
+        owner: DocumentOwner,
+        stored: &mut Option<StoredFile>,
     ) -> Result<Document, AppError> {
-        if let Some(field) = multipart.next_field().await? {
-            let content_type = field
-                .content_type()
-                .ok_or(AppError::BadRequest("Content type is missing".to_string()))?
-                .to_string();
-            let allowed_types = ["image/png", "image/jpeg", "image/webp", "application/pdf"];
-            if !allowed_types.contains(&content_type.as_str()) {
-                return Err(AppError::BadRequest(
-                    "Unsupported file type. Only png/jpeg/webp/pdf allowed.".to_string(),
-                ));
+        let mut details = DocumentDetails::default();
+        while let Some(mut field) = multipart.next_field().await? {
+            match field.name() {
+                Some("category") => {
+                    let value = field.text().await?;
+                    details.category = Some(DocumentCategory::from_name(value.trim()).ok_or(
+                        AppError::BadRequest(format!("Unknown document category {}", value)),
+                    )?);
+                }
+                Some("description") => details.description = Some(field.text().await?),
+                Some("valid_until") => {
+                    let value = field.text().await?;
+                    details.valid_until = Some(
+                        NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+                            AppError::BadRequest(format!(
+                                "valid_until must be a date in YYYY-MM-DD format, got {}",
+                                value
+                            ))
+                        })?,
+                    );
+                }
+                _ if stored.is_none() => *stored = Some(self.store_file(owner, &mut field).await?),
+                _ => {
+                    return Err(AppError::BadRequest(
+                        "Only one file can be uploaded at a time".to_string(),
+                    ));
+                }
             }
+        }
+        let Some(file) = stored else {
+            return Err(AppError::BadRequest("No file uploaded".to_string()));
+        };
+        details.validated()?;
 
-            let name = field
-                .file_name()
-                .ok_or(AppError::BadRequest(
-                    "Failed to determine file_name".to_string(),
-                ))?
-                .to_string();
+        if let Some(duplicate) = self
+            .document_repository
+            .find_by_checksum(owner, &file.checksum)?
+        {
+            return Err(AppError::AlreadyExists(format!(
+                "Document {} with the same file is already attached to {}",
+                duplicate.id, owner
+            )));
+        }
 
-            let new_document = NewDocument { name, teacher_id };
-            let database_entry = self.document_repository.create(new_document)?;
+        let new_document = NewDocument {
+            id: file.id,
+            name: file.file_type.sanitize_file_name(&file.raw_name),
+            owner: owner.into(),
+            content_type: file.file_type.content_type().to_string(),
+            size: file.size as i64,
+            checksum: file.checksum.clone(),
+            storage_key: file.storage_key.clone(),
+            category: details.category.unwrap_or(DocumentCategory::Other),
+            description: details.description,
+            valid_until: details.valid_until,
+        };
+        match self.document_repository.create(new_document) {
+            // Такой же файл успели прикрепить параллельным запросом после проверки выше
+            Err(AppError::Database(DieselError::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                _,
+            ))) => Err(AppError::AlreadyExists(format!(
+                "Document with the same file is already attached to {}",
+                owner
+            ))),
+            result => result,
+        }
+    }
 
-            let file_extension = database_entry.file_extension()?;
-            let file_name = format!("{}.{}", database_entry.id, file_extension);
-            let dir_path = format!("./storage/teachers/{}/", database_entry.teacher_id);
-            let storage_dir = Path::new(&dir_path);
+    /// Проверяет, что запись `owner` существует и пользователь может прикреплять к ней
+    /// документы. Директор может прикреплять документы к любой записи, а преподаватель
+    /// только к группам, которые он ведет, их урокам и ученикам. Документы преподавателей
+    /// прикрепляет только директор.
+    fn ensure_can_attach(&self, user: &User, owner: DocumentOwner) -> Result<(), AppError> {
+        let teacher_id = self.document_repository.get_owner_teacher_id(owner)?;
+        match owner {
+            DocumentOwner::Teacher(_) => Self::ensure_responsible(user, owner, None),
+            _ => Self::ensure_responsible(user, owner, teacher_id),
+        }
+    }
 
-            if let Err(e) = std::fs::create_dir_all(storage_dir) {
-                self.delete(database_entry.id)?;
-                return Err(AppError::InternalServerError(e.to_string()));
+    /// Проверяет, что запись `owner` существует и пользователь может видеть ее документы.
+    /// Документы групп и уроков доступны всем пользователям. Документы преподавателя видят
+    /// директор и сам преподаватель, документы ученика — директор и преподаватель его группы.
+    fn ensure_can_read(&self, user: &User, owner: DocumentOwner) -> Result<(), AppError> {
+        let teacher_id = self.document_repository.get_owner_teacher_id(owner)?;
+        match owner {
+            DocumentOwner::Teacher(_) | DocumentOwner::Student(_) => {
+                Self::ensure_responsible(user, owner, teacher_id)
             }
+            DocumentOwner::StudentGroup(_) | DocumentOwner::Lesson(_) => Ok(()),
+        }
+    }
 
-            let file_path = storage_dir.join(file_name);
-            let mut file = match File::create(&file_path) {
-                Ok(f) => f,
-                Err(e) => {
-                    self.delete(database_entry.id)?;
-                    return Err(AppError::InternalServerError(e.to_string()));
-                }
-            };
+    /// Пропускает директора и преподавателя с ID `teacher_id`, отвечающего за запись `owner`.
+    fn ensure_responsible(
+        user: &User,
+        owner: DocumentOwner,
+        teacher_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        if user.role >= PermissionRole::Director {
+            return Ok(());
+        }
+        if user.teacher_id.is_some() && user.teacher_id == teacher_id {
+            return Ok(());
+        }
+        warn!(
+            "User with ID {} tried to access documents of {} without being responsible for it",
+            user.id, owner
+        );
+        Err(AppError::Forbidden(format!(
+            "Only the director or the responsible teacher can access documents of {}",
+            owner
+        )))
+    }
 
-            let data = field.bytes().await?;
-            let data_vec = data.to_vec();
-            if let Err(e) = file.write_all(&data_vec) {
-                self.delete(database_entry.id)?;
-                return Err(AppError::InternalServerError(e.to_string()));
+    /// Сохраняет файл из запроса в хранилище, передавая его по частям. Загрузка прерывается,
+    /// как только файл превысит допустимый размер или окажется файлом неподдерживаемого типа.
+    async fn store_file(
+        &self,
+        owner: DocumentOwner,
+        field: &mut Field<'_>,
+    ) -> Result<StoredFile, AppError> {
+        let raw_name = field
+            .file_name()
+            .ok_or(AppError::BadRequest(
+                "Failed to determine file_name".to_string(),
+            ))?
+            .to_string();
+        // Тип файла нужен до начала загрузки: от него зависят ключ и Content-Type файла
+        let mut head = BytesMut::new();
+        while head.len() < DocumentFileType::SIGNATURE_LENGTH {
+            match field.chunk().await? {
+                Some(chunk) => head.extend_from_slice(&chunk),
+                None => break,
             }
+        }
+        let file_type = Self::detect_file_type(&head)?;
 
-            info!("Successfully saved document");
-            Ok(database_entry)
-        } else {
-            Err(AppError::BadRequest("No file uploaded".to_string()))
+        let id = Uuid::new_v4();
+        let storage_key = format!(
+            "{}/{}.{}",
+            owner.storage_prefix(),
+            id,
+            file_type.extension()
+        );
+        let mut upload = FileUpload {
+            head: Some(head.freeze()),
+            field,
+            hasher: Sha256::new(),
+            size: 0,
+            max_size: self.config.max_size,
+            error: None,
+        };
+        if let Err(e) = self
+            .storage
+            .put(&storage_key, &mut upload, file_type.content_type())
+            .await
+        {
+            return Err(upload.error.take().unwrap_or(e));
         }
+        Ok(StoredFile {
+            id,
+            raw_name,
+            storage_key,
+            file_type,
+            size: upload.size,
+            checksum: format!("{:x}", upload.hasher.finalize()),
+        })
     }
 
-    pub fn get(&self, document_id: Uuid) -> Result<Document, AppError> {
+    fn detect_file_type(head: &[u8]) -> Result<DocumentFileType, AppError> {
+        DocumentFileType::detect(head).ok_or(AppError::BadRequest(
+            "Unsupported file type. Only png/jpeg/webp/pdf allowed.".to_string(),
+        ))
+    }
+
+    /// Документ с ID `document_id`, если пользователь может видеть документы его владельца.
+    pub fn get(&self, user: &User, document_id: Uuid) -> Result<Document, AppError> {
         let document = self.document_repository.get(document_id)?;
+        self.ensure_can_read(user, document.owner()?)?;
         info!("Document with ID {} successfully get", document_id);
         Ok(document)
     }
 
-    pub fn get_by_teacher_id(&self, teacher_id: i32) -> Result<Vec<Document>, AppError> {
-        let documents = self.document_repository.get_by_teacher_id(teacher_id)?;
-        info!("Got all documents for teacher with ID {}", teacher_id);
+    pub async fn get_content_size(&self, document: &Document) -> Result<u64, AppError> {
+        self.storage.size(&document.storage_key).await
+    }
+
+    /// Содержимое файла документа целиком или только байты из `range`.
+    pub async fn get_content(
+        &self,
+        document: &Document,
+        range: Option<Range<u64>>,
+    ) -> Result<ByteStream, AppError> {
+        let content = self
+            .storage
+            .get(&document.storage_key, range.clone())
+            .await?;
+        match range {
+            Some(range) => info!(
+                "Streaming bytes {}-{} of document with ID {}",
+                range.start,
+                range.end - 1,
+                document.id
+            ),
+            None => info!("Streaming document with ID {}", document.id),
+        }
+        Ok(content)
+    }
+
+    /// Документы записи `owner`. Если записи нет или она удалена, возвращается 404.
+    pub fn get_by_owner(
+        &self,
+        user: &User,
+        owner: DocumentOwner,
+        filter: &DocumentFilter,
+    ) -> Result<Vec<Document>, AppError> {
+        if filter.expires_within_days.is_some_and(|days| days < 0) {
+            return Err(AppError::BadRequest(
+                "expires_within_days must not be negative".to_string(),
+            ));
+        }
+        self.ensure_can_read(user, owner)?;
+        let documents =
+            self.document_repository
+                .get_by_owner(owner, filter, self.schedule.today())?;
+        info!("Got all documents for {}", owner);
         Ok(documents)
     }
 
-    pub fn delete(&self, document_id: Uuid) -> Result<bool, AppError> {
+    /// Меняет вид, описание или срок действия документа.
+    pub fn update(
+        &self,
+        document_id: Uuid,
+        raw_update_document: RawUpdateDocument,
+    ) -> Result<Document, AppError> {
         let document = self.document_repository.get(document_id)?;
+        let update_document = UpdateDocument::from(raw_update_document);
+        if update_document.is_empty() {
+            return Ok(document);
+        }
+        let updated_document = self
+            .document_repository
+            .update(document_id, update_document)?;
+        self.audit_service.record_update(
+            AuditEntityType::Document,
+            document_id,
+            &document,
+            &updated_document,
+        );
+        info!("Document with ID {} successfully updated", document_id);
+        Ok(updated_document)
+    }
+
+    /// Преподаватели, у которых нет обязательного документа какого-либо вида или срок его
+    /// действия истек либо истекает в ближайшие `days` дней. Из нескольких документов
+    /// одного вида учитывается действующий дольше всех, бессрочный документ не истекает.
+    pub fn get_expiry_report(
+        &self,
+        params: &DocumentReportParams,
+    ) -> Result<Vec<TeacherDocumentReport>, AppError> {
+        let days = params.days.unwrap_or(self.config.expiry_warning_days);
+        if !(0..=MAX_REPORT_DAYS).contains(&days) {
+            return Err(AppError::BadRequest(format!(
+                "days must be between 0 and {}",
+                MAX_REPORT_DAYS
+            )));
+        }
+        let today = self.schedule.today();
+        let warning_date = today + Days::new(days as u64);
 
-        let file_extension = document.file_extension()?;
-        let teacher_id = document.teacher_id;
+        let rows = self
+            .document_repository
+            .get_teachers_with_documents(&self.config.mandatory_categories)?;
+        let mut reports: Vec<TeacherDocumentReport> = Vec::new();
+        let mut latest: HashMap<DocumentCategory, &Document> = HashMap::new();
+        let mut rows = rows.iter().peekable();
+        while let Some((teacher, document)) = rows.next() {
+            if let Some(document) = document {
+                let current = latest.entry(document.category).or_insert(document);
+                if Self::lasts_longer(document, current) {
+                    *current = document;
+                }
+            }
+            // Строки одного преподавателя идут подряд
+            if rows
+                .peek()
+                .is_some_and(|(next_teacher, _)| next_teacher.id == teacher.id)
+            {
+                continue;
+            }
 
-        let dir_path = format!("./storage/teachers/{}/", teacher_id);
-        let file_name = document_id.to_string() + "." + file_extension;
-        let full_path = Path::new(&dir_path).join(file_name);
+            let issues: Vec<DocumentIssue> = self
+                .config
+                .mandatory_categories
+                .iter()
+                .filter_map(|&category| {
+                    let Some(document) = latest.get(&category) else {
+                        return Some(DocumentIssue {
+                            category,
+                            kind: DocumentIssueKind::Missing,
+                            document_id: None,
+                            valid_until: None,
+                        });
+                    };
+                    let valid_until = document.valid_until?;
+                    let kind = if valid_until < today {
+                        DocumentIssueKind::Expired
+                    } else if valid_until <= warning_date {
+                        DocumentIssueKind::Expiring
+                    } else {
+                        return None;
+                    };
+                    Some(DocumentIssue {
+                        category,
+                        kind,
+                        document_id: Some(document.id),
+                        valid_until: Some(valid_until),
+                    })
+                })
+                .collect();
+            latest.clear();
+            if !issues.is_empty() {
+                reports.push(TeacherDocumentReport {
+                    teacher_id: teacher.id,
+                    teacher_name: teacher.name.clone(),
+                    issues,
+                });
+            }
+        }
+        info!(
+            "Document expiry report built, {} teachers need attention",
+            reports.len()
+        );
+        Ok(reports)
+    }
 
-        if full_path.exists() {
-            fs::remove_file(&full_path)?;
-            info!("File at {} successfully deleted", full_path.display());
+    /// Действует ли `document` дольше, чем `other`. Бессрочный документ действует дольше
+    /// любого другого.
+    fn lasts_longer(document: &Document, other: &Document) -> bool {
+        match (document.valid_until, other.valid_until) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(document_date), Some(other_date)) => document_date > other_date,
+        }
+    }
 
-            let deleted_count = self.document_repository.delete(document_id)?;
+    /// Удаляет документ с ID `document_id`, прикрепленный к записи `owner`. Документ другой
+    /// записи считается не найденным.
+    pub async fn delete(&self, owner: DocumentOwner, document_id: Uuid) -> Result<bool, AppError> {
+        let document = self.document_repository.get(document_id)?;
+        if document.owner()? != owner {
+            return Err(AppError::NotFound(format!(
+                "Document with ID {} not found for {}",
+                document_id, owner
+            )));
+        }
 
-            if deleted_count > 0 {
-                info!("Document with ID {} successfully deleted", document_id);
-                Ok(true)
-            } else {
-                info!("Document with ID {} not found", document_id);
-                Ok(false)
+        let deleted_count = self.document_repository.delete(document_id)?;
+        if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::Document, document_id, &document);
+            // Запись уже удалена, поэтому оставшийся в хранилище файл недоступен и не мешает
+            // удалению документа
+            if let Err(delete_error) = self.storage.delete(&document.storage_key).await {
+                error!(
+                    "Failed to delete file of deleted document with ID {}: {}",
+                    document_id, delete_error
+                );
             }
+            info!("Document with ID {} successfully deleted", document_id);
+            Ok(true)
         } else {
-            info!("File at {} not found", full_path.display());
+            info!("Document with ID {} not found", document_id);
             Ok(false)
         }
     }
+
+    /// Удаляет из хранилища файлы документов записи `owner`.
+    pub async fn delete_owner_files(&self, owner: DocumentOwner) -> Result<(), AppError> {
+        self.storage.delete_prefix(&owner.storage_prefix()).await?;
+        info!("Files of {} successfully deleted", owner);
+        Ok(())
+    }
 }
 
 impl FromRef<AppState> for DocumentService {
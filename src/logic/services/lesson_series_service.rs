@@ -0,0 +1,350 @@
+use axum::extract::FromRef;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use diesel::PgConnection;
+use tracing::info;
+
+use crate::{
+    AppState,
+    config::ScheduleConfig,
+    error::AppError,
+    logic::{
+        repositories::lesson_series_repository::LessonSeriesRepository,
+        services::{audit_service::AuditService, lesson_service::LessonService},
+    },
+    models::{
+        audit::AuditEntityType,
+        lesson::{Lesson, NewLesson},
+        lesson_series::{
+            LessonSeries, LessonSeriesWithLessons, NewLessonSeries, OccurrenceScope,
+            RawNewLessonSeries, RawUpdateLessonSeries,
+        },
+    },
+};
+
+/// Дата занятия по правилу серии и фактическое время его начала и окончания.
+type Occurrence = (NaiveDate, DateTime<Utc>, DateTime<Utc>);
+
+/// Уроки, удаленные при завершении серии, и обрезанная серия. `None` - у серии не осталось
+/// занятий, и она удалена.
+type EndedSeries = (Vec<Lesson>, Option<LessonSeries>);
+
+#[derive(Clone)]
+pub struct LessonSeriesService {
+    lesson_series_repository: LessonSeriesRepository,
+    lesson_service: LessonService,
+    audit_service: AuditService,
+    schedule: ScheduleConfig,
+}
+
+impl LessonSeriesService {
+    pub fn new(
+        lesson_series_repository: LessonSeriesRepository,
+        lesson_service: LessonService,
+        audit_service: AuditService,
+        schedule: ScheduleConfig,
+    ) -> Self {
+        Self {
+            lesson_series_repository,
+            lesson_service,
+            audit_service,
+            schedule,
+        }
+    }
+
+    pub fn create(
+        &self,
+        raw_new_series: RawNewLessonSeries,
+    ) -> Result<LessonSeriesWithLessons, AppError> {
+        let new_series = NewLessonSeries::try_from(raw_new_series)?;
+        self.lesson_service
+            .validate_room(new_series.room_id, new_series.student_group_id)?;
+        let occurrences = self.plan_occurrences(&new_series, &[])?;
+        self.ensure_no_conflicts(&new_series, &occurrences, &[])?;
+
+        let result = self.lesson_service.transaction(|connection| {
+            let series = LessonSeriesRepository::insert(connection, &new_series)?;
+            let lesson_ids = self.materialize(connection, &series, &occurrences)?;
+            Ok((series, lesson_ids))
+        });
+        let (series, lesson_ids) =
+            self.map_overlap_error(result, &new_series, &occurrences, &[])?;
+        self.audit_service
+            .record_create(AuditEntityType::LessonSeries, series.id, &series);
+        for lesson_id in lesson_ids {
+            self.lesson_service.get_created(lesson_id)?;
+        }
+        info!("Successfully created lesson series with ID {}", series.id);
+        self.get(series.id)
+    }
+
+    pub fn get(&self, series_id: i32) -> Result<LessonSeriesWithLessons, AppError> {
+        let series = self.lesson_series_repository.get(series_id)?;
+        let cancelled_dates = self
+            .lesson_series_repository
+            .get_cancelled_dates(series_id)?;
+        let lessons = self.lesson_service.get_lessons_by_series_id(series_id)?;
+        info!("Lesson series with ID {} successfully get", series_id);
+        Ok(LessonSeriesWithLessons {
+            series,
+            cancelled_dates,
+            lessons,
+        })
+    }
+
+    /// Изменяет занятие `date` и все следующие за ним.
+    ///
+    /// Исходная серия заканчивается перед `date`, а для оставшихся занятий создается новая
+    /// серия с измененными параметрами. Отмененные занятия остаются отмененными.
+    pub fn update_following(
+        &self,
+        series_id: i32,
+        date: NaiveDate,
+        raw_update_series: RawUpdateLessonSeries,
+    ) -> Result<LessonSeriesWithLessons, AppError> {
+        let series = self.lesson_series_repository.get(series_id)?;
+        let passed_occurrences = series.passed_occurrences(date)?;
+        let new_series =
+            raw_update_series.into_new_lesson_series(&series, date, passed_occurrences)?;
+
+        let new_dates = new_series.occurrence_dates();
+        let cancelled_dates: Vec<NaiveDate> = self
+            .lesson_series_repository
+            .get_cancelled_dates(series_id)?
+            .into_iter()
+            .filter(|cancelled_date| new_dates.contains(cancelled_date))
+            .collect();
+        self.lesson_service
+            .validate_room(new_series.room_id, new_series.student_group_id)?;
+        let occurrences = self.plan_occurrences(&new_series, &cancelled_dates)?;
+        let replaced_lesson_ids: Vec<i32> = self
+            .lesson_service
+            .get_lessons_by_series_id(series_id)?
+            .into_iter()
+            .filter(|lesson| {
+                lesson
+                    .series_date
+                    .is_some_and(|series_date| series_date >= date)
+            })
+            .map(|lesson| lesson.id)
+            .collect();
+        self.ensure_no_conflicts(&new_series, &occurrences, &replaced_lesson_ids)?;
+
+        let result = self.lesson_service.transaction(|connection| {
+            let ended_series = self.end_before(connection, &series, date, passed_occurrences)?;
+            let updated_series = LessonSeriesRepository::insert(connection, &new_series)?;
+            LessonSeriesRepository::add_cancelled_dates(
+                connection,
+                updated_series.id,
+                &cancelled_dates,
+            )?;
+            let lesson_ids = self.materialize(connection, &updated_series, &occurrences)?;
+            Ok((ended_series, updated_series, lesson_ids))
+        });
+        let (ended_series, updated_series, lesson_ids) =
+            self.map_overlap_error(result, &new_series, &occurrences, &replaced_lesson_ids)?;
+        self.record_end(&series, ended_series);
+        self.audit_service.record_create(
+            AuditEntityType::LessonSeries,
+            updated_series.id,
+            &updated_series,
+        );
+        for lesson_id in lesson_ids {
+            self.lesson_service.get_created(lesson_id)?;
+        }
+        info!(
+            "Lesson series with ID {} was split at {} into lesson series with ID {}",
+            series_id, date, updated_series.id
+        );
+        self.get(updated_series.id)
+    }
+
+    /// Отменяет занятие `date` или, для `OccurrenceScope::Following`, его и все следующие.
+    pub fn cancel(
+        &self,
+        series_id: i32,
+        date: NaiveDate,
+        scope: OccurrenceScope,
+    ) -> Result<(), AppError> {
+        let series = self.lesson_series_repository.get(series_id)?;
+        let passed_occurrences = series.passed_occurrences(date)?;
+
+        match scope {
+            OccurrenceScope::This => {
+                let lesson = self
+                    .lesson_service
+                    .get_lessons_by_series_id(series_id)?
+                    .into_iter()
+                    .find(|lesson| lesson.series_date == Some(date));
+                match lesson {
+                    Some(lesson) => {
+                        self.lesson_service.delete(lesson.id)?;
+                    }
+                    None => {
+                        self.lesson_service.transaction(|connection| {
+                            LessonSeriesRepository::add_cancelled_dates(
+                                connection,
+                                series_id,
+                                &[date],
+                            )
+                        })?;
+                    }
+                }
+            }
+            OccurrenceScope::Following => {
+                let ended_series = self.lesson_service.transaction(|connection| {
+                    self.end_before(connection, &series, date, passed_occurrences)
+                })?;
+                self.record_end(&series, ended_series);
+            }
+        }
+        info!(
+            "Cancelled occurrence {} of lesson series {} ({})",
+            date,
+            series_id,
+            match scope {
+                OccurrenceScope::This => "this",
+                OccurrenceScope::Following => "this and following",
+            }
+        );
+        Ok(())
+    }
+
+    /// Удаляет уроки серии начиная с `date` и завершает серию перед этой датой в рамках
+    /// транзакции `connection`. Серия, у которой не осталось занятий, удаляется. Изменения
+    /// записываются в журнал после транзакции, см. [`Self::record_end`].
+    fn end_before(
+        &self,
+        connection: &mut PgConnection,
+        series: &LessonSeries,
+        date: NaiveDate,
+        passed_occurrences: usize,
+    ) -> Result<EndedSeries, AppError> {
+        let deleted_lessons = self
+            .lesson_service
+            .delete_series_lessons_from(connection, series.id, date)?;
+
+        let truncated_series = match passed_occurrences.checked_sub(1) {
+            Some(last_index) => {
+                let last_date = series.occurrence_dates()[last_index];
+                Some(LessonSeriesRepository::truncate(
+                    connection, series.id, last_date,
+                )?)
+            }
+            None => {
+                LessonSeriesRepository::delete(connection, series.id)?;
+                None
+            }
+        };
+        Ok((deleted_lessons, truncated_series))
+    }
+
+    /// Записывает в журнал изменения, сделанные [`Self::end_before`].
+    fn record_end(&self, series: &LessonSeries, (deleted_lessons, truncated_series): EndedSeries) {
+        for lesson in &deleted_lessons {
+            self.audit_service
+                .record_delete(AuditEntityType::Lesson, lesson.id, lesson);
+        }
+        match truncated_series {
+            Some(truncated_series) => self.audit_service.record_update(
+                AuditEntityType::LessonSeries,
+                series.id,
+                series,
+                &truncated_series,
+            ),
+            None => {
+                self.audit_service
+                    .record_delete(AuditEntityType::LessonSeries, series.id, series);
+                info!("Lesson series with ID {} was deleted", series.id);
+            }
+        }
+    }
+
+    fn plan_occurrences(
+        &self,
+        new_series: &NewLessonSeries,
+        cancelled_dates: &[NaiveDate],
+    ) -> Result<Vec<Occurrence>, AppError> {
+        let duration = TimeDelta::minutes(new_series.duration_minutes.into());
+        new_series
+            .planned_dates(cancelled_dates)
+            .into_iter()
+            .map(|date| {
+                let starts_at = self.schedule.local_to_utc(date, new_series.start_time)?;
+                let ends_at = starts_at + duration;
+                self.lesson_service
+                    .validate_lesson_time(starts_at, ends_at)?;
+                Ok((date, starts_at, ends_at))
+            })
+            .collect()
+    }
+
+    fn ensure_no_conflicts(
+        &self,
+        new_series: &NewLessonSeries,
+        occurrences: &[Occurrence],
+        exclude_lesson_ids: &[i32],
+    ) -> Result<(), AppError> {
+        self.lesson_service.ensure_no_conflicts(
+            &Self::slots(occurrences),
+            new_series.student_group_id,
+            new_series.room_id,
+            exclude_lesson_ids,
+        )
+    }
+
+    /// См. [`LessonService::map_overlap_error`].
+    fn map_overlap_error<T>(
+        &self,
+        result: Result<T, AppError>,
+        new_series: &NewLessonSeries,
+        occurrences: &[Occurrence],
+        exclude_lesson_ids: &[i32],
+    ) -> Result<T, AppError> {
+        self.lesson_service.map_overlap_error(
+            result,
+            &Self::slots(occurrences),
+            new_series.student_group_id,
+            new_series.room_id,
+            exclude_lesson_ids,
+        )
+    }
+
+    fn slots(occurrences: &[Occurrence]) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        occurrences
+            .iter()
+            .map(|(_, starts_at, ends_at)| (*starts_at, *ends_at))
+            .collect()
+    }
+
+    /// Сохраняет уроки серии в рамках транзакции `connection` и возвращает их ID.
+    fn materialize(
+        &self,
+        connection: &mut PgConnection,
+        series: &LessonSeries,
+        occurrences: &[Occurrence],
+    ) -> Result<Vec<i32>, AppError> {
+        occurrences
+            .iter()
+            .map(|(date, starts_at, ends_at)| {
+                self.lesson_service.insert_lesson(
+                    connection,
+                    &NewLesson {
+                        topic: series.topic.clone(),
+                        student_group_id: series.student_group_id,
+                        starts_at: *starts_at,
+                        ends_at: *ends_at,
+                        lesson_series_id: Some(series.id),
+                        series_date: Some(*date),
+                        room_id: series.room_id,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+impl FromRef<AppState> for LessonSeriesService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.lesson_series_service.clone()
+    }
+}
@@ -1,49 +1,163 @@
 use axum::extract::FromRef;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::{PgConnection, result::Error as DieselError};
 use tracing::{info, warn};
 
 use crate::{
     AppState,
+    config::ScheduleConfig,
     error::AppError,
     logic::{
-        repositories::lesson_repository::LessonRepository,
-        services::attendance_service::AttendanceService,
+        repositories::{
+            lesson_repository::LessonRepository, lesson_series_repository::LessonSeriesRepository,
+        },
+        services::{
+            attendance_service::AttendanceService, audit_service::AuditService,
+            room_service::RoomService,
+        },
+    },
+    models::{
+        audit::AuditEntityType,
+        lesson::{
+            Lesson, LessonFilter, LessonWithRelations, NewLesson, RawNewLesson, RawUpdateLesson,
+        },
+        lesson_conflict::{ConflictReason, LessonConflict, LessonConflictCheck},
+        pagination::{PaginatedResponse, PaginationParams},
     },
-    models::lesson::{Lesson, LessonWithRelations, NewLesson, UpdateLesson},
 };
 
+/// Ограничения базы, которые не дают сохранить пересекающиеся уроки одной группы или одного
+/// кабинета.
+const OVERLAP_CONSTRAINTS: [&str; 2] = [
+    "lessons_room_id_overlap",
+    "lessons_student_group_id_overlap",
+];
+
 #[derive(Clone)]
 pub struct LessonService {
     lesson_repository: LessonRepository,
     attendance_service: AttendanceService,
+    room_service: RoomService,
+    audit_service: AuditService,
+    schedule: ScheduleConfig,
 }
 
 impl LessonService {
-    pub fn new(lesson_repository: LessonRepository, attendance_service: AttendanceService) -> Self {
+    pub fn new(
+        lesson_repository: LessonRepository,
+        attendance_service: AttendanceService,
+        room_service: RoomService,
+        audit_service: AuditService,
+        schedule: ScheduleConfig,
+    ) -> Self {
         Self {
             lesson_repository,
             attendance_service,
+            room_service,
+            audit_service,
+            schedule,
         }
     }
 
-    pub fn create(&self, new_lesson: NewLesson) -> Result<LessonWithRelations, AppError> {
-        let lesson_full = self.lesson_repository.create(new_lesson)?;
-        if let Some(student_group_id) = lesson_full.lesson.student_group_id {
-            self.attendance_service
-                .create_attendances_for_group(lesson_full.lesson.id, student_group_id)?;
+    pub fn create(&self, raw_new_lesson: RawNewLesson) -> Result<LessonWithRelations, AppError> {
+        let new_lesson = raw_new_lesson.into_new_lesson(&self.schedule)?;
+        self.validate_room(new_lesson.room_id, new_lesson.student_group_id)?;
+        self.ensure_no_conflicts(
+            &[(new_lesson.starts_at, new_lesson.ends_at)],
+            new_lesson.student_group_id,
+            new_lesson.room_id,
+            &[],
+        )?;
+        self.create_lesson(new_lesson)
+    }
+
+    /// Сохраняет урок и создает посещения для студентов его группы. Пересечения с другими
+    /// уроками заново проверяются при сохранении.
+    pub fn create_lesson(&self, new_lesson: NewLesson) -> Result<LessonWithRelations, AppError> {
+        let slots = [(new_lesson.starts_at, new_lesson.ends_at)];
+        let lesson_id = self.map_overlap_error(
+            self.transaction(|connection| self.insert_lesson(connection, &new_lesson)),
+            &slots,
+            new_lesson.student_group_id,
+            new_lesson.room_id,
+            &[],
+        )?;
+        self.get_created(lesson_id)
+    }
+
+    /// Сохраняет урок и создает посещения для студентов его группы в рамках транзакции
+    /// `connection`. Если урок пересекается с уже сохраненными, возвращается
+    /// `AppError::Conflict` без списка уроков, его добавляет [`Self::map_overlap_error`].
+    /// Создание урока записывается в журнал уже после транзакции, см. [`Self::get_created`].
+    pub fn insert_lesson(
+        &self,
+        connection: &mut PgConnection,
+        new_lesson: &NewLesson,
+    ) -> Result<i32, AppError> {
+        self.validate_lesson_time(new_lesson.starts_at, new_lesson.ends_at)?;
+        Self::ensure_no_overlap(
+            connection,
+            (new_lesson.starts_at, new_lesson.ends_at),
+            new_lesson.student_group_id,
+            new_lesson.room_id,
+            &[],
+        )?;
+        let lesson_id = LessonRepository::insert(connection, new_lesson)?;
+        if let Some(student_group_id) = new_lesson.student_group_id {
+            self.attendance_service.create_attendances_for_group(
+                connection,
+                lesson_id,
+                student_group_id,
+                self.schedule.local_date(new_lesson.starts_at),
+            )?;
         }
-        info!(
-            "Successfully created lesson with ID {}",
-            lesson_full.lesson.id
-        );
+        Ok(lesson_id)
+    }
+
+    /// Загружает урок, сохраненный [`Self::insert_lesson`], и записывает его создание
+    /// в журнал.
+    pub fn get_created(&self, lesson_id: i32) -> Result<LessonWithRelations, AppError> {
+        let lesson_full = self.lesson_repository.get(lesson_id, false)?;
+        self.audit_service
+            .record_create(AuditEntityType::Lesson, lesson_id, &lesson_full);
+        info!("Successfully created lesson with ID {}", lesson_id);
         Ok(lesson_full)
     }
 
-    pub fn get(&self, lesson_id: i32) -> Result<LessonWithRelations, AppError> {
-        let lesson = self.lesson_repository.get(lesson_id)?;
+    /// Выполняет `f` в одной транзакции, см. [`LessonRepository::transaction`].
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce(&mut PgConnection) -> Result<T, AppError>,
+    ) -> Result<T, AppError> {
+        self.lesson_repository.transaction(f)
+    }
+
+    pub fn get(
+        &self,
+        lesson_id: i32,
+        include_deleted: bool,
+    ) -> Result<LessonWithRelations, AppError> {
+        let lesson = self.lesson_repository.get(lesson_id, include_deleted)?;
         info!("Lesson with ID {} successfully get", lesson_id);
         Ok(lesson)
     }
 
+    pub fn get_all(
+        &self,
+        filter: LessonFilter,
+        pagination: &PaginationParams,
+        include_deleted: bool,
+    ) -> Result<PaginatedResponse<LessonWithRelations>, AppError> {
+        let (lessons, total) = self.lesson_repository.get_all(
+            &filter,
+            pagination.limit(),
+            pagination.offset(),
+            include_deleted,
+        )?;
+        info!("Got {} of {} lessons", lessons.len(), total);
+        Ok(PaginatedResponse::new(lessons, total, pagination))
+    }
+
     pub fn get_lessons_by_group_id(&self, student_group_id: i32) -> Result<Vec<Lesson>, AppError> {
         let lessons = self
             .lesson_repository
@@ -52,35 +166,378 @@ impl LessonService {
         Ok(lessons)
     }
 
+    pub fn get_lessons_by_series_id(&self, series_id: i32) -> Result<Vec<Lesson>, AppError> {
+        let lessons = self.lesson_repository.get_lessons_by_series_id(series_id)?;
+        info!("Got lessons for lesson series with ID {}", series_id);
+        Ok(lessons)
+    }
+
+    pub fn get_lessons_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<LessonWithRelations>, AppError> {
+        let lessons = self.lesson_repository.get_between(from, to)?;
+        info!("Got {} lessons from {} to {}", lessons.len(), from, to);
+        Ok(lessons)
+    }
+
     pub fn update(
         &self,
         lesson_id: i32,
-        update_lesson: UpdateLesson,
+        raw_update_lesson: RawUpdateLesson,
     ) -> Result<LessonWithRelations, AppError> {
-        let lesson = self.lesson_repository.get(lesson_id)?.lesson;
-        if lesson.student_group_id != update_lesson.student_group_id {
-            self.attendance_service.delete_by_lesson_id(lesson_id)?;
+        let lesson_full = self.lesson_repository.get(lesson_id, false)?;
+        let lesson = &lesson_full.lesson;
+        let update_lesson = raw_update_lesson.into_update_lesson(lesson, &self.schedule)?;
+        self.validate_lesson_time(
+            update_lesson.starts_at.unwrap_or(lesson.starts_at),
+            update_lesson.ends_at.unwrap_or(lesson.ends_at),
+        )?;
+        let slots = [(
+            update_lesson.starts_at.unwrap_or(lesson.starts_at),
+            update_lesson.ends_at.unwrap_or(lesson.ends_at),
+        )];
+        let student_group_id = update_lesson
+            .student_group_id
+            .unwrap_or(lesson.student_group_id);
+        let room_id = update_lesson.room_id.unwrap_or(lesson.room_id);
+        if update_lesson.student_group_id.is_some() || update_lesson.room_id.is_some() {
+            self.validate_room(room_id, student_group_id)?;
+        }
+        let placement_changed = update_lesson.starts_at.is_some()
+            || update_lesson.ends_at.is_some()
+            || update_lesson.student_group_id.is_some()
+            || update_lesson.room_id.is_some();
+        if placement_changed {
+            self.ensure_no_conflicts(&slots, student_group_id, room_id, &[lesson_id])?;
+        }
 
-            if let Some(student_group_id) = update_lesson.student_group_id {
-                self.attendance_service
-                    .create_attendances_for_group(lesson_id, student_group_id)?;
+        // Посещения пересоздаются только при явной смене группы: поле, не переданное
+        // в запросе, группу не меняет
+        let group_changed =
+            update_lesson.student_group_id.is_some() && student_group_id != lesson.student_group_id;
+        let lesson_date = self.schedule.local_date(slots[0].0);
+        let result = self.transaction(|connection| {
+            if placement_changed {
+                Self::ensure_no_overlap(
+                    connection,
+                    slots[0],
+                    student_group_id,
+                    room_id,
+                    &[lesson_id],
+                )?;
             }
-        }
+            LessonRepository::update(connection, lesson_id, &update_lesson)?;
+            if !group_changed {
+                return Ok(Vec::new());
+            }
+            let deleted_attendances = self
+                .attendance_service
+                .delete_by_lesson_id(connection, lesson_id)?;
+            if let Some(student_group_id) = student_group_id {
+                self.attendance_service.create_attendances_for_group(
+                    connection,
+                    lesson_id,
+                    student_group_id,
+                    lesson_date,
+                )?;
+            }
+            Ok(deleted_attendances)
+        });
+        let deleted_attendances =
+            self.map_overlap_error(result, &slots, student_group_id, room_id, &[lesson_id])?;
+        self.attendance_service.record_deleted(&deleted_attendances);
 
-        let updated_lesson = self.lesson_repository.update(lesson_id, update_lesson)?;
+        let updated_lesson = self.lesson_repository.get(lesson_id, false)?;
+        self.audit_service.record_update(
+            AuditEntityType::Lesson,
+            lesson_id,
+            &lesson_full,
+            &updated_lesson,
+        );
         info!("Lesson with ID {} was successfully updated", lesson_id);
         Ok(updated_lesson)
     }
 
+    /// Помечает урок удаленным. Если урок был создан серией, его дата запоминается как
+    /// отмененная, чтобы серия не создала его повторно.
     pub fn delete(&self, lesson_id: i32) -> Result<bool, AppError> {
-        let deleted_count = self.lesson_repository.delete(lesson_id)?;
-        if deleted_count > 0 {
-            info!("Lesson with ID {} was successfully deleted", lesson_id);
-            Ok(true)
+        let lesson_full = match self.lesson_repository.get(lesson_id, false) {
+            Ok(lesson_full) => lesson_full,
+            Err(AppError::NotFound(_)) => {
+                warn!("Lesson with ID {} not found", lesson_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let lesson = &lesson_full.lesson;
+        self.transaction(|connection| {
+            LessonRepository::delete(connection, lesson_id)?;
+            if let (Some(series_id), Some(series_date)) =
+                (lesson.lesson_series_id, lesson.series_date)
+            {
+                LessonSeriesRepository::add_cancelled_dates(connection, series_id, &[series_date])?;
+                info!(
+                    "Occurrence {} of lesson series {} was cancelled",
+                    series_date, series_id
+                );
+            }
+            Ok(())
+        })?;
+        self.audit_service
+            .record_delete(AuditEntityType::Lesson, lesson_id, &lesson_full);
+        info!("Lesson with ID {} was successfully deleted", lesson_id);
+        Ok(true)
+    }
+
+    /// Восстанавливает удаленный урок, если он не пересекается с другими уроками. Дата
+    /// урока серии перестает считаться отмененной.
+    pub fn restore(&self, lesson_id: i32) -> Result<LessonWithRelations, AppError> {
+        let lesson = self.lesson_repository.get(lesson_id, true)?.lesson;
+        if lesson.deleted_at.is_none() {
+            return Err(AppError::NotFound(format!(
+                "Deleted lesson with ID {} not found",
+                lesson_id
+            )));
+        }
+        let slots = [(lesson.starts_at, lesson.ends_at)];
+        self.ensure_no_conflicts(&slots, lesson.student_group_id, lesson.room_id, &[])?;
+
+        let result = self.transaction(|connection| {
+            Self::ensure_no_overlap(
+                connection,
+                slots[0],
+                lesson.student_group_id,
+                lesson.room_id,
+                &[lesson_id],
+            )?;
+            LessonRepository::restore(connection, lesson_id)?;
+            if let (Some(series_id), Some(series_date)) =
+                (lesson.lesson_series_id, lesson.series_date)
+            {
+                LessonSeriesRepository::remove_cancelled_date(connection, series_id, series_date)?;
+            }
+            Ok(())
+        });
+        self.map_overlap_error(result, &slots, lesson.student_group_id, lesson.room_id, &[])?;
+        let restored_lesson = self.lesson_repository.get(lesson_id, false)?;
+        self.audit_service
+            .record_restore(AuditEntityType::Lesson, lesson_id, &restored_lesson);
+        info!("Lesson with ID {} was successfully restored", lesson_id);
+        Ok(restored_lesson)
+    }
+
+    /// Помечает удаленными все уроки серии, начиная с даты `from` по правилу серии, в рамках
+    /// транзакции `connection` и возвращает их. Отметки посещаемости и документы уроков
+    /// сохраняются, а даты не считаются отмененными: серия сама заканчивается перед `from`.
+    /// Удаление уроков записывает в журнал вызывающий код после транзакции.
+    pub fn delete_series_lessons_from(
+        &self,
+        connection: &mut PgConnection,
+        series_id: i32,
+        from: NaiveDate,
+    ) -> Result<Vec<Lesson>, AppError> {
+        let lessons = LessonRepository::delete_series_lessons_from(connection, series_id, from)?;
+        info!(
+            "Deleted {} lessons of lesson series {} starting from {}",
+            lessons.len(),
+            series_id,
+            from
+        );
+        Ok(lessons)
+    }
+
+    /// Проверка урока на пересечения без сохранения.
+    pub fn check_conflicts(
+        &self,
+        check: LessonConflictCheck,
+    ) -> Result<Vec<LessonConflict>, AppError> {
+        let ends_at = check
+            .ends_at
+            .unwrap_or(check.starts_at + self.schedule.default_lesson_duration);
+        self.validate_lesson_time(check.starts_at, ends_at)?;
+
+        let exclude_lesson_ids: Vec<i32> = check.lesson_id.into_iter().collect();
+        let conflicts = self.find_conflicts(
+            &[(check.starts_at, ends_at)],
+            check.student_group_id,
+            check.room_id,
+            &exclude_lesson_ids,
+        )?;
+        info!("Found {} conflicting lessons", conflicts.len());
+        Ok(conflicts)
+    }
+
+    /// Уроки, пересекающиеся хотя бы с одним из промежутков `slots`: уроки той же группы,
+    /// уроки других групп ее преподавателя и уроки в том же кабинете.
+    pub fn find_conflicts(
+        &self,
+        slots: &[(DateTime<Utc>, DateTime<Utc>)],
+        student_group_id: Option<i32>,
+        room_id: Option<i32>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<Vec<LessonConflict>, AppError> {
+        let (Some(from), Some(to)) = (
+            slots.iter().map(|(starts_at, _)| *starts_at).min(),
+            slots.iter().map(|(_, ends_at)| *ends_at).max(),
+        ) else {
+            return Ok(Vec::new());
+        };
+
+        let mut candidates = Vec::new();
+        if let Some(student_group_id) = student_group_id {
+            candidates.extend(
+                self.lesson_repository
+                    .get_overlapping_for_group(student_group_id, from, to, exclude_lesson_ids)?
+                    .into_iter()
+                    .map(|lesson_full| {
+                        let reason =
+                            if lesson_full.lesson.student_group_id == Some(student_group_id) {
+                                ConflictReason::StudentGroup
+                            } else {
+                                ConflictReason::Teacher
+                            };
+                        (reason, lesson_full)
+                    }),
+            );
+        }
+        if let Some(room_id) = room_id {
+            for lesson_full in self.lesson_repository.get_overlapping_in_room(
+                room_id,
+                from,
+                to,
+                exclude_lesson_ids,
+            )? {
+                if !candidates
+                    .iter()
+                    .any(|(_, candidate)| candidate.lesson.id == lesson_full.lesson.id)
+                {
+                    candidates.push((ConflictReason::Room, lesson_full));
+                }
+            }
+        }
+
+        let conflicts = candidates
+            .into_iter()
+            .filter(|(_, lesson_full)| {
+                slots.iter().any(|(starts_at, ends_at)| {
+                    lesson_full.lesson.starts_at < *ends_at
+                        && lesson_full.lesson.ends_at > *starts_at
+                })
+            })
+            .map(|(reason, lesson)| LessonConflict { reason, lesson })
+            .collect();
+        Ok(conflicts)
+    }
+
+    pub fn ensure_no_conflicts(
+        &self,
+        slots: &[(DateTime<Utc>, DateTime<Utc>)],
+        student_group_id: Option<i32>,
+        room_id: Option<i32>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<(), AppError> {
+        let conflicts =
+            self.find_conflicts(slots, student_group_id, room_id, exclude_lesson_ids)?;
+        if conflicts.is_empty() {
+            Ok(())
         } else {
-            warn!("Lesson with ID {} not found", lesson_id);
-            Ok(false)
+            Err(AppError::Conflict(conflicts))
+        }
+    }
+
+    /// Проверяет пересечения урока в рамках транзакции `connection`, см.
+    /// [`LessonRepository::has_overlapping`]. Возвращает `AppError::Conflict` без списка
+    /// уроков, его добавляет [`Self::map_overlap_error`].
+    fn ensure_no_overlap(
+        connection: &mut PgConnection,
+        slot: (DateTime<Utc>, DateTime<Utc>),
+        student_group_id: Option<i32>,
+        room_id: Option<i32>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<(), AppError> {
+        if LessonRepository::has_overlapping(
+            connection,
+            slot,
+            student_group_id,
+            room_id,
+            exclude_lesson_ids,
+        )? {
+            Err(AppError::Conflict(Vec::new()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Заменяет ошибку о пересечении уроков при сохранении на 409 со списком пересечений.
+    /// Такая ошибка возникает, если пересекающийся урок сохранил параллельный запрос уже
+    /// после проверки.
+    pub fn map_overlap_error<T>(
+        &self,
+        result: Result<T, AppError>,
+        slots: &[(DateTime<Utc>, DateTime<Utc>)],
+        student_group_id: Option<i32>,
+        room_id: Option<i32>,
+        exclude_lesson_ids: &[i32],
+    ) -> Result<T, AppError> {
+        match result {
+            Err(e) if Self::is_overlap_error(&e) => {
+                warn!("Lesson overlaps a lesson saved by a concurrent request");
+                let conflicts =
+                    self.find_conflicts(slots, student_group_id, room_id, exclude_lesson_ids)?;
+                Err(AppError::Conflict(conflicts))
+            }
+            result => result,
+        }
+    }
+
+    /// Обнаружено ли пересечение уроков при сохранении: проверкой в транзакции, см.
+    /// [`Self::ensure_no_overlap`], или ограничением базы.
+    pub fn is_overlap_error(error: &AppError) -> bool {
+        match error {
+            AppError::Conflict(conflicts) => conflicts.is_empty(),
+            AppError::Database(DieselError::DatabaseError(_, info)) => info
+                .constraint_name()
+                .is_some_and(|name| OVERLAP_CONSTRAINTS.contains(&name)),
+            _ => false,
+        }
+    }
+
+    /// Проверяет, что кабинет существует и в нем помещаются все студенты группы.
+    pub fn validate_room(
+        &self,
+        room_id: Option<i32>,
+        student_group_id: Option<i32>,
+    ) -> Result<(), AppError> {
+        match (room_id, student_group_id) {
+            (Some(room_id), Some(student_group_id)) => self
+                .room_service
+                .validate_room_fits_group(room_id, student_group_id),
+            (Some(room_id), None) => self.room_service.get(room_id).map(|_| ()),
+            (None, _) => Ok(()),
+        }
+    }
+
+    pub fn validate_lesson_time(
+        &self,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> Result<(), AppError> {
+        if ends_at <= starts_at {
+            return Err(AppError::BadRequest(format!(
+                "Lesson must end after it starts (starts at {}, ends at {})",
+                starts_at, ends_at
+            )));
+        }
+        if ends_at - starts_at > self.schedule.max_lesson_duration {
+            return Err(AppError::BadRequest(format!(
+                "Lesson can not last longer than {} minutes",
+                self.schedule.max_lesson_duration.num_minutes()
+            )));
         }
+        Ok(())
     }
 }
 
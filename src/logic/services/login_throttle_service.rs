@@ -0,0 +1,173 @@
+use std::net::IpAddr;
+
+use axum::extract::FromRef;
+use chrono::{TimeDelta, Utc};
+use tracing::{info, warn};
+
+use crate::{
+    AppState,
+    config::LoginThrottleConfig,
+    error::AppError,
+    logic::{
+        repositories::login_attempt_repository::LoginAttemptRepository,
+        services::audit_service::AuditService,
+    },
+    models::{
+        audit::AuditEntityType,
+        login_lockout::{LockoutTarget, LoginLockout},
+    },
+};
+
+/// Защита входа от перебора паролей: считает неудачные попытки по логину и IP-адресу
+/// и временно блокирует вход, когда их становится слишком много.
+#[derive(Clone)]
+pub struct LoginThrottleService {
+    login_attempt_repository: LoginAttemptRepository,
+    audit_service: AuditService,
+    config: LoginThrottleConfig,
+}
+
+impl LoginThrottleService {
+    pub fn new(
+        login_attempt_repository: LoginAttemptRepository,
+        audit_service: AuditService,
+        config: LoginThrottleConfig,
+    ) -> Self {
+        Self {
+            login_attempt_repository,
+            audit_service,
+            config,
+        }
+    }
+
+    /// Возвращает 429, если вход по логину или с IP-адреса сейчас заблокирован.
+    pub async fn ensure_not_locked(&self, username: &str, ip: IpAddr) -> Result<(), AppError> {
+        let mut retry_after = TimeDelta::zero();
+        for (target, subject) in Self::subjects(username, ip) {
+            if let Some(ttl) = self
+                .login_attempt_repository
+                .get_lock_ttl(target, &subject)
+                .await?
+            {
+                info!("Login by {} {} is locked for {}", target, subject, ttl);
+                retry_after = retry_after.max(ttl);
+            }
+        }
+        if retry_after > TimeDelta::zero() {
+            return Err(Self::locked_error(retry_after));
+        }
+        Ok(())
+    }
+
+    /// Учитывает неудачную попытку входа. Если после нее вход блокируется, возвращает 429.
+    pub async fn register_failure(&self, username: &str, ip: IpAddr) -> Result<(), AppError> {
+        let mut retry_after = TimeDelta::zero();
+        for (target, subject) in Self::subjects(username, ip) {
+            let max_failures = match target {
+                LockoutTarget::Username => self.config.max_failures_per_username,
+                LockoutTarget::Ip => self.config.max_failures_per_ip,
+            };
+            let failures = self
+                .login_attempt_repository
+                .register_failure(target, &subject, self.config.failure_window)
+                .await?;
+            if failures < max_failures {
+                continue;
+            }
+            let lockout = self.config.lockout_after(failures, max_failures);
+            // Счетчик должен пережить блокировку, иначе следующая неудачная попытка
+            // после нее не удвоит срок
+            self.login_attempt_repository
+                .lock(
+                    target,
+                    &subject,
+                    lockout,
+                    lockout + self.config.failure_window,
+                )
+                .await?;
+            let login_lockout = LoginLockout {
+                target,
+                subject,
+                failures,
+                locked_until: Utc::now() + lockout,
+            };
+            warn!(
+                "Login by {} {} is locked until {} after {} failed attempts",
+                target, login_lockout.subject, login_lockout.locked_until, failures
+            );
+            self.audit_service.record_create(
+                AuditEntityType::LoginLockout,
+                login_lockout.audit_id(),
+                &login_lockout,
+            );
+            retry_after = retry_after.max(lockout);
+        }
+        if retry_after > TimeDelta::zero() {
+            return Err(Self::locked_error(retry_after));
+        }
+        Ok(())
+    }
+
+    /// Сбрасывает счетчик неудачных попыток по логину после успешного входа. Счетчик
+    /// по IP-адресу не сбрасывается, чтобы вход в свою учетную запись не позволял
+    /// продолжать перебор чужих паролей с того же адреса.
+    pub async fn register_success(&self, username: &str) -> Result<(), AppError> {
+        self.login_attempt_repository
+            .clear(LockoutTarget::Username, username)
+            .await
+    }
+
+    /// Снимает блокировку входа по логину и сбрасывает счетчик неудачных попыток.
+    /// Возвращает `true`, если вход был заблокирован.
+    pub async fn unlock(&self, username: &str) -> Result<bool, AppError> {
+        let target = LockoutTarget::Username;
+        let ttl = self
+            .login_attempt_repository
+            .get_lock_ttl(target, username)
+            .await?;
+        let failures = self
+            .login_attempt_repository
+            .get_failures(target, username)
+            .await?;
+        self.login_attempt_repository
+            .clear(target, username)
+            .await?;
+        let Some(ttl) = ttl else {
+            info!("Login by username {} was not locked", username);
+            return Ok(false);
+        };
+        let login_lockout = LoginLockout {
+            target,
+            subject: username.to_string(),
+            failures,
+            locked_until: Utc::now() + ttl,
+        };
+        self.audit_service.record_delete(
+            AuditEntityType::LoginLockout,
+            login_lockout.audit_id(),
+            &login_lockout,
+        );
+        info!("Login by username {} was unlocked", username);
+        Ok(true)
+    }
+
+    fn subjects(username: &str, ip: IpAddr) -> [(LockoutTarget, String); 2] {
+        [
+            (LockoutTarget::Username, username.to_string()),
+            (LockoutTarget::Ip, ip.to_string()),
+        ]
+    }
+
+    fn locked_error(retry_after: TimeDelta) -> AppError {
+        AppError::TooManyRequests(
+            "Too many failed login attempts, try again later".to_string(),
+            retry_after.num_seconds(),
+        )
+    }
+}
+
+impl FromRef<AppState> for LoginThrottleService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.login_throttle_service.clone()
+    }
+}
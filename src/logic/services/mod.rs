@@ -1,34 +1,66 @@
+use api_token_service::ApiTokenService;
 use attendance_service::AttendanceService;
+use audit_service::AuditService;
 use document_service::DocumentService;
+use lesson_series_service::LessonSeriesService;
 use lesson_service::LessonService;
+use login_throttle_service::LoginThrottleService;
+use parent_portal_service::ParentPortalService;
 use parent_service::ParentService;
+use retention_service::RetentionService;
+use room_service::RoomService;
 use student_group_service::StudentGroupService;
 use student_service::StudentService;
 use teacher_service::TeacherService;
+use timetable_service::TimetableService;
+use waitlist_service::WaitlistService;
 
 use super::repositories::{
-    attendance_repository::AttendanceRepository, document_repository::DocumentRepository,
-    lesson_repository::LessonRepository, parent_repository::ParentRepository,
-    student_group_repository::StudentGroupRepository, student_repository::StudentRepository,
-    teacher_repository::TeacherRepository,
+    api_token_repository::ApiTokenRepository, attendance_repository::AttendanceRepository,
+    audit_repository::AuditRepository, document_repository::DocumentRepository,
+    enrollment_repository::EnrollmentRepository, lesson_repository::LessonRepository,
+    lesson_series_repository::LessonSeriesRepository,
+    login_attempt_repository::LoginAttemptRepository, parent_repository::ParentRepository,
+    password_reset_repository::PasswordResetRepository, retention_repository::RetentionRepository,
+    room_repository::RoomRepository, student_group_repository::StudentGroupRepository,
+    student_repository::StudentRepository, teacher_repository::TeacherRepository,
+    timetable_repository::TimetableRepository, waitlist_repository::WaitlistRepository,
 };
 use crate::{
     AppServices,
-    db::PostgresPool,
-    logic::{repositories::user_repository::UserRepository, services::user_service::UserService},
+    config::AppConfig,
+    db::{PostgresPool, RedisPool},
+    logic::{
+        repositories::user_repository::UserRepository, services::user_service::UserService,
+        storage::init_storage,
+    },
 };
 
+pub mod api_token_service;
 pub mod attendance_service;
+pub mod audit_service;
 pub mod document_service;
+pub mod lesson_series_service;
 pub mod lesson_service;
+pub mod login_throttle_service;
+pub mod parent_portal_service;
 pub mod parent_service;
+pub mod retention_service;
+pub mod room_service;
 pub mod student_group_service;
 pub mod student_service;
 pub mod teacher_service;
+pub mod timetable_service;
 pub mod user_service;
+pub mod waitlist_service;
 
-pub fn init_app_services(pool: PostgresPool) -> AppServices {
+pub fn init_app_services(
+    pool: PostgresPool,
+    redis_pool: RedisPool,
+    config: &AppConfig,
+) -> AppServices {
     let lesson_repo = LessonRepository::new(pool.clone());
+    let lesson_series_repo = LessonSeriesRepository::new(pool.clone());
     let parent_repo = ParentRepository::new(pool.clone());
     let student_repo = StudentRepository::new(pool.clone());
     let student_group_repo = StudentGroupRepository::new(pool.clone());
@@ -36,30 +68,117 @@ pub fn init_app_services(pool: PostgresPool) -> AppServices {
     let attendance_repo = AttendanceRepository::new(pool.clone());
     let document_repo = DocumentRepository::new(pool.clone());
     let user_repo = UserRepository::new(pool.clone());
+    let password_reset_repo = PasswordResetRepository::new(pool.clone());
+    let login_attempt_repo = LoginAttemptRepository::new(redis_pool);
+    let api_token_repo = ApiTokenRepository::new(pool.clone());
+    let room_repo = RoomRepository::new(pool.clone());
+    let timetable_repo = TimetableRepository::new(pool.clone());
+    let waitlist_repo = WaitlistRepository::new(pool.clone());
+    let enrollment_repo = EnrollmentRepository::new(pool.clone());
+    let retention_repo = RetentionRepository::new(pool.clone());
+    let audit_repo = AuditRepository::new(pool.clone());
+    let storage = init_storage(&config.storage);
 
-    let lesson_service = LessonService::new(
+    let audit_service = AuditService::new(audit_repo);
+    let student_service = StudentService::new(
+        student_repo,
+        enrollment_repo.clone(),
+        audit_service.clone(),
+        config.schedule.clone(),
+    );
+    let room_service = RoomService::new(room_repo, student_service.clone(), audit_service.clone());
+    let attendance_service = AttendanceService::new(
+        attendance_repo,
         lesson_repo.clone(),
-        AttendanceService::new(
-            attendance_repo.clone(),
-            StudentService::new(student_repo.clone()),
-        ),
+        enrollment_repo,
+        audit_service.clone(),
+        config.schedule.clone(),
+    );
+    let lesson_service = LessonService::new(
+        lesson_repo,
+        attendance_service.clone(),
+        room_service.clone(),
+        audit_service.clone(),
+        config.schedule.clone(),
+    );
+    let lesson_series_service = LessonSeriesService::new(
+        lesson_series_repo,
+        lesson_service.clone(),
+        audit_service.clone(),
+        config.schedule.clone(),
+    );
+    let parent_portal_service = ParentPortalService::new(
+        student_service.clone(),
+        lesson_service.clone(),
+        attendance_service.clone(),
+    );
+    let parent_service = ParentService::new(parent_repo, audit_service.clone());
+    let student_group_service = StudentGroupService::new(
+        student_group_repo,
+        student_service.clone(),
+        audit_service.clone(),
+        config.schedule.clone(),
+    );
+    let teacher_service = TeacherService::new(teacher_repo, audit_service.clone());
+    let document_service = DocumentService::new(
+        document_repo,
+        storage,
+        audit_service.clone(),
+        config.document.clone(),
+        config.schedule.clone(),
+    );
+    let retention_service = RetentionService::new(
+        retention_repo,
+        document_service.clone(),
+        config.retention.clone(),
+    );
+    let user_service = UserService::new(
+        user_repo,
+        password_reset_repo,
+        teacher_service.clone(),
+        parent_service.clone(),
+        audit_service.clone(),
+        config.password.clone(),
+    );
+    let api_token_service =
+        ApiTokenService::new(api_token_repo, user_service.clone(), audit_service.clone());
+    let login_throttle_service = LoginThrottleService::new(
+        login_attempt_repo,
+        audit_service.clone(),
+        config.login_throttle.clone(),
+    );
+    let timetable_service = TimetableService::new(
+        timetable_repo,
+        lesson_service.clone(),
+        student_group_service.clone(),
+        student_service.clone(),
+        room_service.clone(),
+        config.schedule.clone(),
+    );
+    let waitlist_service = WaitlistService::new(
+        waitlist_repo,
+        student_group_service.clone(),
+        student_service.clone(),
+        audit_service.clone(),
     );
-    let parent_service = ParentService::new(parent_repo);
-    let student_service = StudentService::new(student_repo.clone());
-    let student_group_service = StudentGroupService::new(student_group_repo);
-    let teacher_service = TeacherService::new(teacher_repo);
-    let attendance_service = AttendanceService::new(attendance_repo, student_service.clone());
-    let document_service = DocumentService::new(document_repo);
-    let user_service = UserService::new(user_repo);
 
     AppServices {
         lesson_service,
+        lesson_series_service,
         parent_service,
+        parent_portal_service,
         student_service,
         student_group_service,
         teacher_service,
         attendance_service,
         document_service,
         user_service,
+        login_throttle_service,
+        api_token_service,
+        room_service,
+        timetable_service,
+        waitlist_service,
+        retention_service,
+        audit_service,
     }
 }
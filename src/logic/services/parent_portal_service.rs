@@ -0,0 +1,151 @@
+use axum::extract::FromRef;
+use chrono::Utc;
+use tracing::{info, warn};
+
+use crate::{
+    AppState,
+    error::AppError,
+    logic::services::{
+        attendance_service::AttendanceService, lesson_service::LessonService,
+        student_service::StudentService,
+    },
+    models::{
+        attendance::{AttendanceFilter, AttendanceWithRelations, NewAbsenceExcuse},
+        lesson::{LessonFilter, LessonWithRelations},
+        pagination::{PaginatedResponse, PaginationParams},
+        student::{StudentFilter, StudentWithRelations},
+        user::User,
+    },
+};
+
+/// Данные, доступные родителю: его дети, их уроки и посещения. Родитель определяется
+/// по связи пользователя с записью родителя, чужие ученики для него не существуют.
+#[derive(Clone)]
+pub struct ParentPortalService {
+    student_service: StudentService,
+    lesson_service: LessonService,
+    attendance_service: AttendanceService,
+}
+
+impl ParentPortalService {
+    pub fn new(
+        student_service: StudentService,
+        lesson_service: LessonService,
+        attendance_service: AttendanceService,
+    ) -> Self {
+        Self {
+            student_service,
+            lesson_service,
+            attendance_service,
+        }
+    }
+
+    pub fn get_children(
+        &self,
+        user: &User,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<StudentWithRelations>, AppError> {
+        let parent_id = Self::parent_id(user)?;
+        let filter = StudentFilter {
+            student_group_id: None,
+            parent_id: Some(parent_id),
+            name: None,
+            birth_date_from: None,
+            birth_date_to: None,
+        };
+        self.student_service.get_all(filter, pagination, false)
+    }
+
+    /// Ученик, если он ребенок родителя пользователя. Иначе возвращается 404.
+    pub fn get_child(
+        &self,
+        user: &User,
+        student_id: i32,
+    ) -> Result<StudentWithRelations, AppError> {
+        let parent_id = Self::parent_id(user)?;
+        let student_full = self.student_service.get(student_id, false)?;
+        if student_full.student.parent_id != Some(parent_id) {
+            warn!(
+                "User with ID {} requested student {} who is not their child",
+                user.id, student_id
+            );
+            return Err(AppError::NotFound(format!(
+                "Student with ID {} not found",
+                student_id
+            )));
+        }
+        Ok(student_full)
+    }
+
+    /// Уроки текущей группы ребенка, которые еще не закончились.
+    pub fn get_upcoming_lessons(
+        &self,
+        user: &User,
+        student_id: i32,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<LessonWithRelations>, AppError> {
+        let student = self.get_child(user, student_id)?.student;
+        let Some(student_group_id) = student.student_group_id else {
+            info!("Student with ID {} has no group and no lessons", student_id);
+            return Ok(PaginatedResponse::new(Vec::new(), 0, pagination));
+        };
+        let filter = LessonFilter {
+            student_group_id: Some(student_group_id),
+            lesson_series_id: None,
+            room_id: None,
+            from: Some(Utc::now()),
+            to: None,
+        };
+        self.lesson_service.get_all(filter, pagination, false)
+    }
+
+    pub fn get_attendances(
+        &self,
+        user: &User,
+        student_id: i32,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<AttendanceWithRelations>, AppError> {
+        self.get_child(user, student_id)?;
+        let filter = AttendanceFilter {
+            student_id: Some(student_id),
+            lesson_id: None,
+            is_present: None,
+        };
+        self.attendance_service.get_all(filter, pagination)
+    }
+
+    /// Сообщает, что ребенок пропустит урок. Причину можно указать только для урока,
+    /// который еще не начался.
+    pub fn excuse_absence(
+        &self,
+        user: &User,
+        student_id: i32,
+        excuse: NewAbsenceExcuse,
+    ) -> Result<AttendanceWithRelations, AppError> {
+        let student = self.get_child(user, student_id)?.student;
+        let lesson = self.lesson_service.get(excuse.lesson_id, false)?.lesson;
+        if lesson.starts_at <= Utc::now() {
+            return Err(AppError::BadRequest(format!(
+                "Lesson {} has already started, absence can only be reported in advance",
+                lesson.id
+            )));
+        }
+        self.attendance_service
+            .excuse_absence(&student, &lesson, excuse.skip_reason)
+    }
+
+    fn parent_id(user: &User) -> Result<i32, AppError> {
+        user.parent_id.ok_or_else(|| {
+            AppError::Forbidden(format!(
+                "User with ID {} is not linked to a parent",
+                user.id
+            ))
+        })
+    }
+}
+
+impl FromRef<AppState> for ParentPortalService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.parent_portal_service.clone()
+    }
+}
@@ -1,45 +1,89 @@
 use axum::extract::FromRef;
+use diesel::result::Error as DieselError;
 use tracing::{info, warn};
 
 use crate::{
     AppState,
     error::AppError,
-    logic::repositories::parent_repository::ParentRepository,
-    models::parent::{NewParent, Parent, UpdateParent},
+    logic::{
+        repositories::parent_repository::ParentRepository, services::audit_service::AuditService,
+    },
+    models::{
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        parent::{NewParent, Parent, UpdateParent},
+    },
 };
 
 #[derive(Clone)]
 pub struct ParentService {
     parent_repository: ParentRepository,
+    audit_service: AuditService,
 }
 
 impl ParentService {
-    pub fn new(parent_repository: ParentRepository) -> Self {
-        Self { parent_repository }
+    pub fn new(parent_repository: ParentRepository, audit_service: AuditService) -> Self {
+        Self {
+            parent_repository,
+            audit_service,
+        }
     }
 
     pub fn create(&self, new_parent: NewParent) -> Result<Parent, AppError> {
         let parent = self.parent_repository.create(new_parent)?;
+        self.audit_service
+            .record_create(AuditEntityType::Parent, parent.id, &parent);
         info!("Successfully created parent with ID {}", parent.id);
         Ok(parent)
     }
 
-    pub fn get(&self, parent_id: i32) -> Result<Parent, AppError> {
-        let parent = self.parent_repository.get(parent_id)?;
+    pub fn get(&self, parent_id: i32, include_deleted: bool) -> Result<Parent, AppError> {
+        let parent = self.parent_repository.get(parent_id, include_deleted)?;
         info!("Parent with ID {} successfully get", parent_id);
         Ok(parent)
     }
 
+    pub fn get_all(
+        &self,
+        pagination: &PaginationParams,
+        include_deleted: bool,
+    ) -> Result<PaginatedResponse<Parent>, AppError> {
+        let (parents, total) = self.parent_repository.get_all(
+            pagination.limit(),
+            pagination.offset(),
+            include_deleted,
+        )?;
+        info!("Got {} of {} parents", parents.len(), total);
+        Ok(PaginatedResponse::new(parents, total, pagination))
+    }
+
     pub fn update(&self, parent_id: i32, update_parent: UpdateParent) -> Result<Parent, AppError> {
+        let parent = self.parent_repository.get(parent_id, false)?;
         let updated_parent = self.parent_repository.update(parent_id, update_parent)?;
+        self.audit_service.record_update(
+            AuditEntityType::Parent,
+            parent_id,
+            &parent,
+            &updated_parent,
+        );
         info!("Parent with ID {} was successfully updated", parent_id);
         Ok(updated_parent)
     }
 
     pub fn delete(&self, parent_id: i32) -> Result<bool, AppError> {
+        let parent = match self.parent_repository.get(parent_id, false) {
+            Ok(parent) => parent,
+            Err(AppError::Database(DieselError::NotFound)) => {
+                warn!("Parent with ID {} not found", parent_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
         let deleted_count = self.parent_repository.delete(parent_id)?;
 
         if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::Parent, parent_id, &parent);
             info!("Parent with ID {} was successfully deleted", parent_id);
             Ok(true)
         } else {
@@ -47,6 +91,14 @@ impl ParentService {
             Ok(false)
         }
     }
+
+    pub fn restore(&self, parent_id: i32) -> Result<Parent, AppError> {
+        let parent = self.parent_repository.restore(parent_id)?;
+        self.audit_service
+            .record_restore(AuditEntityType::Parent, parent_id, &parent);
+        info!("Parent with ID {} was successfully restored", parent_id);
+        Ok(parent)
+    }
 }
 
 impl FromRef<AppState> for ParentService {
@@ -0,0 +1,97 @@
+use chrono::Utc;
+use tracing::{error, info};
+
+use crate::{
+    config::RetentionConfig,
+    error::AppError,
+    logic::{
+        repositories::retention_repository::{PurgedRecords, RetentionRepository},
+        services::document_service::DocumentService,
+    },
+    models::document::DocumentOwner,
+};
+
+/// Окончательное удаление записей, срок хранения которых после удаления истек.
+#[derive(Clone)]
+pub struct RetentionService {
+    retention_repository: RetentionRepository,
+    document_service: DocumentService,
+    config: RetentionConfig,
+}
+
+impl RetentionService {
+    pub fn new(
+        retention_repository: RetentionRepository,
+        document_service: DocumentService,
+        config: RetentionConfig,
+    ) -> Self {
+        Self {
+            retention_repository,
+            document_service,
+            config,
+        }
+    }
+
+    /// Удаляет из базы записи, помеченные удаленными раньше срока хранения, и файлы
+    /// прикрепленных к ним документов.
+    pub async fn purge(&self) -> Result<PurgedRecords, AppError> {
+        let cutoff = Utc::now() - self.config.period;
+        let retention_repository = self.retention_repository.clone();
+        let purged =
+            tokio::task::spawn_blocking(move || retention_repository.purge_deleted_before(cutoff))
+                .await
+                .map_err(|e| AppError::InternalServerError(e.to_string()))??;
+        let owners = purged
+            .teacher_ids
+            .iter()
+            .map(|&id| DocumentOwner::Teacher(id))
+            .chain(
+                purged
+                    .student_ids
+                    .iter()
+                    .map(|&id| DocumentOwner::Student(id)),
+            )
+            .chain(
+                purged
+                    .student_group_ids
+                    .iter()
+                    .map(|&id| DocumentOwner::StudentGroup(id)),
+            )
+            .chain(
+                purged
+                    .lesson_ids
+                    .iter()
+                    .map(|&id| DocumentOwner::Lesson(id)),
+            );
+        for owner in owners {
+            self.document_service.delete_owner_files(owner).await?;
+        }
+        info!(
+            "Purged {} records deleted before {}: {} lessons, {} students, {} student groups, {} parents, {} teachers",
+            purged.total(),
+            cutoff,
+            purged.lesson_ids.len(),
+            purged.student_ids.len(),
+            purged.student_group_ids.len(),
+            purged.parents,
+            purged.teacher_ids.len()
+        );
+        Ok(purged)
+    }
+
+    /// Фоновая задача, запускающая [`Self::purge`] раз в `purge_interval`.
+    pub async fn run_purge_job(self) {
+        let period = self
+            .config
+            .purge_interval
+            .to_std()
+            .expect("Purge interval must be positive");
+        let mut interval = tokio::time::interval(period);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.purge().await {
+                error!("Failed to purge deleted records: {}", err);
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use axum::extract::FromRef;
+use tracing::info;
+
+use crate::{
+    AppState,
+    error::AppError,
+    logic::{
+        repositories::room_repository::RoomRepository,
+        services::{audit_service::AuditService, student_service::StudentService},
+    },
+    models::{
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        room::{NewRoom, Room, RoomAvailabilityParams, RoomFilter, UpdateRoom},
+    },
+};
+
+#[derive(Clone)]
+pub struct RoomService {
+    room_repository: RoomRepository,
+    student_service: StudentService,
+    audit_service: AuditService,
+}
+
+impl RoomService {
+    pub fn new(
+        room_repository: RoomRepository,
+        student_service: StudentService,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            room_repository,
+            student_service,
+            audit_service,
+        }
+    }
+
+    pub fn create(&self, new_room: NewRoom) -> Result<Room, AppError> {
+        Self::validate_capacity_value(new_room.capacity)?;
+        let room = self.room_repository.create(new_room)?;
+        self.audit_service
+            .record_create(AuditEntityType::Room, room.id, &room);
+        info!("Successfully created room with ID {}", room.id);
+        Ok(room)
+    }
+
+    pub fn get(&self, room_id: i32) -> Result<Room, AppError> {
+        let room = self.room_repository.get(room_id)?;
+        info!("Room with ID {} successfully get", room_id);
+        Ok(room)
+    }
+
+    pub fn get_all(
+        &self,
+        filter: RoomFilter,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<Room>, AppError> {
+        let (rooms, total) =
+            self.room_repository
+                .get_all(&filter, pagination.limit(), pagination.offset())?;
+        info!("Got {} of {} rooms", rooms.len(), total);
+        Ok(PaginatedResponse::new(rooms, total, pagination))
+    }
+
+    /// Кабинеты с указанными ID или все кабинеты. Если какого-то кабинета нет,
+    /// возвращается 404.
+    pub fn get_many(&self, room_ids: Option<&[i32]>) -> Result<Vec<Room>, AppError> {
+        let rooms = self.room_repository.get_many(room_ids)?;
+        if let Some(missing) = room_ids
+            .unwrap_or_default()
+            .iter()
+            .find(|id| !rooms.iter().any(|room| room.id == **id))
+        {
+            return Err(AppError::NotFound(format!(
+                "Room with ID {} not found",
+                missing
+            )));
+        }
+        info!("Got {} rooms", rooms.len());
+        Ok(rooms)
+    }
+
+    pub fn get_available(
+        &self,
+        filter: RoomFilter,
+        window: RoomAvailabilityParams,
+    ) -> Result<Vec<Room>, AppError> {
+        if window.to <= window.from {
+            return Err(AppError::BadRequest(format!(
+                "Time window must end after it starts (from {}, to {})",
+                window.from, window.to
+            )));
+        }
+        let rooms = self
+            .room_repository
+            .get_available(&filter, window.from, window.to)?;
+        info!(
+            "Got {} rooms available from {} to {}",
+            rooms.len(),
+            window.from,
+            window.to
+        );
+        Ok(rooms)
+    }
+
+    pub fn update(&self, room_id: i32, update_room: UpdateRoom) -> Result<Room, AppError> {
+        if let Some(capacity) = update_room.capacity {
+            Self::validate_capacity_value(capacity)?;
+        }
+        let room = self.room_repository.get(room_id)?;
+        let updated_room = self.room_repository.update(room_id, update_room)?;
+        self.audit_service
+            .record_update(AuditEntityType::Room, room_id, &room, &updated_room);
+        info!("Successfully updated room with ID {}", room_id);
+        Ok(updated_room)
+    }
+
+    pub fn delete(&self, room_id: i32) -> Result<bool, AppError> {
+        let room = match self.room_repository.get(room_id) {
+            Ok(room) => room,
+            Err(AppError::NotFound(_)) => {
+                info!("Room with ID {} not found", room_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        let deleted_count = self.room_repository.delete(room_id)?;
+
+        if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::Room, room_id, &room);
+            info!("Room with ID {} successfully deleted", room_id);
+            Ok(true)
+        } else {
+            info!("Room with ID {} not found", room_id);
+            Ok(false)
+        }
+    }
+
+    /// Проверяет, что все студенты группы помещаются в кабинет.
+    pub fn validate_room_fits_group(
+        &self,
+        room_id: i32,
+        student_group_id: i32,
+    ) -> Result<(), AppError> {
+        let room = self.room_repository.get(room_id)?;
+        let students_count = self
+            .student_service
+            .count_students_in_group(student_group_id)?;
+        if students_count > i64::from(room.capacity) {
+            return Err(AppError::BadRequest(format!(
+                "Room {} has {} seats, but group {} has {} students",
+                room.id, room.capacity, student_group_id, students_count
+            )));
+        }
+        Ok(())
+    }
+
+    fn validate_capacity_value(capacity: i32) -> Result<(), AppError> {
+        if capacity < 1 {
+            return Err(AppError::BadRequest(
+                "Room capacity must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl FromRef<AppState> for RoomService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.room_service.clone()
+    }
+}
@@ -3,20 +3,41 @@ use tracing::{info, warn};
 
 use crate::{
     AppState,
+    config::ScheduleConfig,
     error::AppError,
-    logic::repositories::student_group_repository::StudentGroupRepository,
-    models::student_group::{NewStudentGroup, StudentGroupWithRelations, UpdateStudentGroup},
+    logic::{
+        repositories::student_group_repository::StudentGroupRepository,
+        services::{audit_service::AuditService, student_service::StudentService},
+    },
+    models::{
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        student_group::{
+            NewStudentGroup, RawUpdateStudentGroup, StudentGroupWithRelations, UpdateStudentGroup,
+        },
+    },
 };
 
 #[derive(Clone)]
 pub struct StudentGroupService {
     student_group_repository: StudentGroupRepository,
+    student_service: StudentService,
+    audit_service: AuditService,
+    schedule: ScheduleConfig,
 }
 
 impl StudentGroupService {
-    pub fn new(student_group_repository: StudentGroupRepository) -> Self {
+    pub fn new(
+        student_group_repository: StudentGroupRepository,
+        student_service: StudentService,
+        audit_service: AuditService,
+        schedule: ScheduleConfig,
+    ) -> Self {
         Self {
             student_group_repository,
+            student_service,
+            audit_service,
+            schedule,
         }
     }
 
@@ -24,7 +45,13 @@ impl StudentGroupService {
         &self,
         new_student_group: NewStudentGroup,
     ) -> Result<StudentGroupWithRelations, AppError> {
+        Self::validate_free_spots_value(new_student_group.free_spots)?;
         let student_group_full = self.student_group_repository.create(new_student_group)?;
+        self.audit_service.record_create(
+            AuditEntityType::StudentGroup,
+            student_group_full.student_group.id,
+            &student_group_full,
+        );
         info!(
             "Successfully created student group with ID {}",
             student_group_full.student_group.id
@@ -32,8 +59,14 @@ impl StudentGroupService {
         Ok(student_group_full)
     }
 
-    pub fn get(&self, student_group_id: i32) -> Result<StudentGroupWithRelations, AppError> {
-        let student_group = self.student_group_repository.get(student_group_id)?;
+    pub fn get(
+        &self,
+        student_group_id: i32,
+        include_deleted: bool,
+    ) -> Result<StudentGroupWithRelations, AppError> {
+        let student_group = self
+            .student_group_repository
+            .get(student_group_id, include_deleted)?;
         info!(
             "Student group with ID {} successfully get",
             student_group_id
@@ -41,34 +74,104 @@ impl StudentGroupService {
         Ok(student_group)
     }
 
+    pub fn get_all(
+        &self,
+        pagination: &PaginationParams,
+        include_deleted: bool,
+    ) -> Result<PaginatedResponse<StudentGroupWithRelations>, AppError> {
+        let (student_groups, total) = self.student_group_repository.get_all(
+            pagination.limit(),
+            pagination.offset(),
+            include_deleted,
+        )?;
+        info!("Got {} of {} student groups", student_groups.len(), total);
+        Ok(PaginatedResponse::new(student_groups, total, pagination))
+    }
+
     pub fn update(
         &self,
         student_group_id: i32,
-        update_student_group: UpdateStudentGroup,
+        raw_update_student_group: RawUpdateStudentGroup,
     ) -> Result<StudentGroupWithRelations, AppError> {
-        let updated_student_group = self
-            .student_group_repository
-            .update(student_group_id, update_student_group)?;
+        let update_student_group = UpdateStudentGroup::from(raw_update_student_group);
+        if let Some(free_spots) = update_student_group.free_spots {
+            Self::validate_free_spots_value(free_spots)?;
+        }
+        let student_group = self.student_group_repository.get(student_group_id, false)?;
+        let (updated_student_group, promoted) = self.student_group_repository.update(
+            student_group_id,
+            update_student_group,
+            self.schedule.today(),
+        )?;
+        self.audit_service.record_update(
+            AuditEntityType::StudentGroup,
+            student_group_id,
+            &student_group,
+            &updated_student_group,
+        );
         info!(
             "Student group with ID {} was successfully updated",
             student_group_id
         );
+        self.student_service.record_transfers(&promoted);
         Ok(updated_student_group)
     }
 
     pub fn delete(&self, student_group_id: i32) -> Result<bool, AppError> {
-        let deleted_count = self.student_group_repository.delete(student_group_id)?;
+        let student_group = match self.student_group_repository.get(student_group_id, false) {
+            Ok(student_group) => student_group,
+            Err(AppError::NotFound(_)) => {
+                warn!("Student group with ID {} not found", student_group_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        let deleted = self
+            .student_group_repository
+            .delete(student_group_id, self.schedule.today())?;
+
+        match deleted {
+            Some(unenrolled) => {
+                self.audit_service.record_delete(
+                    AuditEntityType::StudentGroup,
+                    student_group_id,
+                    &student_group,
+                );
+                info!(
+                    "Student group with ID {} was successfully deleted",
+                    student_group_id
+                );
+                self.student_service.record_transfers(&unenrolled);
+                Ok(true)
+            }
+            None => {
+                warn!("Student group with ID {} not found", student_group_id);
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn restore(&self, student_group_id: i32) -> Result<StudentGroupWithRelations, AppError> {
+        let student_group = self.student_group_repository.restore(student_group_id)?;
+        self.audit_service.record_restore(
+            AuditEntityType::StudentGroup,
+            student_group_id,
+            &student_group,
+        );
+        info!(
+            "Student group with ID {} was successfully restored",
+            student_group_id
+        );
+        Ok(student_group)
+    }
 
-        if deleted_count > 0 {
-            info!(
-                "Student group with ID {} was successfully deleted",
-                student_group_id
-            );
-            Ok(true)
-        } else {
-            warn!("Student group with ID {} not found", student_group_id);
-            Ok(false)
+    fn validate_free_spots_value(free_spots: i32) -> Result<(), AppError> {
+        if free_spots < 0 {
+            return Err(AppError::BadRequest(
+                "Number of free spots can not be negative".to_string(),
+            ));
         }
+        Ok(())
     }
 }
 
@@ -3,23 +3,56 @@ use tracing::{info, warn};
 
 use crate::{
     AppState,
+    config::ScheduleConfig,
     error::AppError,
-    logic::repositories::student_repository::StudentRepository,
-    models::student::{NewStudent, StudentWithRelations, UpdateStudent},
+    logic::{
+        repositories::{
+            enrollment_repository::EnrollmentRepository, student_repository::StudentRepository,
+        },
+        services::audit_service::AuditService,
+    },
+    models::{
+        audit::AuditEntityType,
+        enrollment::EnrollmentWithGroup,
+        pagination::{PaginatedResponse, PaginationParams},
+        student::{
+            NewStudent, RawUpdateStudent, StudentFilter, StudentWithRelations, UpdateStudent,
+        },
+    },
 };
 
 #[derive(Clone)]
 pub struct StudentService {
     student_repository: StudentRepository,
+    enrollment_repository: EnrollmentRepository,
+    audit_service: AuditService,
+    schedule: ScheduleConfig,
 }
 
 impl StudentService {
-    pub fn new(student_repository: StudentRepository) -> Self {
-        Self { student_repository }
+    pub fn new(
+        student_repository: StudentRepository,
+        enrollment_repository: EnrollmentRepository,
+        audit_service: AuditService,
+        schedule: ScheduleConfig,
+    ) -> Self {
+        Self {
+            student_repository,
+            enrollment_repository,
+            audit_service,
+            schedule,
+        }
     }
 
     pub fn create(&self, new_student: NewStudent) -> Result<StudentWithRelations, AppError> {
-        let student_full = self.student_repository.create(new_student)?;
+        let student_full = self
+            .student_repository
+            .create(new_student, self.schedule.today())?;
+        self.audit_service.record_create(
+            AuditEntityType::Student,
+            student_full.student.id,
+            &student_full,
+        );
         info!(
             "Successfully created student with ID {}",
             student_full.student.id
@@ -27,42 +60,132 @@ impl StudentService {
         Ok(student_full)
     }
 
-    pub fn get(&self, student_id: i32) -> Result<StudentWithRelations, AppError> {
-        let student = self.student_repository.get(student_id)?;
+    pub fn get(
+        &self,
+        student_id: i32,
+        include_deleted: bool,
+    ) -> Result<StudentWithRelations, AppError> {
+        let student = self.student_repository.get(student_id, include_deleted)?;
         info!("Student with ID {} successfully get", student_id);
         Ok(student)
     }
 
-    pub fn get_students_from_group(
+    pub fn get_all(
         &self,
-        student_group_id: i32,
-    ) -> Result<Vec<StudentWithRelations>, AppError> {
-        let students = self
+        filter: StudentFilter,
+        pagination: &PaginationParams,
+        include_deleted: bool,
+    ) -> Result<PaginatedResponse<StudentWithRelations>, AppError> {
+        let (students, total) = self.student_repository.get_all(
+            &filter,
+            pagination.limit(),
+            pagination.offset(),
+            include_deleted,
+        )?;
+        info!("Got {} of {} students", students.len(), total);
+        Ok(PaginatedResponse::new(students, total, pagination))
+    }
+
+    pub fn get_enrollments(&self, student_id: i32) -> Result<Vec<EnrollmentWithGroup>, AppError> {
+        self.student_repository.get(student_id, false)?;
+        let enrollments = self.enrollment_repository.get_by_student_id(student_id)?;
+        info!(
+            "Got {} enrollments for student with ID {}",
+            enrollments.len(),
+            student_id
+        );
+        Ok(enrollments)
+    }
+
+    pub fn count_students_in_group(&self, student_group_id: i32) -> Result<i64, AppError> {
+        let count = self
             .student_repository
-            .get_students_by_group_id(student_group_id)?;
-        info!("Got students from group with ID {}", student_group_id);
-        Ok(students)
+            .count_by_group_id(student_group_id)?;
+        info!("Group with ID {} has {} students", student_group_id, count);
+        Ok(count)
     }
 
     pub fn update(
         &self,
         student_id: i32,
-        update_student: UpdateStudent,
+        raw_update_student: RawUpdateStudent,
     ) -> Result<StudentWithRelations, AppError> {
-        let updated_student = self.student_repository.update(student_id, update_student)?;
+        let update_student = UpdateStudent::from(raw_update_student);
+        let student = self.student_repository.get(student_id, false)?;
+        let (updated_student, promoted) =
+            self.student_repository
+                .update(student_id, update_student, self.schedule.today())?;
+        self.audit_service.record_update(
+            AuditEntityType::Student,
+            student_id,
+            &student,
+            &updated_student,
+        );
         info!("Student with ID {} was successfully updated", student_id);
+        self.record_transfers(&promoted);
         Ok(updated_student)
     }
 
     pub fn delete(&self, student_id: i32) -> Result<bool, AppError> {
-        let deleted_count = self.student_repository.delete(student_id)?;
+        let student = match self.student_repository.get(student_id, false) {
+            Ok(student) => student,
+            Err(AppError::NotFound(_)) => {
+                warn!("Student with ID {} not found", student_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
+        let deleted = self
+            .student_repository
+            .delete(student_id, self.schedule.today())?;
+
+        match deleted {
+            Some(promoted) => {
+                self.audit_service
+                    .record_delete(AuditEntityType::Student, student_id, &student);
+                info!("Student with ID {} was successfully deleted", student_id);
+                self.record_transfers(&promoted);
+                Ok(true)
+            }
+            None => {
+                warn!("Student with ID {} not found", student_id);
+                Ok(false)
+            }
+        }
+    }
+
+    pub fn restore(&self, student_id: i32) -> Result<StudentWithRelations, AppError> {
+        let student = self.student_repository.restore(student_id)?;
+        self.audit_service
+            .record_restore(AuditEntityType::Student, student_id, &student);
+        info!("Student with ID {} was successfully restored", student_id);
+        Ok(student)
+    }
 
-        if deleted_count > 0 {
-            info!("Student with ID {} was successfully deleted", student_id);
-            Ok(true)
-        } else {
-            warn!("Student with ID {} not found", student_id);
-            Ok(false)
+    /// Записывает в журнал изменения учеников, которых перевели автоматически: зачислили
+    /// из очереди или отчислили из удаленной группы. `students` - их состояние до
+    /// изменения.
+    pub fn record_transfers(&self, students: &[StudentWithRelations]) {
+        for student in students {
+            let student_id = student.student.id;
+            match self.student_repository.get(student_id, true) {
+                Ok(transferred_student) => {
+                    self.audit_service.record_update(
+                        AuditEntityType::Student,
+                        student_id,
+                        student,
+                        &transferred_student,
+                    );
+                    info!(
+                        "Student with ID {} was transferred to group {:?}",
+                        student_id, transferred_student.student.student_group_id
+                    );
+                }
+                Err(e) => warn!(
+                    "Failed to record transfer of student with ID {}: {}",
+                    student_id, e
+                ),
+            }
         }
     }
 }
@@ -1,49 +1,93 @@
 use axum::extract::FromRef;
-use tracing::info;
+use diesel::result::Error as DieselError;
+use tracing::{info, warn};
 
 use crate::{
     AppState,
     error::AppError,
-    logic::repositories::teacher_repository::TeacherRepository,
-    models::teacher::{NewTeacher, Teacher, UpdateTeacher},
+    logic::{
+        repositories::teacher_repository::TeacherRepository, services::audit_service::AuditService,
+    },
+    models::{
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        teacher::{NewTeacher, Teacher, UpdateTeacher},
+    },
 };
 
 #[derive(Clone)]
 pub struct TeacherService {
     teacher_repository: TeacherRepository,
+    audit_service: AuditService,
 }
 
 impl TeacherService {
-    pub fn new(teacher_repository: TeacherRepository) -> Self {
-        Self { teacher_repository }
+    pub fn new(teacher_repository: TeacherRepository, audit_service: AuditService) -> Self {
+        Self {
+            teacher_repository,
+            audit_service,
+        }
     }
 
     pub fn create(&self, new_teacher: NewTeacher) -> Result<Teacher, AppError> {
         let teacher = self.teacher_repository.create(new_teacher)?;
+        self.audit_service
+            .record_create(AuditEntityType::Teacher, teacher.id, &teacher);
         info!("Successfully created teacher with ID {}", teacher.id);
         Ok(teacher)
     }
 
-    pub fn get(&self, teacher_id: i32) -> Result<Teacher, AppError> {
-        let teacher = self.teacher_repository.get(teacher_id)?;
+    pub fn get(&self, teacher_id: i32, include_deleted: bool) -> Result<Teacher, AppError> {
+        let teacher = self.teacher_repository.get(teacher_id, include_deleted)?;
         info!("Teacher with ID {} successfully get", teacher_id);
         Ok(teacher)
     }
 
+    pub fn get_all(
+        &self,
+        pagination: &PaginationParams,
+        include_deleted: bool,
+    ) -> Result<PaginatedResponse<Teacher>, AppError> {
+        let (teachers, total) = self.teacher_repository.get_all(
+            pagination.limit(),
+            pagination.offset(),
+            include_deleted,
+        )?;
+        info!("Got {} of {} teachers", teachers.len(), total);
+        Ok(PaginatedResponse::new(teachers, total, pagination))
+    }
+
     pub fn update(
         &self,
         teacher_id: i32,
         update_teacher: UpdateTeacher,
     ) -> Result<Teacher, AppError> {
+        let teacher = self.teacher_repository.get(teacher_id, false)?;
         let updated_teacher = self.teacher_repository.update(teacher_id, update_teacher)?;
+        self.audit_service.record_update(
+            AuditEntityType::Teacher,
+            teacher_id,
+            &teacher,
+            &updated_teacher,
+        );
         info!("Successfully updated teacher with ID {}", teacher_id);
         Ok(updated_teacher)
     }
 
     pub fn delete(&self, teacher_id: i32) -> Result<bool, AppError> {
+        let teacher = match self.teacher_repository.get(teacher_id, false) {
+            Ok(teacher) => teacher,
+            Err(AppError::Database(DieselError::NotFound)) => {
+                warn!("Teacher with ID {} not found", teacher_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
         let deleted_count = self.teacher_repository.delete(teacher_id)?;
 
         if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::Teacher, teacher_id, &teacher);
             info!("Teacher with ID {} successfully deleted", teacher_id);
             Ok(true)
         } else {
@@ -51,6 +95,14 @@ impl TeacherService {
             Ok(false)
         }
     }
+
+    pub fn restore(&self, teacher_id: i32) -> Result<Teacher, AppError> {
+        let teacher = self.teacher_repository.restore(teacher_id)?;
+        self.audit_service
+            .record_restore(AuditEntityType::Teacher, teacher_id, &teacher);
+        info!("Teacher with ID {} was successfully restored", teacher_id);
+        Ok(teacher)
+    }
 }
 
 impl FromRef<AppState> for TeacherService {
@@ -0,0 +1,605 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::extract::FromRef;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, TimeDelta, Utc, Weekday};
+use tracing::{error, info, warn};
+
+use crate::{
+    AppState,
+    config::ScheduleConfig,
+    error::AppError,
+    logic::{
+        repositories::timetable_repository::{TimetableRepository, TimetableScore},
+        services::{
+            lesson_service::LessonService, room_service::RoomService,
+            student_group_service::StudentGroupService, student_service::StudentService,
+        },
+        timetable::{self, LessonToPlace, TimetableProblem, TimetableSolution, Violation},
+    },
+    models::{
+        lesson::{LessonWithRelations, RawNewLesson},
+        lesson_conflict::LessonConflict,
+        pagination::{PaginatedResponse, PaginationParams},
+        timetable::{
+            NewTimetableJob, NewTimetableProposedLesson, NewTimetableViolation,
+            TeacherAvailability, TimetableJob, TimetableJobStatus, TimetableJobWithProposal,
+            TimetablePeriod, TimetableProposedLesson, TimetableRequest,
+        },
+    },
+};
+
+const DEFAULT_WEEKDAYS: [u32; 5] = [1, 2, 3, 4, 5];
+const DEFAULT_ITERATIONS: u32 = 20_000;
+const MAX_ITERATIONS: u32 = 200_000;
+const MAX_LESSONS_PER_WEEK: u32 = 2_000;
+
+/// Начало и конец слота расписания в UTC.
+type SlotTime = (DateTime<Utc>, DateTime<Utc>);
+
+#[derive(Clone)]
+pub struct TimetableService {
+    timetable_repository: TimetableRepository,
+    lesson_service: LessonService,
+    student_group_service: StudentGroupService,
+    student_service: StudentService,
+    room_service: RoomService,
+    schedule: ScheduleConfig,
+}
+
+impl TimetableService {
+    pub fn new(
+        timetable_repository: TimetableRepository,
+        lesson_service: LessonService,
+        student_group_service: StudentGroupService,
+        student_service: StudentService,
+        room_service: RoomService,
+        schedule: ScheduleConfig,
+    ) -> Self {
+        Self {
+            timetable_repository,
+            lesson_service,
+            student_group_service,
+            student_service,
+            room_service,
+            schedule,
+        }
+    }
+
+    /// Проверяет параметры и запускает составление расписания в фоне. Результат
+    /// появляется в задаче, когда она переходит в статус `completed`.
+    pub fn start(&self, request: TimetableRequest) -> Result<TimetableJob, AppError> {
+        self.validate_request(&request)?;
+        let seed = request
+            .seed
+            .unwrap_or_else(|| Utc::now().timestamp_micros());
+        let job = self.timetable_repository.create_job(NewTimetableJob {
+            week_start: request.week_start,
+            seed,
+        })?;
+
+        let service = self.clone();
+        let job_id = job.id;
+        tokio::task::spawn_blocking(move || service.run(job_id, seed, request));
+        info!(
+            "Started timetable job with ID {} for week of {}",
+            job.id, job.week_start
+        );
+        Ok(job)
+    }
+
+    pub fn get(&self, job_id: i32) -> Result<TimetableJobWithProposal, AppError> {
+        let job = self.timetable_repository.get_job(job_id)?;
+        let lessons = self.timetable_repository.get_proposed_lessons(job_id)?;
+        let violations = self.timetable_repository.get_violations(job_id)?;
+        info!("Timetable job with ID {} successfully get", job_id);
+        Ok(TimetableJobWithProposal {
+            job,
+            lessons,
+            violations,
+        })
+    }
+
+    pub fn get_all(
+        &self,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<TimetableJob>, AppError> {
+        let (jobs, total) = self
+            .timetable_repository
+            .get_all_jobs(pagination.limit(), pagination.offset())?;
+        info!("Got {} of {} timetable jobs", jobs.len(), total);
+        Ok(PaginatedResponse::new(jobs, total, pagination))
+    }
+
+    /// Создает уроки по составленному расписанию.
+    ///
+    /// Пока задача выполнялась, в расписании могли появиться новые уроки, поэтому все
+    /// предложенные уроки заново проверяются на пересечения. Уроки создаются вместе со
+    /// сменой статуса задачи в одной транзакции: если хотя бы один урок пересекается с
+    /// существующими, ничего не создается.
+    pub fn accept(&self, job_id: i32) -> Result<Vec<LessonWithRelations>, AppError> {
+        let job = self.timetable_repository.get_job(job_id)?;
+        if job.status != TimetableJobStatus::Completed {
+            return Err(AppError::BadRequest(format!(
+                "Timetable job {} is not completed, only completed jobs can be accepted",
+                job_id
+            )));
+        }
+
+        let proposed = self.timetable_repository.get_proposed_lessons(job_id)?;
+        let conflicts = self.find_proposal_conflicts(&proposed)?;
+        if !conflicts.is_empty() {
+            return Err(AppError::Conflict(conflicts));
+        }
+
+        let mut new_lessons = Vec::with_capacity(proposed.len());
+        for lesson in &proposed {
+            let new_lesson = RawNewLesson {
+                topic: lesson.topic.clone(),
+                starts_at: Some(lesson.starts_at),
+                ends_at: Some(lesson.ends_at),
+                scheduled_at: None,
+                student_group_id: Some(lesson.student_group_id),
+                room_id: lesson.room_id,
+            }
+            .into_new_lesson(&self.schedule)?;
+            self.lesson_service
+                .validate_room(new_lesson.room_id, new_lesson.student_group_id)?;
+            new_lessons.push(new_lesson);
+        }
+
+        let result = self.lesson_service.transaction(|connection| {
+            TimetableRepository::mark_accepted(connection, job_id)?;
+            new_lessons
+                .iter()
+                .map(|new_lesson| self.lesson_service.insert_lesson(connection, new_lesson))
+                .collect::<Result<Vec<i32>, AppError>>()
+        });
+        let lesson_ids = match result {
+            Err(e) if LessonService::is_overlap_error(&e) => {
+                warn!(
+                    "Timetable job {} overlaps lessons saved by a concurrent request",
+                    job_id
+                );
+                return Err(AppError::Conflict(self.find_proposal_conflicts(&proposed)?));
+            }
+            result => result?,
+        };
+
+        let created = lesson_ids
+            .into_iter()
+            .map(|lesson_id| self.lesson_service.get_created(lesson_id))
+            .collect::<Result<Vec<_>, AppError>>()?;
+        info!(
+            "Timetable job with ID {} accepted, created {} lessons",
+            job_id,
+            created.len()
+        );
+        Ok(created)
+    }
+
+    /// Помечает задачи, которые не успели завершиться до остановки сервера, как
+    /// завершившиеся ошибкой: составление расписания идет в памяти процесса и после
+    /// перезапуска не продолжается.
+    pub fn fail_interrupted(&self) -> Result<usize, AppError> {
+        let failed = self
+            .timetable_repository
+            .fail_unfinished("Interrupted by server restart".to_string())?;
+        if failed > 0 {
+            warn!("Marked {} interrupted timetable jobs as failed", failed);
+        }
+        Ok(failed)
+    }
+
+    /// Существующие уроки, пересекающиеся с предложенными.
+    fn find_proposal_conflicts(
+        &self,
+        proposed: &[TimetableProposedLesson],
+    ) -> Result<Vec<LessonConflict>, AppError> {
+        let mut conflicts: Vec<LessonConflict> = Vec::new();
+        for lesson in proposed {
+            for conflict in self.lesson_service.find_conflicts(
+                &[(lesson.starts_at, lesson.ends_at)],
+                Some(lesson.student_group_id),
+                lesson.room_id,
+                &[],
+            )? {
+                if !conflicts
+                    .iter()
+                    .any(|known| known.lesson.lesson.id == conflict.lesson.lesson.id)
+                {
+                    conflicts.push(conflict);
+                }
+            }
+        }
+        Ok(conflicts)
+    }
+
+    fn run(&self, job_id: i32, seed: i64, request: TimetableRequest) {
+        let result = self
+            .timetable_repository
+            .set_status(job_id, TimetableJobStatus::Running)
+            .and_then(|_| self.generate(job_id, seed, &request));
+        if let Err(err) = result {
+            error!("Timetable job {} failed: {}", job_id, err);
+            if let Err(err) = self
+                .timetable_repository
+                .set_failed(job_id, failure_reason(&err))
+            {
+                error!("Failed to mark timetable job {} as failed: {}", job_id, err);
+            }
+        }
+    }
+
+    fn generate(&self, job_id: i32, seed: i64, request: &TimetableRequest) -> Result<(), AppError> {
+        let days = Self::school_days(request);
+        let slot_times = self.slot_times(&days, &request.periods)?;
+        let rooms = self.room_service.get_many(request.room_ids.as_deref())?;
+
+        let mut groups: HashMap<i32, (Option<i32>, i64)> = HashMap::new();
+        for requirement in &request.requirements {
+            if groups.contains_key(&requirement.student_group_id) {
+                continue;
+            }
+            let student_group = self
+                .student_group_service
+                .get(requirement.student_group_id, false)?
+                .student_group;
+            let size = self
+                .student_service
+                .count_students_in_group(student_group.id)?;
+            groups.insert(student_group.id, (student_group.teacher_id, size));
+        }
+
+        let mut busy_groups = HashSet::new();
+        let mut busy_teachers = HashSet::new();
+        let mut busy_rooms = HashSet::new();
+        let (Some(week_from), Some(week_to)) = (
+            slot_times.iter().map(|(starts_at, _)| *starts_at).min(),
+            slot_times.iter().map(|(_, ends_at)| *ends_at).max(),
+        ) else {
+            return Err(AppError::BadRequest(
+                "Timetable has no time slots".to_string(),
+            ));
+        };
+        for existing in self
+            .lesson_service
+            .get_lessons_between(week_from, week_to)?
+        {
+            for (slot, (starts_at, ends_at)) in slot_times.iter().enumerate() {
+                if existing.lesson.starts_at >= *ends_at || existing.lesson.ends_at <= *starts_at {
+                    continue;
+                }
+                if let Some(group_id) = existing.lesson.student_group_id {
+                    busy_groups.insert((group_id, slot));
+                }
+                if let Some(teacher_id) = existing
+                    .student_group
+                    .as_ref()
+                    .and_then(|group| group.teacher_id)
+                {
+                    busy_teachers.insert((teacher_id, slot));
+                }
+                if let Some(room) = existing
+                    .room
+                    .as_ref()
+                    .and_then(|room| rooms.iter().position(|known| known.id == room.id))
+                {
+                    busy_rooms.insert((slot, room));
+                }
+            }
+        }
+
+        let periods_per_day = request.periods.len();
+        let mut lessons = Vec::new();
+        for requirement in &request.requirements {
+            let (teacher_id, size) = groups[&requirement.student_group_id];
+            let allowed_slots = (0..slot_times.len())
+                .filter(|slot| !busy_groups.contains(&(requirement.student_group_id, *slot)))
+                .filter(|slot| {
+                    teacher_id.is_none_or(|teacher_id| {
+                        !busy_teachers.contains(&(teacher_id, *slot))
+                            && Self::is_teacher_available(
+                                &request.teacher_availability,
+                                teacher_id,
+                                days[slot / periods_per_day],
+                                &request.periods[slot % periods_per_day],
+                            )
+                    })
+                })
+                .collect::<Vec<_>>();
+            let allowed_rooms = rooms
+                .iter()
+                .enumerate()
+                .filter(|(_, room)| i64::from(room.capacity) >= size)
+                .map(|(index, _)| index)
+                .collect::<Vec<_>>();
+            for _ in 0..requirement.lessons_per_week {
+                lessons.push(LessonToPlace {
+                    student_group_id: requirement.student_group_id,
+                    teacher_id,
+                    topic: requirement.topic.clone(),
+                    allowed_slots: allowed_slots.clone(),
+                    allowed_rooms: allowed_rooms.clone(),
+                    preferred_periods: requirement.preferred_periods.clone(),
+                });
+            }
+        }
+
+        let problem = TimetableProblem {
+            days: days.len(),
+            periods_per_day,
+            rooms: rooms.iter().map(|room| room.id).collect(),
+            busy_rooms,
+            max_lessons_per_day: request.max_lessons_per_day.map(|max| max as usize),
+            lessons,
+        };
+        let iterations = request.iterations.unwrap_or(DEFAULT_ITERATIONS);
+        let solution = timetable::solve(&problem, seed as u64, iterations as usize);
+        self.save_solution(job_id, &problem, &solution, &slot_times, &days)?;
+        info!(
+            "Timetable job with ID {} completed with score {}",
+            job_id,
+            solution.score()
+        );
+        Ok(())
+    }
+
+    fn save_solution(
+        &self,
+        job_id: i32,
+        problem: &TimetableProblem,
+        solution: &TimetableSolution,
+        slot_times: &[SlotTime],
+        days: &[NaiveDate],
+    ) -> Result<(), AppError> {
+        let lessons = problem
+            .lessons
+            .iter()
+            .zip(&solution.placements)
+            .filter_map(|(lesson, placement)| {
+                placement.map(|placement| NewTimetableProposedLesson {
+                    timetable_job_id: job_id,
+                    topic: lesson.topic.clone(),
+                    student_group_id: lesson.student_group_id,
+                    room_id: placement.room.map(|room| problem.rooms[room]),
+                    starts_at: slot_times[placement.slot].0,
+                    ends_at: slot_times[placement.slot].1,
+                })
+            })
+            .collect();
+
+        let violations = solution
+            .violations
+            .iter()
+            .map(|violation| {
+                let lesson = &problem.lessons[violation.lesson()];
+                let placed_at = solution.placements[violation.lesson()].map(|placement| {
+                    (
+                        days[problem.day_of(placement.slot)],
+                        self.schedule.local_time(slot_times[placement.slot].0),
+                    )
+                });
+                let description = match (violation, placed_at) {
+                    (Violation::NotPreferredPeriod { .. }, Some((date, time))) => format!(
+                        "Lesson \"{}\" of group {} is placed on {} at {}, outside of preferred periods",
+                        lesson.topic, lesson.student_group_id, date, time
+                    ),
+                    (Violation::RepeatedTopicInDay { .. }, Some((date, _))) => format!(
+                        "Group {} has more than one \"{}\" lesson on {}",
+                        lesson.student_group_id, lesson.topic, date
+                    ),
+                    _ => format!(
+                        "Lesson \"{}\" of group {} could not be placed",
+                        lesson.topic, lesson.student_group_id
+                    ),
+                };
+                NewTimetableViolation {
+                    timetable_job_id: job_id,
+                    kind: violation.kind().to_string(),
+                    is_hard: violation.is_hard(),
+                    description,
+                }
+            })
+            .collect();
+
+        self.timetable_repository.save_result(
+            job_id,
+            lessons,
+            violations,
+            TimetableScore {
+                hard_violations: solution.hard_violations() as i32,
+                soft_violations: solution.soft_violations() as i32,
+                score: solution.score() as i32,
+            },
+        )
+    }
+
+    fn school_days(request: &TimetableRequest) -> Vec<NaiveDate> {
+        let mut weekdays = request
+            .weekdays
+            .clone()
+            .unwrap_or_else(|| DEFAULT_WEEKDAYS.to_vec());
+        weekdays.sort_unstable();
+        weekdays.dedup();
+        weekdays
+            .into_iter()
+            .map(|weekday| request.week_start + TimeDelta::days(i64::from(weekday) - 1))
+            .collect()
+    }
+
+    /// Время каждого слота: сначала все уроки первого учебного дня, затем второго и т.д.
+    fn slot_times(
+        &self,
+        days: &[NaiveDate],
+        periods: &[TimetablePeriod],
+    ) -> Result<Vec<SlotTime>, AppError> {
+        let mut slot_times = Vec::with_capacity(days.len() * periods.len());
+        for date in days {
+            for period in periods {
+                let starts_at = self.schedule.local_to_utc(*date, period.start_time)?;
+                let ends_at = starts_at + TimeDelta::minutes(i64::from(period.duration_minutes));
+                slot_times.push((starts_at, ends_at));
+            }
+        }
+        Ok(slot_times)
+    }
+
+    fn is_teacher_available(
+        availability: &[TeacherAvailability],
+        teacher_id: i32,
+        date: NaiveDate,
+        period: &TimetablePeriod,
+    ) -> bool {
+        let mut entries = availability
+            .iter()
+            .filter(|entry| entry.teacher_id == teacher_id)
+            .peekable();
+        if entries.peek().is_none() {
+            return true;
+        }
+        let period_end = period_end(period);
+        entries.any(|entry| {
+            entry.weekday == date.weekday().number_from_monday()
+                && entry.from <= period.start_time
+                && period_end <= entry.to
+        })
+    }
+
+    fn validate_request(&self, request: &TimetableRequest) -> Result<(), AppError> {
+        if request.week_start.weekday() != Weekday::Mon {
+            return Err(AppError::BadRequest(format!(
+                "week_start must be a Monday, got {}",
+                request.week_start
+            )));
+        }
+
+        if let Some(weekdays) = &request.weekdays {
+            if weekdays.is_empty() {
+                return Err(AppError::BadRequest(
+                    "At least one weekday is required".to_string(),
+                ));
+            }
+            if let Some(weekday) = weekdays.iter().find(|weekday| !(1..=7).contains(*weekday)) {
+                return Err(AppError::BadRequest(format!(
+                    "Weekday must be between 1 and 7, got {}",
+                    weekday
+                )));
+            }
+        }
+
+        if request.periods.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one period is required".to_string(),
+            ));
+        }
+        for period in &request.periods {
+            let starts_at = self
+                .schedule
+                .local_to_utc(request.week_start, period.start_time)?;
+            self.lesson_service.validate_lesson_time(
+                starts_at,
+                starts_at + TimeDelta::minutes(i64::from(period.duration_minutes)),
+            )?;
+            if period_end(period) <= period.start_time {
+                return Err(AppError::BadRequest(format!(
+                    "Period starting at {} must end before midnight",
+                    period.start_time
+                )));
+            }
+        }
+        for pair in request.periods.windows(2) {
+            if period_end(&pair[0]) > pair[1].start_time {
+                return Err(AppError::BadRequest(format!(
+                    "Periods must be ordered and must not overlap (period at {} overlaps period at {})",
+                    pair[0].start_time, pair[1].start_time
+                )));
+            }
+        }
+
+        if request.requirements.is_empty() {
+            return Err(AppError::BadRequest(
+                "At least one lesson requirement is required".to_string(),
+            ));
+        }
+        let mut total_lessons: u32 = 0;
+        for requirement in &request.requirements {
+            if requirement.lessons_per_week == 0 {
+                return Err(AppError::BadRequest(format!(
+                    "Requirement \"{}\" for group {} must have at least one lesson per week",
+                    requirement.topic, requirement.student_group_id
+                )));
+            }
+            if let Some(period) = requirement
+                .preferred_periods
+                .iter()
+                .find(|period| **period >= request.periods.len())
+            {
+                return Err(AppError::BadRequest(format!(
+                    "Preferred period {} does not exist, there are {} periods",
+                    period,
+                    request.periods.len()
+                )));
+            }
+            total_lessons = total_lessons.saturating_add(requirement.lessons_per_week);
+        }
+        if total_lessons > MAX_LESSONS_PER_WEEK {
+            return Err(AppError::BadRequest(format!(
+                "Timetable can not contain more than {} lessons",
+                MAX_LESSONS_PER_WEEK
+            )));
+        }
+
+        if let Some(entry) = request
+            .teacher_availability
+            .iter()
+            .find(|entry| !(1..=7).contains(&entry.weekday) || entry.from >= entry.to)
+        {
+            return Err(AppError::BadRequest(format!(
+                "Invalid availability of teacher {}: weekday must be between 1 and 7 and the interval must not be empty",
+                entry.teacher_id
+            )));
+        }
+        if request.max_lessons_per_day == Some(0) {
+            return Err(AppError::BadRequest(
+                "max_lessons_per_day must be positive".to_string(),
+            ));
+        }
+        if request
+            .iterations
+            .is_some_and(|iterations| iterations > MAX_ITERATIONS)
+        {
+            return Err(AppError::BadRequest(format!(
+                "iterations can not exceed {}",
+                MAX_ITERATIONS
+            )));
+        }
+
+        for requirement in &request.requirements {
+            self.student_group_service
+                .get(requirement.student_group_id, false)?;
+        }
+        self.room_service.get_many(request.room_ids.as_deref())?;
+        Ok(())
+    }
+}
+
+/// Местное время окончания урока. Если урок заканчивается после полуночи, результат
+/// оказывается раньше начала.
+fn period_end(period: &TimetablePeriod) -> NaiveTime {
+    period.start_time + TimeDelta::minutes(i64::from(period.duration_minutes))
+}
+
+/// Текст ошибки для сохранения в задаче. Сообщения об ошибках базы данных не
+/// раскрываются.
+fn failure_reason(err: &AppError) -> String {
+    match err {
+        AppError::BadRequest(msg) | AppError::NotFound(msg) => msg.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl FromRef<AppState> for TimetableService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.timetable_service.clone()
+    }
+}
@@ -1,26 +1,77 @@
 use axum::extract::FromRef;
-use tracing::info;
+use chrono::Utc;
+use diesel::result::Error as DieselError;
+use password_auth::generate_hash;
+use tracing::{info, warn};
 
 use crate::{
     AppState,
+    auth::token::{generate_token, hash_token},
+    config::PasswordConfig,
     error::AppError,
-    logic::repositories::user_repository::UserRepository,
-    models::user::{NewUser, RawNewUser, UpdateUser, User},
+    logic::{
+        repositories::{
+            password_reset_repository::PasswordResetRepository, user_repository::UserRepository,
+        },
+        services::{
+            audit_service::AuditService, parent_service::ParentService,
+            teacher_service::TeacherService,
+        },
+    },
+    models::{
+        audit::AuditEntityType,
+        pagination::{PaginatedResponse, PaginationParams},
+        password_reset::{IssuedPasswordResetToken, NewPasswordResetToken, PasswordReset},
+        user::{
+            ChangePassword, NewUser, RawNewUser, RawUpdateUser, UpdateUser, User, UserWithProfile,
+            validate_password_policy,
+        },
+        validation::ValidationErrors,
+    },
 };
 
 #[derive(Clone)]
 pub struct UserService {
     user_repository: UserRepository,
+    password_reset_repository: PasswordResetRepository,
+    teacher_service: TeacherService,
+    parent_service: ParentService,
+    audit_service: AuditService,
+    password_config: PasswordConfig,
 }
 
 impl UserService {
-    pub fn new(user_repository: UserRepository) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: UserRepository,
+        password_reset_repository: PasswordResetRepository,
+        teacher_service: TeacherService,
+        parent_service: ParentService,
+        audit_service: AuditService,
+        password_config: PasswordConfig,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_reset_repository,
+            teacher_service,
+            parent_service,
+            audit_service,
+            password_config,
+        }
     }
 
     pub fn create(&self, raw_new_user: RawNewUser) -> Result<User, AppError> {
+        let mut errors = ValidationErrors::default();
+        validate_password_policy(
+            &mut errors,
+            "password",
+            raw_new_user.password(),
+            &self.password_config,
+        );
+        errors.into_result()?;
         let new_user = NewUser::from(raw_new_user);
         let new_user = self.user_repository.create(new_user)?;
+        self.audit_service
+            .record_create(AuditEntityType::User, new_user.id, &new_user);
         info!(
             "Successfully created user with ID {} and username {}",
             new_user.id, new_user.username
@@ -34,15 +85,141 @@ impl UserService {
         Ok(user)
     }
 
-    pub fn update(&self, user_id: i32, update_user: UpdateUser) -> Result<User, AppError> {
-        let user = self.user_repository.update(user_id, update_user)?;
+    /// Пользователь вместе со связанными профилями. Профили возвращаются, даже если
+    /// преподаватель или родитель удален.
+    pub fn get_with_profile(&self, user: User) -> Result<UserWithProfile, AppError> {
+        let teacher = user
+            .teacher_id
+            .map(|teacher_id| self.teacher_service.get(teacher_id, true))
+            .transpose()?;
+        let parent = user
+            .parent_id
+            .map(|parent_id| self.parent_service.get(parent_id, true))
+            .transpose()?;
+        info!("Got profile of user with ID {}", user.id);
+        Ok(UserWithProfile {
+            user,
+            teacher,
+            parent,
+        })
+    }
+
+    pub fn get_all(
+        &self,
+        pagination: &PaginationParams,
+    ) -> Result<PaginatedResponse<User>, AppError> {
+        let (users, total) = self
+            .user_repository
+            .get_all(pagination.limit(), pagination.offset())?;
+        info!("Got {} of {} users", users.len(), total);
+        Ok(PaginatedResponse::new(users, total, pagination))
+    }
+
+    pub fn update(&self, user_id: i32, raw_update_user: RawUpdateUser) -> Result<User, AppError> {
+        let update_user = UpdateUser::from(raw_update_user);
+        let user = self.user_repository.get(user_id)?;
+        let updated_user = self.user_repository.update(user_id, update_user)?;
+        self.audit_service
+            .record_update(AuditEntityType::User, user_id, &user, &updated_user);
         info!("Successfully updated user with ID {}", user_id);
-        Ok(user)
+        Ok(updated_user)
+    }
+
+    /// Смена пароля самим пользователем. Остальные сессии пользователя после этого
+    /// становятся недействительными.
+    pub fn change_password(&self, user: &User, change: ChangePassword) -> Result<User, AppError> {
+        let mut errors = ValidationErrors::default();
+        errors.check(
+            user.has_password(&change.old_password),
+            "old_password",
+            "incorrect",
+            "Old password is incorrect",
+        );
+        validate_password_policy(
+            &mut errors,
+            "new_password",
+            &change.new_password,
+            &self.password_config,
+        );
+        errors.into_result()?;
+
+        let updated_user = self
+            .user_repository
+            .update_password(user.id, &generate_hash(change.new_password))?;
+        self.audit_service
+            .record_update(AuditEntityType::User, user.id, user, &updated_user);
+        info!("User with ID {} changed their password", user.id);
+        Ok(updated_user)
+    }
+
+    /// Выдает одноразовый токен, по которому пользователь сможет задать новый пароль.
+    /// Ранее выданные неиспользованные токены перестают действовать.
+    pub fn issue_password_reset(
+        &self,
+        user_id: i32,
+        issued_by: &User,
+    ) -> Result<IssuedPasswordResetToken, AppError> {
+        let user = self.user_repository.get(user_id)?;
+        let token = generate_token();
+        let reset_token = self
+            .password_reset_repository
+            .create(NewPasswordResetToken {
+                user_id: user.id,
+                token_hash: hash_token(&token),
+                created_by: Some(issued_by.id),
+                expires_at: Utc::now() + self.password_config.reset_token_ttl,
+            })?;
+        info!(
+            "User with ID {} issued password reset token for user with ID {}",
+            issued_by.id, user.id
+        );
+        Ok(IssuedPasswordResetToken {
+            user_id: user.id,
+            token,
+            expires_at: reset_token.expires_at,
+        })
+    }
+
+    /// Устанавливает новый пароль по токену сброса. Токен можно использовать один раз.
+    pub fn reset_password(&self, password_reset: PasswordReset) -> Result<User, AppError> {
+        let mut errors = ValidationErrors::default();
+        validate_password_policy(
+            &mut errors,
+            "new_password",
+            &password_reset.new_password,
+            &self.password_config,
+        );
+        errors.into_result()?;
+
+        let Some((user, updated_user)) = self.password_reset_repository.reset_password(
+            &hash_token(&password_reset.token),
+            &generate_hash(password_reset.new_password),
+        )?
+        else {
+            warn!("Attempt to reset password with invalid or expired token");
+            return Err(AppError::BadRequest(
+                "Password reset token is invalid or expired".to_string(),
+            ));
+        };
+        self.audit_service
+            .record_update(AuditEntityType::User, user.id, &user, &updated_user);
+        info!("Password of user with ID {} was reset", user.id);
+        Ok(updated_user)
     }
 
     pub fn delete(&self, user_id: i32) -> Result<bool, AppError> {
+        let user = match self.user_repository.get(user_id) {
+            Ok(user) => user,
+            Err(AppError::Database(DieselError::NotFound)) => {
+                info!("User with ID {} not found", user_id);
+                return Ok(false);
+            }
+            Err(e) => return Err(e),
+        };
         let deleted_count = self.user_repository.delete(user_id)?;
         if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::User, user_id, &user);
             info!("Deleted user with ID {}", user_id);
             Ok(true)
         } else {
@@ -0,0 +1,178 @@
+use axum::extract::FromRef;
+use tracing::{info, warn};
+
+use crate::{
+    AppState,
+    error::AppError,
+    logic::{
+        repositories::waitlist_repository::WaitlistRepository,
+        services::{
+            audit_service::AuditService, student_group_service::StudentGroupService,
+            student_service::StudentService,
+        },
+    },
+    models::{
+        audit::AuditEntityType,
+        waitlist::{
+            RawNewWaitlistEntry, UpdateWaitlistEntry, WaitlistEntry, WaitlistEntryWithStudent,
+        },
+    },
+};
+
+#[derive(Clone)]
+pub struct WaitlistService {
+    waitlist_repository: WaitlistRepository,
+    student_group_service: StudentGroupService,
+    student_service: StudentService,
+    audit_service: AuditService,
+}
+
+impl WaitlistService {
+    pub fn new(
+        waitlist_repository: WaitlistRepository,
+        student_group_service: StudentGroupService,
+        student_service: StudentService,
+        audit_service: AuditService,
+    ) -> Self {
+        Self {
+            waitlist_repository,
+            student_group_service,
+            student_service,
+            audit_service,
+        }
+    }
+
+    /// Ставит ученика в очередь группы. Встать в очередь можно только в заполненную
+    /// группу: если свободные места есть, ученика нужно зачислить сразу.
+    pub fn add(
+        &self,
+        student_group_id: i32,
+        raw_new_entry: RawNewWaitlistEntry,
+    ) -> Result<WaitlistEntryWithStudent, AppError> {
+        let student_group = self.student_group_service.get(student_group_id, false)?;
+        let student = self
+            .student_service
+            .get(raw_new_entry.student_id, false)?
+            .student;
+        if student.student_group_id == Some(student_group_id) {
+            return Err(AppError::BadRequest(format!(
+                "Student with ID {} is already in group {}",
+                student.id, student_group_id
+            )));
+        }
+        if student_group.student_group.free_spots > 0 {
+            return Err(AppError::BadRequest(format!(
+                "Student group with ID {} has free spots, enroll the student directly",
+                student_group_id
+            )));
+        }
+        if self
+            .waitlist_repository
+            .get_by_student(student_group_id, student.id)?
+            .is_some()
+        {
+            return Err(AppError::BadRequest(format!(
+                "Student with ID {} is already on the waitlist of group {}",
+                student.id, student_group_id
+            )));
+        }
+
+        let entry = self.waitlist_repository.add(student_group_id, student.id)?;
+        self.audit_service
+            .record_create(AuditEntityType::WaitlistEntry, entry.id, &entry);
+        info!(
+            "Student with ID {} added to the waitlist of group {} at position {}",
+            student.id, student_group_id, entry.position
+        );
+        Ok(WaitlistEntryWithStudent { entry, student })
+    }
+
+    pub fn get_by_group_id(
+        &self,
+        student_group_id: i32,
+    ) -> Result<Vec<WaitlistEntryWithStudent>, AppError> {
+        self.student_group_service.get(student_group_id, false)?;
+        let entries = self.waitlist_repository.get_by_group_id(student_group_id)?;
+        info!(
+            "Got {} waitlist entries for group with ID {}",
+            entries.len(),
+            student_group_id
+        );
+        Ok(entries)
+    }
+
+    /// Переносит запись на новую позицию и возвращает очередь группы целиком.
+    pub fn update(
+        &self,
+        student_group_id: i32,
+        entry_id: i32,
+        update_entry: UpdateWaitlistEntry,
+    ) -> Result<Vec<WaitlistEntryWithStudent>, AppError> {
+        if update_entry.position < 1 {
+            return Err(AppError::BadRequest(
+                "Waitlist position must be positive".to_string(),
+            ));
+        }
+        let entry = self.get_entry_in_group(student_group_id, entry_id)?;
+        let moved_entry = self
+            .waitlist_repository
+            .move_to(entry_id, update_entry.position)?;
+        self.audit_service.record_update(
+            AuditEntityType::WaitlistEntry,
+            entry_id,
+            &entry,
+            &moved_entry,
+        );
+        info!(
+            "Waitlist entry with ID {} moved to position {}",
+            entry_id, moved_entry.position
+        );
+        self.get_by_group_id(student_group_id)
+    }
+
+    pub fn delete(&self, student_group_id: i32, entry_id: i32) -> Result<bool, AppError> {
+        let entry = match self.get_entry_in_group(student_group_id, entry_id) {
+            Ok(entry) => entry,
+            Err(AppError::NotFound(_)) => {
+                warn!("Waitlist entry with ID {} not found", entry_id);
+                return Ok(false);
+            }
+            Err(err) => return Err(err),
+        };
+
+        let deleted_count = self.waitlist_repository.delete(entry_id)?;
+        if deleted_count > 0 {
+            self.audit_service
+                .record_delete(AuditEntityType::WaitlistEntry, entry_id, &entry);
+            info!(
+                "Waitlist entry with ID {} was successfully deleted",
+                entry_id
+            );
+            Ok(true)
+        } else {
+            warn!("Waitlist entry with ID {} not found", entry_id);
+            Ok(false)
+        }
+    }
+
+    fn get_entry_in_group(
+        &self,
+        student_group_id: i32,
+        entry_id: i32,
+    ) -> Result<WaitlistEntry, AppError> {
+        let entry = self.waitlist_repository.get(entry_id)?;
+        if entry.student_group_id != student_group_id {
+            return Err(AppError::NotFound(format!(
+                "Waitlist entry with ID {} does not belong to group {}",
+                entry_id, student_group_id
+            )));
+        }
+        Ok(entry)
+    }
+}
+
+impl FromRef<AppState> for WaitlistService {
+    fn from_ref(state: &AppState) -> Self {
+        state.services.waitlist_service.clone()
+    }
+}
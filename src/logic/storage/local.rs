@@ -0,0 +1,149 @@
+use std::{
+    ffi::OsString,
+    io::{ErrorKind, SeekFrom},
+    ops::Range,
+    path::{Component, Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use futures_util::TryStreamExt;
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+};
+use tokio_util::io::ReaderStream;
+use tracing::{error, info};
+
+use super::{ByteStream, Storage, UploadStream};
+use crate::error::AppError;
+
+/// Файлы на локальном диске в каталоге `root`. Ключ файла — его путь внутри каталога.
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Путь к файлу по ключу. Ключи с `..` и абсолютные пути не принимаются, чтобы
+    /// нельзя было выйти за пределы каталога хранилища.
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(AppError::Storage(format!("Invalid storage key {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    fn not_found(key: &str) -> AppError {
+        AppError::NotFound(format!("File {} not found in storage", key))
+    }
+
+    /// Путь к временному файлу, в который пишется файл `path` до конца загрузки.
+    fn temp_path(path: &Path) -> PathBuf {
+        let mut temp_path = OsString::from(path.as_os_str());
+        temp_path.push(".part");
+        PathBuf::from(temp_path)
+    }
+
+    async fn write(path: &Path, data: &mut UploadStream<'_>) -> Result<(), AppError> {
+        let mut file = fs::File::create(path).await?;
+        while let Some(chunk) = data.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+    async fn put(
+        &self,
+        key: &str,
+        data: &mut UploadStream<'_>,
+        _content_type: &str,
+    ) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        // Файл появляется под своим ключом только целиком, прерванная загрузка не оставляет
+        // в хранилище обрезанный файл
+        let temp_path = Self::temp_path(&path);
+        let result = match Self::write(&temp_path, data).await {
+            Ok(()) => fs::rename(&temp_path, &path).await.map_err(AppError::from),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            match fs::remove_file(&temp_path).await {
+                Err(delete_error) if delete_error.kind() != ErrorKind::NotFound => error!(
+                    "Failed to delete temporary file {}: {}",
+                    temp_path.display(),
+                    delete_error
+                ),
+                _ => {}
+            }
+            return Err(e);
+        }
+        info!("File {} successfully written", path.display());
+        Ok(())
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, AppError> {
+        let path = self.path(key)?;
+        match fs::metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => Ok(metadata.len()),
+            Ok(_) => Err(Self::not_found(key)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(Self::not_found(key)),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<ByteStream, AppError> {
+        let path = self.path(key)?;
+        let mut file = match fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(Self::not_found(key)),
+            Err(e) => return Err(e.into()),
+        };
+        match range {
+            Some(range) => {
+                file.seek(SeekFrom::Start(range.start)).await?;
+                Ok(Box::pin(ReaderStream::new(
+                    file.take(range.end - range.start),
+                )))
+            }
+            None => Ok(Box::pin(ReaderStream::new(file))),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        match fs::remove_file(&path).await {
+            Ok(()) => {
+                info!("File {} successfully deleted", path.display());
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), AppError> {
+        let path = self.path(prefix)?;
+        match fs::remove_dir_all(&path).await {
+            Ok(()) => {
+                info!("Directory {} successfully deleted", path.display());
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Хранилище файлов документов.
+//!
+//! Сервисы работают с файлами только через [`Storage`] и ничего не знают о том, где они
+//! лежат. Файл адресуется ключом вида `teachers/1/<uuid>.pdf`, части ключа разделяются `/`.
+//! Реализация выбирается настройкой `STORAGE_BACKEND` при старте.
+
+use std::{io, ops::Range, pin::Pin, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::Stream;
+
+use crate::{config::StorageConfig, error::AppError};
+
+mod local;
+mod s3;
+
+pub use local::LocalStorage;
+pub use s3::S3Storage;
+
+/// Содержимое файла, читаемое по частям.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send>>;
+
+/// Содержимое сохраняемого файла, которое хранилище читает по частям, не собирая файл
+/// в памяти целиком.
+pub type UploadStream<'a> = dyn Stream<Item = Result<Bytes, io::Error>> + Send + Unpin + 'a;
+
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Сохраняет файл, заменяя существующий с тем же ключом. Если чтение `data` прервалось
+    /// ошибкой, файл не сохраняется и существующий файл не меняется.
+    async fn put(
+        &self,
+        key: &str,
+        data: &mut UploadStream<'_>,
+        content_type: &str,
+    ) -> Result<(), AppError>;
+
+    /// Размер файла в байтах. Если файла нет, возвращается 404.
+    async fn size(&self, key: &str) -> Result<u64, AppError>;
+
+    /// Читает файл целиком или, если указан `range`, только байты из него. Диапазон
+    /// должен лежать внутри файла. Если файла нет, возвращается 404.
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<ByteStream, AppError>;
+
+    /// Удаляет файл. Отсутствие файла ошибкой не считается.
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+
+    /// Удаляет все файлы, ключи которых начинаются с `prefix/`.
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), AppError>;
+}
+
+pub fn init_storage(config: &StorageConfig) -> Arc<dyn Storage> {
+    match config {
+        StorageConfig::Local { root } => Arc::new(LocalStorage::new(root.clone())),
+        StorageConfig::S3(s3_config) => Arc::new(S3Storage::new(s3_config)),
+    }
+}
@@ -0,0 +1,324 @@
+use std::{io, ops::Range, time::Duration};
+
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures_util::TryStreamExt;
+use reqwest::{Client, StatusCode, Url, header};
+use rusty_s3::{
+    Bucket, Credentials, S3Action, UrlStyle,
+    actions::{CreateMultipartUpload, ListObjectsV2},
+};
+use tracing::{error, info};
+
+use super::{ByteStream, Storage, UploadStream};
+use crate::{config::S3Config, error::AppError};
+
+/// Сколько действует подпись запроса. Запрос отправляется сразу после подписи, так что
+/// запас нужен только на расхождение часов с хранилищем.
+const SIGNATURE_TTL: Duration = Duration::from_secs(60);
+
+/// Размер части при загрузке файла по частям. Файлы меньше одной части загружаются одним
+/// запросом. S3 требует, чтобы все части, кроме последней, были не меньше 5 МиБ.
+const PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Файлы в бакете S3-совместимого хранилища. Ключ файла — ключ объекта в бакете.
+pub struct S3Storage {
+    client: Client,
+    bucket: Bucket,
+    credentials: Credentials,
+}
+
+impl S3Storage {
+    pub fn new(config: &S3Config) -> Self {
+        let endpoint = Url::parse(&config.endpoint).expect("S3_ENDPOINT must be a valid URL");
+        let url_style = if config.path_style {
+            UrlStyle::Path
+        } else {
+            UrlStyle::VirtualHost
+        };
+        let bucket = Bucket::new(
+            endpoint,
+            url_style,
+            config.bucket.clone(),
+            config.region.clone(),
+        )
+        .expect("S3_ENDPOINT must be an http or https URL");
+
+        Self {
+            client: Client::new(),
+            bucket,
+            credentials: Credentials::new(&config.access_key_id, &config.secret_access_key),
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, AppError> {
+        request
+            .send()
+            .await
+            .map_err(|e| AppError::Storage(format!("S3 request failed: {}", e)))
+    }
+
+    fn not_found(key: &str) -> AppError {
+        AppError::NotFound(format!("File {} not found in storage", key))
+    }
+
+    async fn error(response: reqwest::Response) -> AppError {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        AppError::Storage(format!("S3 responded with {}: {}", status, body))
+    }
+
+    async fn body(response: reqwest::Response) -> Result<Bytes, AppError> {
+        response
+            .bytes()
+            .await
+            .map_err(|e| AppError::Storage(format!("S3 request failed: {}", e)))
+    }
+
+    /// Читает из `data` следующую часть файла. Часть меньше [`PART_SIZE`] только в конце
+    /// файла.
+    async fn read_part(data: &mut UploadStream<'_>) -> Result<Bytes, AppError> {
+        let mut part = BytesMut::new();
+        while part.len() < PART_SIZE {
+            match data.try_next().await? {
+                Some(chunk) => part.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+        Ok(part.freeze())
+    }
+
+    async fn put_object(&self, key: &str, data: Bytes, content_type: &str) -> Result<(), AppError> {
+        let url = self
+            .bucket
+            .put_object(Some(&self.credentials), key)
+            .sign(SIGNATURE_TTL);
+        let response = self
+            .send(
+                self.client
+                    .put(url)
+                    .header(header::CONTENT_TYPE, content_type)
+                    .body(data),
+            )
+            .await?;
+        if !response.status().is_success() {
+            return Err(Self::error(response).await);
+        }
+        Ok(())
+    }
+
+    /// Загружает файл по частям, начиная с уже прочитанной части `first_part`. Если загрузка
+    /// не удалась, загруженные части удаляются.
+    async fn put_multipart(
+        &self,
+        key: &str,
+        first_part: Bytes,
+        data: &mut UploadStream<'_>,
+        content_type: &str,
+    ) -> Result<(), AppError> {
+        let url = self
+            .bucket
+            .create_multipart_upload(Some(&self.credentials), key)
+            .sign(SIGNATURE_TTL);
+        let response = self
+            .send(
+                self.client
+                    .post(url)
+                    .header(header::CONTENT_TYPE, content_type),
+            )
+            .await?;
+        if !response.status().is_success() {
+            return Err(Self::error(response).await);
+        }
+        let upload =
+            CreateMultipartUpload::parse_response(Self::body(response).await?).map_err(|e| {
+                AppError::Storage(format!("Failed to parse S3 multipart upload: {}", e))
+            })?;
+        let upload_id = upload.upload_id();
+
+        let result = match self.upload_parts(key, upload_id, first_part, data).await {
+            Ok(etags) => self.complete_multipart(key, upload_id, &etags).await,
+            Err(e) => Err(e),
+        };
+        if result.is_err() {
+            let url = self
+                .bucket
+                .abort_multipart_upload(Some(&self.credentials), key, upload_id)
+                .sign(SIGNATURE_TTL);
+            match self.send(self.client.delete(url)).await {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => error!(
+                    "Failed to abort multipart upload of object {}: {}",
+                    key,
+                    Self::error(response).await
+                ),
+                Err(abort_error) => error!(
+                    "Failed to abort multipart upload of object {}: {}",
+                    key, abort_error
+                ),
+            }
+        }
+        result
+    }
+
+    /// Загружает части файла и возвращает их `ETag` в порядке загрузки.
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        first_part: Bytes,
+        data: &mut UploadStream<'_>,
+    ) -> Result<Vec<String>, AppError> {
+        let mut etags = Vec::new();
+        let mut part = first_part;
+        while !part.is_empty() {
+            let part_number = u16::try_from(etags.len() + 1)
+                .map_err(|_| AppError::Storage(format!("Object {} has too many parts", key)))?;
+            let url = self
+                .bucket
+                .upload_part(Some(&self.credentials), key, part_number, upload_id)
+                .sign(SIGNATURE_TTL);
+            let response = self.send(self.client.put(url).body(part)).await?;
+            if !response.status().is_success() {
+                return Err(Self::error(response).await);
+            }
+            let etag = response
+                .headers()
+                .get(header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(AppError::Storage(format!(
+                    "S3 did not return ETag of part {} of object {}",
+                    part_number, key
+                )))?;
+            etags.push(etag.to_string());
+            part = Self::read_part(data).await?;
+        }
+        Ok(etags)
+    }
+
+    async fn complete_multipart(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<(), AppError> {
+        let action = self.bucket.complete_multipart_upload(
+            Some(&self.credentials),
+            key,
+            upload_id,
+            etags.iter().map(String::as_str),
+        );
+        let url = action.sign(SIGNATURE_TTL);
+        let response = self.send(self.client.post(url).body(action.body())).await?;
+        if !response.status().is_success() {
+            return Err(Self::error(response).await);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Storage for S3Storage {
+    async fn put(
+        &self,
+        key: &str,
+        data: &mut UploadStream<'_>,
+        content_type: &str,
+    ) -> Result<(), AppError> {
+        let first_part = Self::read_part(data).await?;
+        if first_part.len() < PART_SIZE {
+            self.put_object(key, first_part, content_type).await?;
+        } else {
+            self.put_multipart(key, first_part, data, content_type)
+                .await?;
+        }
+        info!("Object {} successfully uploaded", key);
+        Ok(())
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, AppError> {
+        let url = self
+            .bucket
+            .head_object(Some(&self.credentials), key)
+            .sign(SIGNATURE_TTL);
+        let response = self.send(self.client.head(url)).await?;
+        match response.status() {
+            status if status.is_success() => response
+                .headers()
+                .get(header::CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .ok_or(AppError::Storage(format!(
+                    "S3 did not return size of object {}",
+                    key
+                ))),
+            StatusCode::NOT_FOUND => Err(Self::not_found(key)),
+            _ => Err(Self::error(response).await),
+        }
+    }
+
+    async fn get(&self, key: &str, range: Option<Range<u64>>) -> Result<ByteStream, AppError> {
+        let url = self
+            .bucket
+            .get_object(Some(&self.credentials), key)
+            .sign(SIGNATURE_TTL);
+        let mut request = self.client.get(url);
+        if let Some(range) = range {
+            request = request.header(
+                header::RANGE,
+                format!("bytes={}-{}", range.start, range.end - 1),
+            );
+        }
+        let response = self.send(request).await?;
+        match response.status() {
+            status if status.is_success() => {
+                Ok(Box::pin(response.bytes_stream().map_err(io::Error::other)))
+            }
+            StatusCode::NOT_FOUND => Err(Self::not_found(key)),
+            _ => Err(Self::error(response).await),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        let url = self
+            .bucket
+            .delete_object(Some(&self.credentials), key)
+            .sign(SIGNATURE_TTL);
+        let response = self.send(self.client.delete(url)).await?;
+        // S3 отвечает 204 и на удаление несуществующего объекта, но не все совместимые
+        // хранилища ведут себя так же
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            return Err(Self::error(response).await);
+        }
+        info!("Object {} successfully deleted", key);
+        Ok(())
+    }
+
+    async fn delete_prefix(&self, prefix: &str) -> Result<(), AppError> {
+        let prefix = format!("{}/", prefix);
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let mut action = ListObjectsV2::new(&self.bucket, Some(&self.credentials));
+            action.with_prefix(prefix.as_str());
+            if let Some(token) = &continuation_token {
+                action.with_continuation_token(token.clone());
+            }
+            let url = action.sign(SIGNATURE_TTL);
+            let response = self.send(self.client.get(url)).await?;
+            if !response.status().is_success() {
+                return Err(Self::error(response).await);
+            }
+            let body = Self::body(response).await?;
+            let listing = ListObjectsV2::parse_response(&body).map_err(|e| {
+                AppError::Storage(format!("Failed to parse S3 object listing: {}", e))
+            })?;
+            for object in listing.contents {
+                self.delete(&object.key).await?;
+            }
+            continuation_token = listing.next_continuation_token;
+            if continuation_token.is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
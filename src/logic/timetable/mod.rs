@@ -0,0 +1,113 @@
+//! Составление недельного расписания.
+//!
+//! Модуль ничего не знает о базе данных: он получает уже подготовленную задачу, где время
+//! разбито на слоты (учебный день × номер урока), и возвращает размещение уроков по слотам
+//! и кабинетам. Подготовкой задачи и сохранением результата занимается `TimetableService`.
+
+use std::collections::HashSet;
+
+mod rng;
+mod solver;
+
+pub use solver::solve;
+
+pub struct TimetableProblem {
+    pub days: usize,
+    pub periods_per_day: usize,
+    /// ID кабинетов. Если список пуст, кабинеты урокам не назначаются
+    pub rooms: Vec<i32>,
+    /// Слоты, в которые кабинет уже занят существующими уроками: (слот, индекс кабинета)
+    pub busy_rooms: HashSet<(usize, usize)>,
+    pub max_lessons_per_day: Option<usize>,
+    pub lessons: Vec<LessonToPlace>,
+}
+
+impl TimetableProblem {
+    pub fn slot_count(&self) -> usize {
+        self.days * self.periods_per_day
+    }
+
+    pub fn day_of(&self, slot: usize) -> usize {
+        slot / self.periods_per_day
+    }
+
+    pub fn period_of(&self, slot: usize) -> usize {
+        slot % self.periods_per_day
+    }
+}
+
+#[derive(Clone)]
+pub struct LessonToPlace {
+    pub student_group_id: i32,
+    pub teacher_id: Option<i32>,
+    pub topic: String,
+    /// Слоты, в которые урок можно поставить: преподаватель доступен, а у группы и
+    /// преподавателя нет других уроков
+    pub allowed_slots: Vec<usize>,
+    /// Индексы кабинетов, в которых помещается группа
+    pub allowed_rooms: Vec<usize>,
+    /// Предпочтительные номера уроков дня. Пустой список означает отсутствие предпочтений
+    pub preferred_periods: Vec<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub slot: usize,
+    /// Индекс кабинета в `TimetableProblem::rooms`
+    pub room: Option<usize>,
+}
+
+pub enum Violation {
+    /// Урок не удалось поставить ни в один слот (жесткое ограничение)
+    Unplaced { lesson: usize },
+    /// Урок стоит не на предпочтительном номере урока
+    NotPreferredPeriod { lesson: usize },
+    /// У группы в этот день уже есть урок по этому предмету
+    RepeatedTopicInDay { lesson: usize },
+}
+
+impl Violation {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Violation::Unplaced { .. } => "unplaced",
+            Violation::NotPreferredPeriod { .. } => "preferred_period",
+            Violation::RepeatedTopicInDay { .. } => "repeated_topic_in_day",
+        }
+    }
+
+    pub fn is_hard(&self) -> bool {
+        matches!(self, Violation::Unplaced { .. })
+    }
+
+    pub fn lesson(&self) -> usize {
+        match self {
+            Violation::Unplaced { lesson }
+            | Violation::NotPreferredPeriod { lesson }
+            | Violation::RepeatedTopicInDay { lesson } => *lesson,
+        }
+    }
+}
+
+pub struct TimetableSolution {
+    /// Размещение каждого урока из `TimetableProblem::lessons`, `None` - урок не размещен
+    pub placements: Vec<Option<Placement>>,
+    pub violations: Vec<Violation>,
+}
+
+impl TimetableSolution {
+    pub fn hard_violations(&self) -> usize {
+        self.violations.iter().filter(|v| v.is_hard()).count()
+    }
+
+    pub fn soft_violations(&self) -> usize {
+        self.violations.len() - self.hard_violations()
+    }
+
+    /// Итоговая оценка: чем меньше, тем лучше. У каждого урока не больше двух мягких
+    /// нарушений, поэтому неразмещенный урок весит больше, чем все мягкие нарушения
+    /// расписания, и оценки сравниваются так же, как пары (жесткие, мягкие нарушения).
+    pub fn score(&self) -> usize {
+        let hard_violation_weight = 2 * self.placements.len() + 1;
+        self.hard_violations() * hard_violation_weight + self.soft_violations()
+    }
+}
@@ -0,0 +1,540 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Placement, TimetableProblem, TimetableSolution, Violation, rng::SplitMix64};
+
+const START_TEMPERATURE: f64 = 2.0;
+const END_TEMPERATURE: f64 = 0.01;
+
+/// Составляет расписание: сначала жадно расставляет уроки, начиная с самых ограниченных,
+/// затем улучшает результат имитацией отжига. Жесткие ограничения (пересечения групп,
+/// преподавателей и кабинетов, лимит уроков в день) размещенные уроки не нарушают никогда;
+/// урок, для которого не нашлось места, остается неразмещенным.
+///
+/// Результат зависит только от задачи и `seed`.
+pub fn solve(problem: &TimetableProblem, seed: u64, iterations: usize) -> TimetableSolution {
+    let mut rng = SplitMix64::new(seed);
+    let topics = intern_topics(problem);
+
+    let mut order: Vec<usize> = (0..problem.lessons.len()).collect();
+    rng.shuffle(&mut order);
+    order.sort_by_key(|&lesson| problem.lessons[lesson].allowed_slots.len());
+
+    let mut state = State::new(problem.lessons.len());
+    for lesson in order {
+        state.try_place(problem, &topics, lesson, &mut rng);
+    }
+
+    let mut cost = state.cost(problem);
+    let mut best = state.clone();
+    let mut best_cost = cost;
+
+    for step in 0..iterations {
+        if best_cost == Cost::default() || problem.lessons.is_empty() {
+            break;
+        }
+        let Some(next) = state.random_move(problem, &topics, &mut rng) else {
+            continue;
+        };
+        let next_cost = next.cost(problem);
+        let progress = step as f64 / iterations as f64;
+        let temperature = START_TEMPERATURE * (END_TEMPERATURE / START_TEMPERATURE).powf(progress);
+        // Худшее по мягким ограничениям решение иногда принимается, чтобы выбраться из
+        // локального минимума, а решение с большим числом неразмещенных уроков - никогда
+        let accept = next_cost <= cost
+            || next_cost.unplaced == cost.unplaced && {
+                let delta = next_cost.soft as f64 - cost.soft as f64;
+                rng.next_f64() < (-delta / temperature).exp()
+            };
+        if accept {
+            state = next;
+            cost = next_cost;
+            if cost < best_cost {
+                best = state.clone();
+                best_cost = cost;
+            }
+        }
+    }
+
+    for lesson in 0..problem.lessons.len() {
+        if best.placements[lesson].is_none() {
+            best.try_place(problem, &topics, lesson, &mut rng);
+        }
+    }
+
+    TimetableSolution {
+        violations: best.violations(problem, &topics),
+        placements: best.placements,
+    }
+}
+
+fn intern_topics(problem: &TimetableProblem) -> Vec<usize> {
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    problem
+        .lessons
+        .iter()
+        .map(|lesson| {
+            let next_id = ids.len();
+            *ids.entry(lesson.topic.as_str()).or_insert(next_id)
+        })
+        .collect()
+}
+
+/// Стоимость размещения. Стоимости сравниваются сначала по числу неразмещенных уроков,
+/// затем по числу мягких нарушений, поэтому решатель никогда не жертвует уроком ради
+/// предпочтений.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+struct Cost {
+    unplaced: usize,
+    soft: usize,
+}
+
+/// Текущее размещение и индексы занятости для быстрых проверок. Индексы используются
+/// только для поиска, но не для перебора, поэтому порядок обхода `HashMap` на результат
+/// не влияет.
+#[derive(Clone)]
+struct State {
+    placements: Vec<Option<Placement>>,
+    by_group: HashMap<(i32, usize), usize>,
+    by_teacher: HashMap<(i32, usize), usize>,
+    by_room: HashMap<(usize, usize), usize>,
+    per_day: HashMap<(i32, usize), usize>,
+    per_topic_day: HashMap<(i32, usize, usize), usize>,
+}
+
+impl State {
+    fn new(lessons: usize) -> Self {
+        Self {
+            placements: vec![None; lessons],
+            by_group: HashMap::new(),
+            by_teacher: HashMap::new(),
+            by_room: HashMap::new(),
+            per_day: HashMap::new(),
+            per_topic_day: HashMap::new(),
+        }
+    }
+
+    fn place(
+        &mut self,
+        problem: &TimetableProblem,
+        topics: &[usize],
+        lesson: usize,
+        placement: Placement,
+    ) {
+        let data = &problem.lessons[lesson];
+        let day = problem.day_of(placement.slot);
+        self.by_group
+            .insert((data.student_group_id, placement.slot), lesson);
+        if let Some(teacher_id) = data.teacher_id {
+            self.by_teacher.insert((teacher_id, placement.slot), lesson);
+        }
+        if let Some(room) = placement.room {
+            self.by_room.insert((room, placement.slot), lesson);
+        }
+        *self
+            .per_day
+            .entry((data.student_group_id, day))
+            .or_insert(0) += 1;
+        *self
+            .per_topic_day
+            .entry((data.student_group_id, topics[lesson], day))
+            .or_insert(0) += 1;
+        self.placements[lesson] = Some(placement);
+    }
+
+    fn unplace(&mut self, problem: &TimetableProblem, topics: &[usize], lesson: usize) {
+        let Some(placement) = self.placements[lesson].take() else {
+            return;
+        };
+        let data = &problem.lessons[lesson];
+        let day = problem.day_of(placement.slot);
+        self.by_group
+            .remove(&(data.student_group_id, placement.slot));
+        if let Some(teacher_id) = data.teacher_id {
+            self.by_teacher.remove(&(teacher_id, placement.slot));
+        }
+        if let Some(room) = placement.room {
+            self.by_room.remove(&(room, placement.slot));
+        }
+        if let Some(count) = self.per_day.get_mut(&(data.student_group_id, day)) {
+            *count -= 1;
+        }
+        if let Some(count) =
+            self.per_topic_day
+                .get_mut(&(data.student_group_id, topics[lesson], day))
+        {
+            *count -= 1;
+        }
+    }
+
+    /// Уроки, которые мешают поставить `lesson` на `placement`.
+    fn clashes(
+        &self,
+        problem: &TimetableProblem,
+        lesson: usize,
+        placement: Placement,
+    ) -> Vec<usize> {
+        let data = &problem.lessons[lesson];
+        let mut clashes = Vec::new();
+        if let Some(&other) = self.by_group.get(&(data.student_group_id, placement.slot)) {
+            clashes.push(other);
+        }
+        if let Some(teacher_id) = data.teacher_id
+            && let Some(&other) = self.by_teacher.get(&(teacher_id, placement.slot))
+        {
+            clashes.push(other);
+        }
+        if let Some(room) = placement.room
+            && let Some(&other) = self.by_room.get(&(room, placement.slot))
+        {
+            clashes.push(other);
+        }
+        clashes.retain(|&other| other != lesson);
+        clashes.sort_unstable();
+        clashes.dedup();
+        clashes
+    }
+
+    fn fits_day(&self, problem: &TimetableProblem, lesson: usize, day: usize) -> bool {
+        let Some(max) = problem.max_lessons_per_day else {
+            return true;
+        };
+        let group_id = problem.lessons[lesson].student_group_id;
+        self.per_day.get(&(group_id, day)).copied().unwrap_or(0) < max
+    }
+
+    /// Стоимость мягких ограничений, которую добавит урок на этом месте.
+    fn soft_cost_at(
+        &self,
+        problem: &TimetableProblem,
+        topics: &[usize],
+        lesson: usize,
+        placement: Placement,
+    ) -> usize {
+        let data = &problem.lessons[lesson];
+        let day = problem.day_of(placement.slot);
+        let mut cost = 0;
+        if !is_preferred(problem, lesson, placement) {
+            cost += 1;
+        }
+        if self
+            .per_topic_day
+            .get(&(data.student_group_id, topics[lesson], day))
+            .is_some_and(|&count| count > 0)
+        {
+            cost += 1;
+        }
+        cost
+    }
+
+    fn candidates(&self, problem: &TimetableProblem, lesson: usize) -> Vec<Placement> {
+        let data = &problem.lessons[lesson];
+        if problem.rooms.is_empty() {
+            return data
+                .allowed_slots
+                .iter()
+                .map(|&slot| Placement { slot, room: None })
+                .collect();
+        }
+        data.allowed_slots
+            .iter()
+            .flat_map(|&slot| {
+                data.allowed_rooms.iter().map(move |&room| Placement {
+                    slot,
+                    room: Some(room),
+                })
+            })
+            .filter(|placement| !is_room_busy(problem, *placement))
+            .collect()
+    }
+
+    /// Ставит урок на самое дешевое свободное место, не сдвигая другие уроки.
+    /// Среди равных по стоимости мест выбирается случайное.
+    fn try_place(
+        &mut self,
+        problem: &TimetableProblem,
+        topics: &[usize],
+        lesson: usize,
+        rng: &mut SplitMix64,
+    ) -> bool {
+        let mut best: Option<(usize, Placement)> = None;
+        let mut ties = 0;
+        for placement in self.candidates(problem, lesson) {
+            if !self.clashes(problem, lesson, placement).is_empty()
+                || !self.fits_day(problem, lesson, problem.day_of(placement.slot))
+            {
+                continue;
+            }
+            let cost = self.soft_cost_at(problem, topics, lesson, placement);
+            match best {
+                Some((best_cost, _)) if cost > best_cost => {}
+                Some((best_cost, _)) if cost == best_cost => {
+                    ties += 1;
+                    if rng.below(ties) == 0 {
+                        best = Some((cost, placement));
+                    }
+                }
+                _ => {
+                    ties = 1;
+                    best = Some((cost, placement));
+                }
+            }
+        }
+        match best {
+            Some((_, placement)) => {
+                self.place(problem, topics, lesson, placement);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Переносит случайный урок на случайное допустимое место. Мешающие уроки снимаются
+    /// и расставляются заново, если для них найдется место.
+    fn random_move(
+        &self,
+        problem: &TimetableProblem,
+        topics: &[usize],
+        rng: &mut SplitMix64,
+    ) -> Option<State> {
+        let lesson = rng.below(problem.lessons.len());
+        let data = &problem.lessons[lesson];
+        if data.allowed_slots.is_empty() {
+            return None;
+        }
+        let slot = data.allowed_slots[rng.below(data.allowed_slots.len())];
+        let room = if problem.rooms.is_empty() {
+            None
+        } else if data.allowed_rooms.is_empty() {
+            return None;
+        } else {
+            Some(data.allowed_rooms[rng.below(data.allowed_rooms.len())])
+        };
+        let placement = Placement { slot, room };
+        if self.placements[lesson] == Some(placement) || is_room_busy(problem, placement) {
+            return None;
+        }
+
+        let mut next = self.clone();
+        next.unplace(problem, topics, lesson);
+        let ejected = next.clashes(problem, lesson, placement);
+        for &other in &ejected {
+            next.unplace(problem, topics, other);
+        }
+        if !next.fits_day(problem, lesson, problem.day_of(slot)) {
+            return None;
+        }
+        next.place(problem, topics, lesson, placement);
+        for other in ejected {
+            next.try_place(problem, topics, other, rng);
+        }
+        Some(next)
+    }
+
+    fn cost(&self, problem: &TimetableProblem) -> Cost {
+        let unplaced = self.placements.iter().filter(|p| p.is_none()).count();
+        let not_preferred = self
+            .placements
+            .iter()
+            .enumerate()
+            .filter(|(lesson, placement)| {
+                placement.is_some_and(|p| !is_preferred(problem, *lesson, p))
+            })
+            .count();
+        let repeated: usize = self
+            .per_topic_day
+            .values()
+            .map(|&count| count.saturating_sub(1))
+            .sum();
+        Cost {
+            unplaced,
+            soft: not_preferred + repeated,
+        }
+    }
+
+    fn violations(&self, problem: &TimetableProblem, topics: &[usize]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut seen_topics: HashSet<(i32, usize, usize)> = HashSet::new();
+        for (lesson, placement) in self.placements.iter().enumerate() {
+            let Some(placement) = placement else {
+                violations.push(Violation::Unplaced { lesson });
+                continue;
+            };
+            if !is_preferred(problem, lesson, *placement) {
+                violations.push(Violation::NotPreferredPeriod { lesson });
+            }
+            let key = (
+                problem.lessons[lesson].student_group_id,
+                topics[lesson],
+                problem.day_of(placement.slot),
+            );
+            if !seen_topics.insert(key) {
+                violations.push(Violation::RepeatedTopicInDay { lesson });
+            }
+        }
+        violations
+    }
+}
+
+fn is_preferred(problem: &TimetableProblem, lesson: usize, placement: Placement) -> bool {
+    let preferred = &problem.lessons[lesson].preferred_periods;
+    preferred.is_empty() || preferred.contains(&problem.period_of(placement.slot))
+}
+
+fn is_room_busy(problem: &TimetableProblem, placement: Placement) -> bool {
+    placement
+        .room
+        .is_some_and(|room| problem.busy_rooms.contains(&(placement.slot, room)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{Cost, solve};
+    use crate::logic::timetable::{LessonToPlace, TimetableProblem, TimetableSolution};
+
+    fn lesson(group: i32, teacher: Option<i32>, topic: &str, slots: usize) -> LessonToPlace {
+        LessonToPlace {
+            student_group_id: group,
+            teacher_id: teacher,
+            topic: topic.to_string(),
+            allowed_slots: (0..slots).collect(),
+            allowed_rooms: vec![0, 1],
+            preferred_periods: Vec::new(),
+        }
+    }
+
+    fn problem(lessons: Vec<LessonToPlace>) -> TimetableProblem {
+        TimetableProblem {
+            days: 2,
+            periods_per_day: 3,
+            rooms: vec![10, 20],
+            busy_rooms: HashSet::new(),
+            max_lessons_per_day: None,
+            lessons,
+        }
+    }
+
+    fn placed_slots(solution: &TimetableSolution) -> Vec<(usize, usize, Option<usize>)> {
+        solution
+            .placements
+            .iter()
+            .enumerate()
+            .filter_map(|(lesson, p)| p.map(|p| (lesson, p.slot, p.room)))
+            .collect()
+    }
+
+    fn assert_no_clashes(problem: &TimetableProblem, solution: &TimetableSolution) {
+        let mut groups = HashSet::new();
+        let mut teachers = HashSet::new();
+        let mut rooms = HashSet::new();
+        for (lesson, slot, room) in placed_slots(solution) {
+            let l = &problem.lessons[lesson];
+            assert!(l.allowed_slots.contains(&slot));
+            assert!(groups.insert((l.student_group_id, slot)), "group clash");
+            if let Some(teacher) = l.teacher_id {
+                assert!(teachers.insert((teacher, slot)), "teacher clash");
+            }
+            if let Some(room) = room {
+                assert!(l.allowed_rooms.contains(&room));
+                assert!(rooms.insert((room, slot)), "room clash");
+                assert!(!problem.busy_rooms.contains(&(slot, room)), "busy room");
+            }
+        }
+    }
+
+    #[test]
+    fn cost_compares_unplaced_lessons_first() {
+        let one_unplaced = Cost {
+            unplaced: 1,
+            soft: 0,
+        };
+        let many_soft = Cost {
+            unplaced: 0,
+            soft: 100,
+        };
+        assert!(many_soft < one_unplaced);
+        assert!(Cost::default() < many_soft);
+    }
+
+    #[test]
+    fn same_seed_gives_same_timetable() {
+        let lessons = (0..8)
+            .map(|i| lesson(i % 3, Some(i % 2), &format!("topic {}", i % 4), 6))
+            .collect();
+        let problem = problem(lessons);
+
+        let first = solve(&problem, 42, 2000);
+        let second = solve(&problem, 42, 2000);
+
+        assert_eq!(placed_slots(&first), placed_slots(&second));
+        assert_eq!(first.score(), second.score());
+    }
+
+    #[test]
+    fn placements_respect_hard_constraints() {
+        let mut lessons: Vec<_> = (0..10)
+            .map(|i| lesson(i % 3, Some(i % 4), &format!("topic {}", i), 6))
+            .collect();
+        lessons[0].allowed_slots = vec![1, 4];
+        lessons[1].allowed_rooms = vec![1];
+        let mut problem = problem(lessons);
+        problem.busy_rooms.insert((0, 0));
+        problem.busy_rooms.insert((2, 1));
+
+        let solution = solve(&problem, 7, 5000);
+
+        assert_eq!(solution.hard_violations(), 0);
+        assert_no_clashes(&problem, &solution);
+    }
+
+    #[test]
+    fn respects_max_lessons_per_day() {
+        let lessons = (0..4)
+            .map(|i| lesson(1, None, &format!("topic {}", i), 6))
+            .collect();
+        let mut problem = problem(lessons);
+        problem.max_lessons_per_day = Some(2);
+
+        let solution = solve(&problem, 1, 2000);
+
+        assert_eq!(solution.hard_violations(), 0);
+        for day in 0..problem.days {
+            let per_day = placed_slots(&solution)
+                .iter()
+                .filter(|(_, slot, _)| problem.day_of(*slot) == day)
+                .count();
+            assert!(per_day <= 2);
+        }
+    }
+
+    #[test]
+    fn leaves_lessons_unplaced_when_slots_run_out() {
+        let lessons = (0..8)
+            .map(|i| lesson(1, None, &format!("topic {}", i), 6))
+            .collect();
+        let problem = problem(lessons);
+
+        let solution = solve(&problem, 3, 2000);
+
+        assert_eq!(solution.hard_violations(), 2);
+        assert_no_clashes(&problem, &solution);
+    }
+
+    #[test]
+    fn places_every_lesson_at_the_cost_of_preferences() {
+        let lessons = (0..6)
+            .map(|_| LessonToPlace {
+                preferred_periods: vec![0],
+                ..lesson(1, Some(1), "math", 6)
+            })
+            .collect();
+        let problem = problem(lessons);
+
+        let solution = solve(&problem, 5, 5000);
+
+        assert_eq!(solution.hard_violations(), 0);
+        assert!(solution.soft_violations() > 0);
+        assert_no_clashes(&problem, &solution);
+    }
+}
@@ -1,18 +1,23 @@
+use axum::middleware::from_fn;
 use dotenvy::dotenv;
 use school_schedule::{
     AppState,
-    auth::{self},
+    auth::{self, bearer::bearer_auth},
+    config::AppConfig,
     db, handlers,
+    handlers::utils::{
+        audit_actor::audit_actor, fallback::route_not_found, request_id::request_id,
+    },
     logic::services,
-    open_api::ApiDoc,
+    open_api::{ApiDoc, BearerSecurity, ProblemResponses},
 };
-use std::io::Error;
+use std::{io::Error, net::SocketAddr};
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
 use tower_sessions_redis_store::fred::prelude::ClientLike;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
-use utoipa::OpenApi;
+use utoipa::{Modify, OpenApi};
 use utoipa_axum::router::OpenApiRouter;
 use utoipa_swagger_ui::SwaggerUi;
 
@@ -20,6 +25,7 @@ use utoipa_swagger_ui::SwaggerUi;
 async fn main() -> Result<(), Error> {
     setup_tracing();
     dotenv().ok();
+    let config = AppConfig::from_env();
     let redis_pool = db::establish_redis_connection().await;
     redis_pool.connect_pool();
     redis_pool
@@ -29,35 +35,63 @@ async fn main() -> Result<(), Error> {
 
     let postgres_pool = db::establish_postgres_connection();
     db::run_db_migrations(&postgres_pool);
-    let auth_layer = auth::get_auth_layer(postgres_pool.clone(), redis_pool);
-    let services = services::init_app_services(postgres_pool);
+    let auth_layer = auth::get_auth_layer(postgres_pool.clone(), redis_pool.clone());
+    let services = services::init_app_services(postgres_pool, redis_pool, &config);
+    services
+        .timetable_service
+        .fail_interrupted()
+        .expect("Failed to mark interrupted timetable jobs as failed");
+    tokio::spawn(services.retention_service.clone().run_purge_job());
     let state = AppState { services };
 
-    let (router, open_api) = OpenApiRouter::with_openapi(ApiDoc::openapi())
+    let (router, mut open_api) = OpenApiRouter::with_openapi(ApiDoc::openapi())
         .nest("/api/v1/auth", handlers::auth_handler::router())
         .nest("/api/v1/users", handlers::user_handler::router())
+        .nest("/api/v1/api_tokens", handlers::api_token_handler::router())
         .nest("/api/v1/students", handlers::student_handler::router())
         .nest(
             "/api/v1/student_groups",
             handlers::student_group_handler::router(),
         )
         .nest("/api/v1/parents", handlers::parent_handler::router())
+        .nest(
+            "/api/v1/parent_portal",
+            handlers::parent_portal_handler::router(),
+        )
         .nest("/api/v1/teachers", handlers::teacher_handler::router())
+        .nest("/api/v1/documents", handlers::document_handler::router())
+        .nest("/api/v1/rooms", handlers::room_handler::router())
         .nest("/api/v1/lessons", handlers::lesson_handler::router())
+        .nest(
+            "/api/v1/lesson_series",
+            handlers::lesson_series_handler::router(),
+        )
         .nest(
             "/api/v1/attendances",
             handlers::attendances_handler::router(),
         )
+        .nest("/api/v1/timetables", handlers::timetable_handler::router())
+        .nest("/api/v1/audit", handlers::audit_handler::router())
+        .fallback(route_not_found)
+        .layer(from_fn(audit_actor))
+        .layer(from_fn(bearer_auth))
+        .layer(from_fn(request_id))
         .layer(TraceLayer::new_for_http())
         .layer(auth_layer)
         .with_state(state)
         .split_for_parts();
+    ProblemResponses.modify(&mut open_api);
+    BearerSecurity.modify(&mut open_api);
 
     let router = router.merge(SwaggerUi::new("/swagger").url("/apidoc/openapi.json", open_api));
 
     let listener = TcpListener::bind("0.0.0.0:1234").await?;
     info!("Server started on {}", listener.local_addr()?);
-    axum::serve(listener, router).await
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
 }
 
 fn setup_tracing() {
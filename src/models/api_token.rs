@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    models::{
+        user::PermissionRole,
+        validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    },
+    schema::api_tokens,
+};
+
+/// API токен для скриптов и других программных клиентов. Передается в заголовке
+/// `Authorization: Bearer <токен>` вместо сессионной cookie.
+#[derive(Debug, Serialize, Queryable, Selectable, Identifiable, ToSchema)]
+pub struct ApiToken {
+    pub id: i32,
+    /// ID пользователя, от имени которого действует токен
+    pub user_id: i32,
+    /// Название токена, например `kiosk` или `backup script`
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    /// Роль, с которой действует токен. Если роль пользователя ниже, действует она
+    pub role: PermissionRole,
+    pub created_at: DateTime<Utc>,
+    /// Время, после которого токен перестает действовать. Отсутствует у бессрочных токенов
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    /// Время отзыва токена. Отозванный токен не действует
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// Созданный токен. Значение токена показывается только один раз, в ответе на создание.
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApiToken {
+    #[serde(flatten)]
+    pub api_token: ApiToken,
+    pub token: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RawNewApiToken {
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    /// Роль токена. По умолчанию совпадает с ролью пользователя и не может быть выше нее
+    pub role: Option<PermissionRole>,
+    /// Время истечения токена. Если не указано, токен действует до отзыва
+    pub expires_at: Option<DateTime<Utc>>,
+    /// ID пользователя, для которого создается токен. Токены для других пользователей
+    /// может создавать только администратор
+    #[schema(minimum = 1)]
+    pub user_id: Option<i32>,
+}
+
+impl Validate for RawNewApiToken {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("name", &self.name, NAME_MAX_LENGTH);
+        errors.id("user_id", self.user_id);
+        if let Some(expires_at) = self.expires_at {
+            errors.check(
+                expires_at > Utc::now(),
+                "expires_at",
+                "in_past",
+                "Expiration time must be in the future",
+            );
+        }
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_tokens)]
+pub struct NewApiToken {
+    pub user_id: i32,
+    pub name: String,
+    pub token_hash: String,
+    pub role: PermissionRole,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ApiTokenFilter {
+    /// ID пользователя. Токены других пользователей может смотреть только администратор
+    pub user_id: Option<i32>,
+}
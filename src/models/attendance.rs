@@ -3,10 +3,14 @@ use diesel::{
     prelude::{AsChangeset, Associations, Identifiable, Insertable, Queryable},
 };
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    models::{lesson::Lesson, student::Student},
+    models::{
+        lesson::Lesson,
+        student::Student,
+        validation::{TEXT_MAX_LENGTH, Validate, ValidationErrors},
+    },
     schema::attendances,
 };
 
@@ -34,9 +38,13 @@ pub struct AttendanceWithRelations {
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = attendances)]
 pub struct NewAttendance {
+    #[schema(minimum = 1)]
     pub student_id: i32,
+    #[schema(minimum = 1)]
     pub lesson_id: i32,
     pub is_present: bool,
+    /// Причина пропуска, только для отсутствовавших учеников
+    #[schema(min_length = 1, max_length = 2000)]
     pub skip_reason: Option<String>,
 }
 
@@ -44,5 +52,69 @@ pub struct NewAttendance {
 #[diesel(table_name = attendances)]
 pub struct UpdateAttendance {
     pub is_present: Option<bool>,
+    /// Причина пропуска, только для отсутствовавших учеников
+    #[schema(min_length = 1, max_length = 2000)]
     pub skip_reason: Option<String>,
 }
+
+impl UpdateAttendance {
+    pub fn is_empty(&self) -> bool {
+        self.is_present.is_none() && self.skip_reason.is_none()
+    }
+}
+
+/// Причина, по которой ученик пропустит урок, сообщенная родителем заранее.
+#[derive(ToSchema, Deserialize)]
+pub struct NewAbsenceExcuse {
+    #[schema(minimum = 1)]
+    pub lesson_id: i32,
+    /// Причина пропуска
+    #[schema(min_length = 1, max_length = 2000)]
+    pub skip_reason: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AttendanceFilter {
+    /// ID ученика
+    pub student_id: Option<i32>,
+    /// ID урока
+    pub lesson_id: Option<i32>,
+    /// Отметка о присутствии
+    pub is_present: Option<bool>,
+}
+
+impl Validate for NewAttendance {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.id("student_id", Some(self.student_id));
+        errors.id("lesson_id", Some(self.lesson_id));
+        validate_skip_reason(errors, Some(self.is_present), self.skip_reason.as_deref());
+    }
+}
+
+impl Validate for NewAbsenceExcuse {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.id("lesson_id", Some(self.lesson_id));
+        errors.text("skip_reason", &self.skip_reason, TEXT_MAX_LENGTH);
+    }
+}
+
+impl Validate for UpdateAttendance {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        validate_skip_reason(errors, self.is_present, self.skip_reason.as_deref());
+    }
+}
+
+fn validate_skip_reason(
+    errors: &mut ValidationErrors,
+    is_present: Option<bool>,
+    skip_reason: Option<&str>,
+) {
+    errors.optional_text("skip_reason", skip_reason, TEXT_MAX_LENGTH);
+    errors.check(
+        !(is_present == Some(true) && skip_reason.is_some()),
+        "skip_reason",
+        "not_allowed",
+        "Skip reason can only be set for an absent student",
+    );
+}
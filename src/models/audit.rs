@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::schema::audit_log;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize, ToSchema)]
+#[db_enum(existing_type_path = "crate::schema::sql_types::AuditAction")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    /// Удаление, в том числе мягкое
+    Delete,
+    /// Восстановление удаленной записи
+    Restore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize, ToSchema)]
+#[db_enum(existing_type_path = "crate::schema::sql_types::AuditEntityType")]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEntityType {
+    Student,
+    Parent,
+    Teacher,
+    StudentGroup,
+    Lesson,
+    LessonSeries,
+    Attendance,
+    Document,
+    Room,
+    User,
+    WaitlistEntry,
+    /// Блокировка входа после неудачных попыток
+    LoginLockout,
+    ApiToken,
+}
+
+/// Запись журнала изменений.
+#[derive(Debug, Serialize, Queryable, Selectable, ToSchema)]
+#[diesel(table_name = audit_log)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: DateTime<Utc>,
+    /// ID пользователя, выполнившего действие. Отсутствует для действий, выполненных
+    /// системой
+    pub user_id: Option<i32>,
+    /// Логин пользователя на момент действия
+    pub username: Option<String>,
+    pub action: AuditAction,
+    pub entity_type: AuditEntityType,
+    /// ID измененной записи
+    pub entity_id: String,
+    /// Состояние записи до изменения. Отсутствует для созданных записей
+    #[schema(value_type = Option<Object>)]
+    pub before: Option<Value>,
+    /// Состояние записи после изменения. Отсутствует для удаленных записей
+    #[schema(value_type = Option<Object>)]
+    pub after: Option<Value>,
+    /// ID запроса, в котором было выполнено действие
+    pub request_id: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewAuditEntry {
+    pub user_id: Option<i32>,
+    pub username: Option<String>,
+    pub action: AuditAction,
+    pub entity_type: AuditEntityType,
+    pub entity_id: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditFilter {
+    /// Тип измененной записи
+    pub entity_type: Option<AuditEntityType>,
+    /// ID измененной записи
+    pub entity_id: Option<String>,
+    /// ID пользователя, выполнившего действие
+    pub user_id: Option<i32>,
+    /// Действия, выполненные не раньше указанного момента
+    pub from: Option<DateTime<Utc>>,
+    /// Действия, выполненные раньше указанного момента
+    pub to: Option<DateTime<Utc>>,
+}
@@ -1,44 +1,497 @@
-use chrono::NaiveDateTime;
+use std::fmt;
+
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::{AsChangeset, Insertable};
 use diesel::{Identifiable, Queryable, Selectable, prelude::Associations};
+use diesel_derive_enum::DbEnum;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::error::AppError;
+use crate::models::patch::Patch;
 use crate::models::teacher::Teacher;
+use crate::models::validation::{TEXT_MAX_LENGTH, Validate, ValidationErrors};
 use crate::schema::documents;
 
+/// Вид документа.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, DbEnum, Serialize, Deserialize, ToSchema)]
+#[db_enum(existing_type_path = "crate::schema::sql_types::DocumentCategory")]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentCategory {
+    /// Сертификаты, дипломы и удостоверения о квалификации
+    Certificate,
+    /// Медицинские осмотры и справки
+    MedicalCheck,
+    /// Трудовые договоры и дополнительные соглашения
+    Contract,
+    Other,
+}
+
+impl DocumentCategory {
+    pub const ALL: [Self; 4] = [
+        Self::Certificate,
+        Self::MedicalCheck,
+        Self::Contract,
+        Self::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Certificate => "certificate",
+            Self::MedicalCheck => "medical_check",
+            Self::Contract => "contract",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_str() == name)
+    }
+}
+
+/// Документ прикреплен ровно к одной записи: преподавателю, ученику, учебной группе или
+/// уроку. ID этой записи заполнен, остальные равны `null`.
 #[derive(Serialize, Deserialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
 #[diesel(belongs_to(Teacher))]
 pub struct Document {
     pub id: Uuid,
     pub name: String,
     pub uploaded_at: NaiveDateTime,
-    pub teacher_id: i32,
+    pub teacher_id: Option<i32>,
+    pub content_type: String,
+    /// Размер файла в байтах. Неизвестен для документов, загруженных до появления проверки
+    /// файлов
+    pub size: Option<i64>,
+    /// SHA-256 содержимого файла в виде hex-строки. Неизвестен для документов, загруженных
+    /// до появления проверки файлов
+    pub checksum: Option<String>,
+    #[serde(skip)]
+    pub storage_key: String,
+    pub category: DocumentCategory,
+    pub description: Option<String>,
+    /// Последний день действия документа. Отсутствует у бессрочных документов
+    pub valid_until: Option<NaiveDate>,
+    pub student_id: Option<i32>,
+    pub student_group_id: Option<i32>,
+    pub lesson_id: Option<i32>,
 }
 
 impl Document {
-    pub fn file_extension(&self) -> Result<&str, AppError> {
-        self.name
-            .split('.')
-            .next_back()
-            .ok_or(AppError::InternalServerError(
-                "Could not get document file extension".to_string(),
-            ))
+    /// Запись, к которой прикреплен документ.
+    pub fn owner(&self) -> Result<DocumentOwner, AppError> {
+        match (
+            self.teacher_id,
+            self.student_id,
+            self.student_group_id,
+            self.lesson_id,
+        ) {
+            (Some(id), _, _, _) => Ok(DocumentOwner::Teacher(id)),
+            (_, Some(id), _, _) => Ok(DocumentOwner::Student(id)),
+            (_, _, Some(id), _) => Ok(DocumentOwner::StudentGroup(id)),
+            (_, _, _, Some(id)) => Ok(DocumentOwner::Lesson(id)),
+            // Ограничение documents_single_owner не дает сохранить такой документ
+            (None, None, None, None) => Err(AppError::InternalServerError(format!(
+                "Document {} has no owner",
+                self.id
+            ))),
+        }
+    }
+}
+
+/// Запись, к которой прикреплен документ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentOwner {
+    Teacher(i32),
+    Student(i32),
+    StudentGroup(i32),
+    Lesson(i32),
+}
+
+impl DocumentOwner {
+    /// Общая часть ключей файлов документов записи.
+    pub fn storage_prefix(self) -> String {
+        match self {
+            Self::Teacher(id) => format!("teachers/{}", id),
+            Self::Student(id) => format!("students/{}", id),
+            Self::StudentGroup(id) => format!("student_groups/{}", id),
+            Self::Lesson(id) => format!("lessons/{}", id),
+        }
+    }
+}
+
+impl fmt::Display for DocumentOwner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Teacher(id) => write!(f, "teacher with ID {}", id),
+            Self::Student(id) => write!(f, "student with ID {}", id),
+            Self::StudentGroup(id) => write!(f, "student group with ID {}", id),
+            Self::Lesson(id) => write!(f, "lesson with ID {}", id),
+        }
+    }
+}
+
+/// Ссылка нового документа на запись, к которой он прикреплен.
+#[derive(Insertable, Default)]
+#[diesel(table_name = documents)]
+pub struct DocumentOwnerIds {
+    pub teacher_id: Option<i32>,
+    pub student_id: Option<i32>,
+    pub student_group_id: Option<i32>,
+    pub lesson_id: Option<i32>,
+}
+
+impl From<DocumentOwner> for DocumentOwnerIds {
+    fn from(owner: DocumentOwner) -> Self {
+        match owner {
+            DocumentOwner::Teacher(id) => Self {
+                teacher_id: Some(id),
+                ..Self::default()
+            },
+            DocumentOwner::Student(id) => Self {
+                student_id: Some(id),
+                ..Self::default()
+            },
+            DocumentOwner::StudentGroup(id) => Self {
+                student_group_id: Some(id),
+                ..Self::default()
+            },
+            DocumentOwner::Lesson(id) => Self {
+                lesson_id: Some(id),
+                ..Self::default()
+            },
+        }
     }
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+#[derive(Insertable)]
 #[diesel(table_name = documents)]
 pub struct NewDocument {
+    pub id: Uuid,
     pub name: String,
+    #[diesel(embed)]
+    pub owner: DocumentOwnerIds,
+    pub content_type: String,
+    pub size: i64,
+    pub checksum: String,
+    pub storage_key: String,
+    pub category: DocumentCategory,
+    pub description: Option<String>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+/// Сведения о документе, которые присылаются вместе с файлом.
+#[derive(Default)]
+pub struct DocumentDetails {
+    pub category: Option<DocumentCategory>,
+    pub description: Option<String>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+impl Validate for DocumentDetails {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("description", self.description.as_deref(), TEXT_MAX_LENGTH);
+    }
+}
+
+/// Изменения сведений о документе в том виде, в котором их присылает клиент. Файл
+/// документа после загрузки не меняется.
+///
+/// `null` в `description` и `valid_until` удаляет описание или делает документ бессрочным.
+#[derive(ToSchema, Deserialize)]
+pub struct RawUpdateDocument {
+    pub category: Option<DocumentCategory>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>, min_length = 1, max_length = 2000)]
+    pub description: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<NaiveDate>)]
+    pub valid_until: Patch<NaiveDate>,
+}
+
+impl Validate for RawUpdateDocument {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text(
+            "description",
+            self.description.value().map(String::as_str),
+            TEXT_MAX_LENGTH,
+        );
+    }
+}
+
+impl From<RawUpdateDocument> for UpdateDocument {
+    fn from(raw: RawUpdateDocument) -> Self {
+        Self {
+            category: raw.category,
+            description: raw.description.into_change(),
+            valid_until: raw.valid_until.into_change(),
+        }
+    }
+}
+
+/// Изменения документа: `None` оставляет поле без изменений, `Some(None)` сбрасывает его.
+#[derive(AsChangeset)]
+#[diesel(table_name = documents)]
+pub struct UpdateDocument {
+    pub category: Option<DocumentCategory>,
+    pub description: Option<Option<String>>,
+    pub valid_until: Option<Option<NaiveDate>>,
+}
+
+impl UpdateDocument {
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none() && self.description.is_none() && self.valid_until.is_none()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DocumentFilter {
+    /// Вид документа
+    pub category: Option<DocumentCategory>,
+    /// `true` — только документы с истекшим сроком действия, `false` — только
+    /// действующие и бессрочные
+    pub expired: Option<bool>,
+    /// Действующие документы, срок действия которых истекает в ближайшие дни
+    #[param(minimum = 0)]
+    pub expires_within_days: Option<i64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DocumentReportParams {
+    /// За сколько дней до окончания срока действия документ попадает в отчет (по
+    /// умолчанию `DOCUMENT_EXPIRY_WARNING_DAYS`)
+    #[param(minimum = 0, maximum = 366)]
+    pub days: Option<i64>,
+}
+
+/// Проблема с обязательным документом преподавателя.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentIssueKind {
+    /// Документа этого вида нет
+    Missing,
+    /// Срок действия всех документов этого вида истек
+    Expired,
+    /// Срок действия документа скоро истечет
+    Expiring,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DocumentIssue {
+    pub category: DocumentCategory,
+    pub kind: DocumentIssueKind,
+    /// Документ этого вида с самым поздним сроком действия. Отсутствует, если документа нет
+    pub document_id: Option<Uuid>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+/// Преподаватель, у которого не хватает обязательных документов или они скоро истекут.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TeacherDocumentReport {
     pub teacher_id: i32,
+    pub teacher_name: String,
+    pub issues: Vec<DocumentIssue>,
+}
+
+/// Допустимые типы файлов документов. Тип определяется по первым байтам файла, а не по
+/// заявленному клиентом типу или имени файла.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFileType {
+    Png,
+    Jpeg,
+    Webp,
+    Pdf,
+}
+
+impl DocumentFileType {
+    /// Сколько первых байт файла нужно, чтобы определить его тип.
+    pub const SIGNATURE_LENGTH: usize = 12;
+
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        if head.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if head.starts_with(b"\xff\xd8\xff") {
+            Some(Self::Jpeg)
+        } else if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+            Some(Self::Webp)
+        } else if head.starts_with(b"%PDF-") {
+            Some(Self::Pdf)
+        } else {
+            None
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+            Self::Pdf => "application/pdf",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+            Self::Pdf => "pdf",
+        }
+    }
+
+    fn matches_extension(self, extension: &str) -> bool {
+        let extension = extension.to_ascii_lowercase();
+        extension == self.extension() || self == Self::Jpeg && extension == "jpeg"
+    }
+
+    /// Безопасное имя файла из присланного клиентом: без пути, управляющих символов и
+    /// символов, запрещенных в именах файлов Windows, не длиннее 255 символов и с
+    /// расширением, соответствующим типу файла.
+    pub fn sanitize_file_name(self, raw_name: &str) -> String {
+        let base_name = raw_name.rsplit(['/', '\\']).next().unwrap_or_default();
+        let cleaned: String = base_name
+            .chars()
+            .map(|c| {
+                // Символы управления направлением текста позволяют замаскировать
+                // настоящее расширение файла
+                if c.is_control()
+                    || "<>:\"|?*".contains(c)
+                    || matches!(c, '\u{202a}'..='\u{202e}' | '\u{2066}'..='\u{2069}')
+                {
+                    '_'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let cleaned = cleaned.trim().trim_matches('.');
+
+        let (stem, extension) = match cleaned.rsplit_once('.') {
+            Some((stem, extension)) if self.matches_extension(extension) => (stem, extension),
+            Some((stem, extension)) if Self::is_known_extension(extension) => {
+                (stem, self.extension())
+            }
+            _ => (cleaned, self.extension()),
+        };
+        let stem = stem.trim();
+        let stem = if stem.is_empty() { "document" } else { stem };
+        let max_stem_length = MAX_FILE_NAME_LENGTH - extension.chars().count() - 1;
+        let stem: String = stem.chars().take(max_stem_length).collect();
+        format!("{}.{}", stem, extension)
+    }
+
+    fn is_known_extension(extension: &str) -> bool {
+        [Self::Png, Self::Jpeg, Self::Webp, Self::Pdf]
+            .into_iter()
+            .any(|file_type| file_type.matches_extension(extension))
+    }
 }
 
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
 // Структура для сваггера
 #[derive(Deserialize, ToSchema)]
 pub struct DocumentFileForm {
     #[schema(format = Binary, content_media_type = "application/octet-stream")]
     pub document: String,
+    /// По умолчанию `other`
+    pub category: Option<DocumentCategory>,
+    #[schema(min_length = 1, max_length = 2000)]
+    pub description: Option<String>,
+    pub valid_until: Option<NaiveDate>,
+}
+
+// Структура для сваггера
+#[derive(ToSchema)]
+#[schema(value_type = String, format = Binary)]
+pub struct DocumentContent(pub Vec<u8>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_recognizes_signatures() {
+        let cases: [(&[u8], Option<DocumentFileType>); 6] = [
+            (b"\x89PNG\r\n\x1a\n\0\0\0\x0d", Some(DocumentFileType::Png)),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", Some(DocumentFileType::Jpeg)),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", Some(DocumentFileType::Webp)),
+            (b"%PDF-1.7\n", Some(DocumentFileType::Pdf)),
+            (b"RIFF\x24\0\0\0WAVEfmt ", None),
+            (b"plain text file", None),
+        ];
+        for (head, expected) in cases {
+            assert_eq!(DocumentFileType::detect(head), expected, "{:?}", head);
+        }
+    }
+
+    #[test]
+    fn detect_rejects_truncated_headers() {
+        let cases: [&[u8]; 6] = [
+            b"",
+            b"\x89PNG\r\n",
+            b"\xff\xd8",
+            b"RIFF\x24\0\0\0WEB",
+            b"RIFF",
+            b"%PDF",
+        ];
+        for head in cases {
+            assert_eq!(DocumentFileType::detect(head), None, "{:?}", head);
+        }
+    }
+
+    #[test]
+    fn sanitize_file_name_strips_paths() {
+        let cases = [
+            ("../../etc/passwd", "passwd.pdf"),
+            ("..\\..\\windows\\secret.pdf", "secret.pdf"),
+            ("/tmp/../", "document.pdf"),
+            ("..", "document.pdf"),
+            ("  report.pdf  ", "report.pdf"),
+        ];
+        for (raw_name, expected) in cases {
+            assert_eq!(
+                DocumentFileType::Pdf.sanitize_file_name(raw_name),
+                expected,
+                "{}",
+                raw_name
+            );
+        }
+    }
+
+    #[test]
+    fn sanitize_file_name_replaces_forbidden_characters() {
+        let cases = [
+            ("a<b>:c.pdf", "a_b__c.pdf"),
+            ("line\nbreak.pdf", "line_break.pdf"),
+            ("inv\u{202e}fdp.exe", "inv_fdp.exe.pdf"),
+        ];
+        for (raw_name, expected) in cases {
+            assert_eq!(DocumentFileType::Pdf.sanitize_file_name(raw_name), expected);
+        }
+    }
+
+    #[test]
+    fn sanitize_file_name_fixes_extension() {
+        let cases = [
+            (DocumentFileType::Jpeg, "photo.JPEG", "photo.JPEG"),
+            (DocumentFileType::Png, "photo.pdf", "photo.png"),
+            (DocumentFileType::Png, "photo.exe", "photo.exe.png"),
+            (DocumentFileType::Webp, "photo", "photo.webp"),
+        ];
+        for (file_type, raw_name, expected) in cases {
+            assert_eq!(file_type.sanitize_file_name(raw_name), expected);
+        }
+    }
+
+    #[test]
+    fn sanitize_file_name_limits_length() {
+        let name = DocumentFileType::Pdf.sanitize_file_name(&format!("{}.pdf", "я".repeat(300)));
+        assert_eq!(name.chars().count(), MAX_FILE_NAME_LENGTH);
+        assert!(name.ends_with(".pdf"));
+    }
 }
@@ -0,0 +1,44 @@
+use chrono::NaiveDate;
+use diesel::{
+    Selectable,
+    prelude::{Associations, Identifiable, Insertable, Queryable},
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::{
+    models::{student::Student, student_group::StudentGroup},
+    schema::enrollments,
+};
+
+/// Период, в течение которого ученик состоял в группе.
+#[derive(Serialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[diesel(belongs_to(Student))]
+#[diesel(belongs_to(StudentGroup))]
+#[diesel(table_name = enrollments)]
+pub struct Enrollment {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub student_id: i32,
+    #[serde(skip_serializing)]
+    pub student_group_id: i32,
+    /// Первый день в группе
+    pub start_date: NaiveDate,
+    /// Первый день, когда ученик уже не в группе. Отсутствует у текущей группы
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct EnrollmentWithGroup {
+    #[serde(flatten)]
+    pub enrollment: Enrollment,
+    pub student_group: StudentGroup,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = enrollments)]
+pub struct NewEnrollment {
+    pub student_id: i32,
+    pub student_group_id: i32,
+    pub start_date: NaiveDate,
+}
@@ -1,42 +1,244 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{
-    AsChangeset, Identifiable, Queryable, Selectable,
+    AsChangeset, Identifiable, Queryable, Selectable, define_sql_function,
     prelude::{Associations, Insertable},
+    sql_types::Timestamptz,
 };
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
-use crate::{models::student_group::StudentGroup, schema::lessons};
+use crate::{
+    config::ScheduleConfig,
+    error::AppError,
+    models::{
+        patch::Patch,
+        room::Room,
+        student_group::StudentGroup,
+        validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    },
+    schema::lessons,
+};
+
+define_sql_function! {
+    /// Дата по местному времени школы
+    fn school_date(moment: Timestamptz) -> Date;
+}
 
-#[derive(Serialize, Deserialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[derive(
+    Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable, Associations, ToSchema,
+)]
 #[diesel(belongs_to(StudentGroup))]
 pub struct Lesson {
     pub id: i32,
     pub topic: String,
+    /// Дата урока по местному времени школы. Устарело, используйте `starts_at`
+    #[schema(deprecated)]
+    #[diesel(select_expression = school_date(lessons::starts_at))]
+    #[diesel(select_expression_type = school_date<lessons::starts_at>)]
     pub scheduled_at: NaiveDate,
     #[serde(skip_serializing)]
     pub student_group_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub lesson_series_id: Option<i32>,
+    /// Дата занятия по правилу серии (не меняется при переносе урока)
+    pub series_date: Option<NaiveDate>,
+    #[serde(skip_serializing)]
+    pub room_id: Option<i32>,
+    /// Время удаления. Возвращается только для удаленных записей
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct LessonWithRelations {
     #[serde(flatten)]
     pub lesson: Lesson,
     pub student_group: Option<StudentGroup>,
+    pub room: Option<Room>,
+}
+
+/// Данные для создания урока в том виде, в котором их присылает клиент.
+///
+/// Старые клиенты присылают только `scheduled_at` с датой урока: в этом случае урок
+/// начинается в стандартное время школы и длится стандартную продолжительность.
+#[derive(ToSchema, Deserialize)]
+pub struct RawNewLesson {
+    #[schema(min_length = 1, max_length = 255)]
+    pub topic: String,
+    /// Начало урока, обязательно без `scheduled_at`
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Конец урока, позже начала
+    pub ends_at: Option<DateTime<Utc>>,
+    #[schema(deprecated)]
+    pub scheduled_at: Option<NaiveDate>,
+    #[schema(minimum = 1)]
+    pub student_group_id: Option<i32>,
+    #[schema(minimum = 1)]
+    pub room_id: Option<i32>,
+}
+
+impl Validate for RawNewLesson {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("topic", &self.topic, NAME_MAX_LENGTH);
+        errors.check(
+            self.starts_at.is_some() || self.scheduled_at.is_some(),
+            "starts_at",
+            "required",
+            "Either starts_at or scheduled_at must be provided",
+        );
+        validate_lesson_bounds(errors, self.starts_at, self.ends_at);
+        errors.id("student_group_id", self.student_group_id);
+        errors.id("room_id", self.room_id);
+    }
+}
+
+impl RawNewLesson {
+    pub fn into_new_lesson(self, schedule: &ScheduleConfig) -> Result<NewLesson, AppError> {
+        let starts_at = match (self.starts_at, self.scheduled_at) {
+            (Some(starts_at), _) => starts_at,
+            (None, Some(date)) => schedule.local_to_utc(date, schedule.default_lesson_start)?,
+            (None, None) => {
+                return Err(AppError::BadRequest(
+                    "Either starts_at or scheduled_at must be provided".to_string(),
+                ));
+            }
+        };
+        let ends_at = self
+            .ends_at
+            .unwrap_or(starts_at + schedule.default_lesson_duration);
+
+        Ok(NewLesson {
+            topic: self.topic,
+            student_group_id: self.student_group_id,
+            starts_at,
+            ends_at,
+            lesson_series_id: None,
+            series_date: None,
+            room_id: self.room_id,
+        })
+    }
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+#[derive(Insertable)]
 #[diesel(table_name = lessons)]
 pub struct NewLesson {
     pub topic: String,
-    pub scheduled_at: NaiveDate,
     pub student_group_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub lesson_series_id: Option<i32>,
+    pub series_date: Option<NaiveDate>,
+    pub room_id: Option<i32>,
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+/// Изменения урока в том виде, в котором их присылает клиент.
+///
+/// `scheduled_at` переносит урок на другую дату с сохранением времени начала и
+/// продолжительности. Если передано только `starts_at`, продолжительность тоже сохраняется.
+/// `null` в `student_group_id` и `room_id` отвязывает урок от группы или кабинета.
+#[derive(ToSchema, Deserialize)]
+pub struct RawUpdateLesson {
+    #[schema(min_length = 1, max_length = 255)]
+    pub topic: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    /// Конец урока, позже начала
+    pub ends_at: Option<DateTime<Utc>>,
+    #[schema(deprecated)]
+    pub scheduled_at: Option<NaiveDate>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub student_group_id: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub room_id: Patch<i32>,
+}
+
+impl Validate for RawUpdateLesson {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("topic", self.topic.as_deref(), NAME_MAX_LENGTH);
+        validate_lesson_bounds(errors, self.starts_at, self.ends_at);
+        errors.id("student_group_id", self.student_group_id.value().copied());
+        errors.id("room_id", self.room_id.value().copied());
+    }
+}
+
+fn validate_lesson_bounds(
+    errors: &mut ValidationErrors,
+    starts_at: Option<DateTime<Utc>>,
+    ends_at: Option<DateTime<Utc>>,
+) {
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        errors.check(
+            ends_at > starts_at,
+            "ends_at",
+            "out_of_range",
+            "Lesson must end after it starts",
+        );
+    }
+}
+
+impl RawUpdateLesson {
+    pub fn into_update_lesson(
+        self,
+        lesson: &Lesson,
+        schedule: &ScheduleConfig,
+    ) -> Result<UpdateLesson, AppError> {
+        let starts_at = match (self.starts_at, self.scheduled_at) {
+            (Some(starts_at), _) => Some(starts_at),
+            (None, Some(date)) => {
+                Some(schedule.local_to_utc(date, schedule.local_time(lesson.starts_at))?)
+            }
+            (None, None) => None,
+        };
+        let ends_at = match (self.ends_at, starts_at) {
+            (Some(ends_at), _) => Some(ends_at),
+            (None, Some(starts_at)) => Some(starts_at + (lesson.ends_at - lesson.starts_at)),
+            (None, None) => None,
+        };
+
+        Ok(UpdateLesson {
+            topic: self.topic,
+            student_group_id: self.student_group_id.into_change(),
+            starts_at,
+            ends_at,
+            room_id: self.room_id.into_change(),
+        })
+    }
+}
+
+/// Изменения урока: `None` оставляет поле без изменений, `Some(None)` сбрасывает его.
+#[derive(AsChangeset)]
 #[diesel(table_name = lessons)]
 pub struct UpdateLesson {
     pub topic: Option<String>,
-    pub scheduled_at: Option<NaiveDate>,
+    pub student_group_id: Option<Option<i32>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub room_id: Option<Option<i32>>,
+}
+
+impl UpdateLesson {
+    pub fn is_empty(&self) -> bool {
+        self.topic.is_none()
+            && self.student_group_id.is_none()
+            && self.starts_at.is_none()
+            && self.ends_at.is_none()
+            && self.room_id.is_none()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LessonFilter {
+    /// ID учебной группы
     pub student_group_id: Option<i32>,
+    /// ID серии уроков
+    pub lesson_series_id: Option<i32>,
+    /// ID кабинета
+    pub room_id: Option<i32>,
+    /// Уроки, заканчивающиеся позже указанного момента
+    pub from: Option<DateTime<Utc>>,
+    /// Уроки, начинающиеся раньше указанного момента
+    pub to: Option<DateTime<Utc>>,
 }
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::lesson::LessonWithRelations;
+
+/// Причина, по которой урок пересекается с уже существующим.
+#[derive(Debug, Serialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictReason {
+    /// У учебной группы в это время уже есть урок
+    StudentGroup,
+    /// У преподавателя группы в это время урок с другой группой
+    Teacher,
+    /// Кабинет в это время занят
+    Room,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LessonConflict {
+    pub reason: ConflictReason,
+    /// Существующий урок, с которым пересекается новое время
+    pub lesson: LessonWithRelations,
+}
+
+/// Урок, который требуется проверить на пересечения перед сохранением.
+#[derive(Deserialize, ToSchema)]
+pub struct LessonConflictCheck {
+    /// ID изменяемого урока, чтобы он не считался пересечением сам с собой
+    pub lesson_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub student_group_id: Option<i32>,
+    pub room_id: Option<i32>,
+}
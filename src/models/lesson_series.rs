@@ -0,0 +1,624 @@
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+use diesel::{Identifiable, Insertable, Queryable, Selectable, prelude::Associations};
+use serde::{Deserialize, Serialize, Serializer};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    error::AppError,
+    models::{
+        lesson::Lesson,
+        patch::Patch,
+        student_group::StudentGroup,
+        validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    },
+    schema::lesson_series,
+};
+
+/// Максимальное количество занятий, которое может породить одна серия.
+pub const MAX_SERIES_OCCURRENCES: usize = 366;
+
+#[derive(Serialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[diesel(belongs_to(StudentGroup))]
+#[diesel(table_name = lesson_series)]
+pub struct LessonSeries {
+    pub id: i32,
+    pub topic: String,
+    pub student_group_id: Option<i32>,
+    pub start_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub duration_minutes: i32,
+    /// Дни недели, в которые проходят занятия: 1 - понедельник, 7 - воскресенье
+    #[serde(serialize_with = "serialize_weekdays")]
+    #[schema(value_type = Vec<u32>)]
+    pub weekdays: i32,
+    pub interval_weeks: i32,
+    pub until_date: Option<NaiveDate>,
+    pub occurrence_count: Option<i32>,
+    pub room_id: Option<i32>,
+}
+
+impl LessonSeries {
+    /// Все даты занятий по правилу повторения, включая отмененные.
+    pub fn occurrence_dates(&self) -> Vec<NaiveDate> {
+        expand_occurrences(
+            self.start_date,
+            self.weekdays,
+            self.interval_weeks,
+            self.until_date,
+            self.occurrence_count,
+        )
+    }
+
+    /// Количество занятий серии до `date`. `date` должна быть датой одного из занятий.
+    pub fn passed_occurrences(&self, date: NaiveDate) -> Result<usize, AppError> {
+        let dates = self.occurrence_dates();
+        let passed_occurrences = dates.iter().take_while(|d| **d < date).count();
+        if dates.get(passed_occurrences) != Some(&date) {
+            return Err(AppError::BadRequest(format!(
+                "{} is not an occurrence of lesson series {}",
+                date, self.id
+            )));
+        }
+        Ok(passed_occurrences)
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LessonSeriesWithLessons {
+    #[serde(flatten)]
+    pub series: LessonSeries,
+    pub cancelled_dates: Vec<NaiveDate>,
+    pub lessons: Vec<Lesson>,
+}
+
+/// Данные для создания серии уроков в том виде, в котором их присылает клиент.
+#[derive(Deserialize, ToSchema)]
+pub struct RawNewLessonSeries {
+    #[schema(min_length = 1, max_length = 255)]
+    pub topic: String,
+    #[schema(minimum = 1)]
+    pub student_group_id: Option<i32>,
+    #[schema(minimum = 1)]
+    pub room_id: Option<i32>,
+    pub start_date: NaiveDate,
+    #[schema(value_type = String, example = "09:00:00")]
+    pub start_time: NaiveTime,
+    #[schema(minimum = 1)]
+    pub duration_minutes: i32,
+    /// Дни недели: 1 - понедельник, 7 - воскресенье
+    #[schema(min_items = 1)]
+    pub weekdays: Vec<u32>,
+    /// Повторять каждые N недель (по умолчанию 1)
+    #[schema(minimum = 1)]
+    pub interval_weeks: Option<i32>,
+    /// Последний день серии, обязателен без `occurrence_count`
+    pub until_date: Option<NaiveDate>,
+    /// Количество занятий, обязательно без `until_date`
+    #[schema(minimum = 1, maximum = 366)]
+    pub occurrence_count: Option<i32>,
+}
+
+impl Validate for RawNewLessonSeries {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("topic", &self.topic, NAME_MAX_LENGTH);
+        errors.id("student_group_id", self.student_group_id);
+        errors.id("room_id", self.room_id);
+        errors.min("duration_minutes", self.duration_minutes, 1);
+        validate_weekdays(errors, &self.weekdays);
+        if let Some(interval_weeks) = self.interval_weeks {
+            errors.min("interval_weeks", interval_weeks, 1);
+        }
+        if let Some(occurrence_count) = self.occurrence_count {
+            validate_occurrence_count(errors, occurrence_count);
+        }
+        if let Some(until_date) = self.until_date {
+            errors.check(
+                until_date >= self.start_date,
+                "until_date",
+                "out_of_range",
+                "until_date can not be earlier than start_date",
+            );
+        }
+        errors.check(
+            self.until_date.is_some() || self.occurrence_count.is_some(),
+            "until_date",
+            "required",
+            "Either until_date or occurrence_count must be provided",
+        );
+    }
+}
+
+impl TryFrom<RawNewLessonSeries> for NewLessonSeries {
+    type Error = AppError;
+
+    fn try_from(raw: RawNewLessonSeries) -> Result<Self, Self::Error> {
+        let new_series = Self {
+            topic: raw.topic,
+            student_group_id: raw.student_group_id,
+            room_id: raw.room_id,
+            start_date: raw.start_date,
+            start_time: raw.start_time,
+            duration_minutes: raw.duration_minutes,
+            weekdays: weekdays_to_mask(&raw.weekdays)?,
+            interval_weeks: raw.interval_weeks.unwrap_or(1),
+            until_date: raw.until_date,
+            occurrence_count: raw.occurrence_count,
+        };
+        new_series.validate()?;
+        Ok(new_series)
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = lesson_series)]
+pub struct NewLessonSeries {
+    pub topic: String,
+    pub student_group_id: Option<i32>,
+    pub room_id: Option<i32>,
+    pub start_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub duration_minutes: i32,
+    pub weekdays: i32,
+    pub interval_weeks: i32,
+    pub until_date: Option<NaiveDate>,
+    pub occurrence_count: Option<i32>,
+}
+
+impl NewLessonSeries {
+    pub fn occurrence_dates(&self) -> Vec<NaiveDate> {
+        expand_occurrences(
+            self.start_date,
+            self.weekdays,
+            self.interval_weeks,
+            self.until_date,
+            self.occurrence_count,
+        )
+    }
+
+    /// Даты занятий, для которых нужно создать уроки: все даты по правилу повторения,
+    /// кроме отмененных.
+    pub fn planned_dates(&self, cancelled_dates: &[NaiveDate]) -> Vec<NaiveDate> {
+        self.occurrence_dates()
+            .into_iter()
+            .filter(|date| !cancelled_dates.contains(date))
+            .collect()
+    }
+
+    fn validate(&self) -> Result<(), AppError> {
+        if self.interval_weeks < 1 {
+            return Err(AppError::BadRequest(
+                "interval_weeks must be at least 1".to_string(),
+            ));
+        }
+        if self.duration_minutes < 1 {
+            return Err(AppError::BadRequest(
+                "duration_minutes must be positive".to_string(),
+            ));
+        }
+        match (self.until_date, self.occurrence_count) {
+            (None, None) => {
+                return Err(AppError::BadRequest(
+                    "Either until_date or occurrence_count must be provided".to_string(),
+                ));
+            }
+            (Some(until_date), _) if until_date < self.start_date => {
+                return Err(AppError::BadRequest(
+                    "until_date can not be earlier than start_date".to_string(),
+                ));
+            }
+            (_, Some(count)) if count < 1 || count as usize > MAX_SERIES_OCCURRENCES => {
+                return Err(AppError::BadRequest(format!(
+                    "occurrence_count must be between 1 and {}",
+                    MAX_SERIES_OCCURRENCES
+                )));
+            }
+            _ => {}
+        }
+
+        let occurrences = expand_occurrences_limited(
+            self.start_date,
+            self.weekdays,
+            self.interval_weeks,
+            self.until_date,
+            self.occurrence_count,
+            MAX_SERIES_OCCURRENCES + 1,
+        );
+        if occurrences.is_empty() {
+            return Err(AppError::BadRequest(
+                "Lesson series does not produce any lessons".to_string(),
+            ));
+        }
+        if occurrences.len() > MAX_SERIES_OCCURRENCES {
+            return Err(AppError::BadRequest(format!(
+                "Lesson series can not produce more than {} lessons",
+                MAX_SERIES_OCCURRENCES
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Изменения серии, применяемые к выбранному занятию и всем следующим за ним.
+///
+/// Не переданные поля берутся из исходной серии, `null` в `student_group_id` и `room_id`
+/// отвязывает серию от группы или кабинета. Если у исходной серии было ограничение по
+/// количеству занятий, новая серия получает оставшееся количество.
+#[derive(Deserialize, ToSchema)]
+pub struct RawUpdateLessonSeries {
+    #[schema(min_length = 1, max_length = 255)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub student_group_id: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub room_id: Patch<i32>,
+    #[schema(value_type = Option<String>, example = "09:00:00")]
+    pub start_time: Option<NaiveTime>,
+    #[schema(minimum = 1)]
+    pub duration_minutes: Option<i32>,
+    /// Дни недели: 1 - понедельник, 7 - воскресенье
+    #[schema(min_items = 1)]
+    pub weekdays: Option<Vec<u32>>,
+    #[schema(minimum = 1)]
+    pub interval_weeks: Option<i32>,
+    pub until_date: Option<NaiveDate>,
+    #[schema(minimum = 1, maximum = 366)]
+    pub occurrence_count: Option<i32>,
+}
+
+impl Validate for RawUpdateLessonSeries {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("topic", self.topic.as_deref(), NAME_MAX_LENGTH);
+        errors.id("student_group_id", self.student_group_id.value().copied());
+        errors.id("room_id", self.room_id.value().copied());
+        if let Some(duration_minutes) = self.duration_minutes {
+            errors.min("duration_minutes", duration_minutes, 1);
+        }
+        if let Some(weekdays) = &self.weekdays {
+            validate_weekdays(errors, weekdays);
+        }
+        if let Some(interval_weeks) = self.interval_weeks {
+            errors.min("interval_weeks", interval_weeks, 1);
+        }
+        if let Some(occurrence_count) = self.occurrence_count {
+            validate_occurrence_count(errors, occurrence_count);
+        }
+    }
+}
+
+impl RawUpdateLessonSeries {
+    /// Строит новую серию, начинающуюся с `split_date`. `passed_occurrences` - количество
+    /// занятий исходной серии до этой даты.
+    pub fn into_new_lesson_series(
+        self,
+        series: &LessonSeries,
+        split_date: NaiveDate,
+        passed_occurrences: usize,
+    ) -> Result<NewLessonSeries, AppError> {
+        let weekdays = match self.weekdays {
+            Some(weekdays) => weekdays_to_mask(&weekdays)?,
+            None => series.weekdays,
+        };
+        let (until_date, occurrence_count) = match (self.until_date, self.occurrence_count) {
+            (None, None) => (
+                series.until_date,
+                series
+                    .occurrence_count
+                    .map(|count| count - passed_occurrences as i32),
+            ),
+            (until_date, occurrence_count) => (until_date, occurrence_count),
+        };
+
+        let new_series = NewLessonSeries {
+            topic: self.topic.unwrap_or_else(|| series.topic.clone()),
+            student_group_id: self.student_group_id.apply(series.student_group_id),
+            room_id: self.room_id.apply(series.room_id),
+            start_date: split_date,
+            start_time: self.start_time.unwrap_or(series.start_time),
+            duration_minutes: self.duration_minutes.unwrap_or(series.duration_minutes),
+            weekdays,
+            interval_weeks: self.interval_weeks.unwrap_or(series.interval_weeks),
+            until_date,
+            occurrence_count,
+        };
+        new_series.validate()?;
+        Ok(new_series)
+    }
+}
+
+#[derive(Deserialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OccurrenceScope {
+    /// Только выбранное занятие
+    This,
+    /// Выбранное занятие и все следующие
+    Following,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OccurrenceScopeParams {
+    /// Какие занятия затрагивает операция (по умолчанию только выбранное)
+    #[serde(default = "default_scope")]
+    #[param(inline)]
+    pub scope: OccurrenceScope,
+}
+
+fn default_scope() -> OccurrenceScope {
+    OccurrenceScope::This
+}
+
+fn weekdays_to_mask(weekdays: &[u32]) -> Result<i32, AppError> {
+    if weekdays.is_empty() {
+        return Err(AppError::BadRequest(
+            "At least one weekday must be provided".to_string(),
+        ));
+    }
+    weekdays.iter().try_fold(0, |mask, day| match day {
+        1..=7 => Ok(mask | (1 << (day - 1))),
+        _ => Err(AppError::BadRequest(format!(
+            "Weekday must be between 1 and 7, got {}",
+            day
+        ))),
+    })
+}
+
+fn validate_weekdays(errors: &mut ValidationErrors, weekdays: &[u32]) {
+    errors.check(
+        !weekdays.is_empty(),
+        "weekdays",
+        "required",
+        "At least one weekday must be provided",
+    );
+    for (index, weekday) in weekdays.iter().enumerate() {
+        errors.range(&format!("weekdays[{}]", index), *weekday, 1, 7);
+    }
+}
+
+fn validate_occurrence_count(errors: &mut ValidationErrors, occurrence_count: i32) {
+    errors.range(
+        "occurrence_count",
+        occurrence_count,
+        1,
+        MAX_SERIES_OCCURRENCES as i32,
+    );
+}
+
+fn serialize_weekdays<S: Serializer>(mask: &i32, serializer: S) -> Result<S::Ok, S::Error> {
+    let weekdays: Vec<u32> = (1..=7).filter(|day| mask & (1 << (day - 1)) != 0).collect();
+    weekdays.serialize(serializer)
+}
+
+fn expand_occurrences(
+    start_date: NaiveDate,
+    weekdays: i32,
+    interval_weeks: i32,
+    until_date: Option<NaiveDate>,
+    occurrence_count: Option<i32>,
+) -> Vec<NaiveDate> {
+    expand_occurrences_limited(
+        start_date,
+        weekdays,
+        interval_weeks,
+        until_date,
+        occurrence_count,
+        MAX_SERIES_OCCURRENCES,
+    )
+}
+
+fn expand_occurrences_limited(
+    start_date: NaiveDate,
+    weekdays: i32,
+    interval_weeks: i32,
+    until_date: Option<NaiveDate>,
+    occurrence_count: Option<i32>,
+    limit: usize,
+) -> Vec<NaiveDate> {
+    let limit = occurrence_count.map_or(limit, |count| limit.min(count.max(0) as usize));
+    let mut dates = Vec::new();
+    if weekdays & 0x7f == 0 || interval_weeks < 1 {
+        return dates;
+    }
+
+    let mut week_start = start_date - Days::new(start_date.weekday().num_days_from_monday() as u64);
+    loop {
+        for day in 0..7 {
+            if weekdays & (1 << day) == 0 {
+                continue;
+            }
+            let date = week_start + Days::new(day);
+            if date < start_date {
+                continue;
+            }
+            if until_date.is_some_and(|until_date| date > until_date) || dates.len() >= limit {
+                return dates;
+            }
+            dates.push(date);
+        }
+        week_start = week_start + Days::new(7 * interval_weeks as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc};
+    use chrono_tz::Europe::Berlin;
+
+    use super::{LessonSeries, MAX_SERIES_OCCURRENCES, RawUpdateLessonSeries};
+    use crate::{config::ScheduleConfig, models::patch::Patch};
+
+    const MONDAY: i32 = 1;
+    const TUESDAY: i32 = 1 << 1;
+    const WEDNESDAY: i32 = 1 << 2;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn dates(list: &[&str]) -> Vec<NaiveDate> {
+        list.iter().map(|s| date(s)).collect()
+    }
+
+    fn series(
+        start_date: &str,
+        weekdays: i32,
+        interval_weeks: i32,
+        until_date: Option<&str>,
+        occurrence_count: Option<i32>,
+    ) -> LessonSeries {
+        LessonSeries {
+            id: 1,
+            topic: "Math".to_string(),
+            student_group_id: None,
+            start_date: date(start_date),
+            start_time: NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+            duration_minutes: 45,
+            weekdays,
+            interval_weeks,
+            until_date: until_date.map(date),
+            occurrence_count,
+            room_id: None,
+        }
+    }
+
+    fn empty_update() -> RawUpdateLessonSeries {
+        RawUpdateLessonSeries {
+            topic: None,
+            student_group_id: Patch::Absent,
+            room_id: Patch::Absent,
+            start_time: None,
+            duration_minutes: None,
+            weekdays: None,
+            interval_weeks: None,
+            until_date: None,
+            occurrence_count: None,
+        }
+    }
+
+    #[test]
+    fn expands_selected_weekdays_up_to_count() {
+        let series = series("2025-09-01", MONDAY | WEDNESDAY, 1, None, Some(4));
+        assert_eq!(
+            series.occurrence_dates(),
+            dates(&["2025-09-01", "2025-09-03", "2025-09-08", "2025-09-10"])
+        );
+    }
+
+    #[test]
+    fn skips_weekdays_before_start_date() {
+        let series = series("2025-09-03", MONDAY | WEDNESDAY, 1, None, Some(3));
+        assert_eq!(
+            series.occurrence_dates(),
+            dates(&["2025-09-03", "2025-09-08", "2025-09-10"])
+        );
+    }
+
+    #[test]
+    fn crosses_month_and_year_end_and_includes_until_date() {
+        let series = series(
+            "2025-12-29",
+            MONDAY | WEDNESDAY,
+            1,
+            Some("2026-01-07"),
+            None,
+        );
+        assert_eq!(
+            series.occurrence_dates(),
+            dates(&["2025-12-29", "2025-12-31", "2026-01-05", "2026-01-07"])
+        );
+    }
+
+    #[test]
+    fn includes_leap_day() {
+        let series = series("2028-02-28", TUESDAY, 1, None, Some(2));
+        assert_eq!(
+            series.occurrence_dates(),
+            dates(&["2028-02-29", "2028-03-07"])
+        );
+    }
+
+    #[test]
+    fn repeats_every_interval_weeks() {
+        let series = series("2025-09-01", MONDAY, 2, Some("2025-10-01"), None);
+        assert_eq!(
+            series.occurrence_dates(),
+            dates(&["2025-09-01", "2025-09-15", "2025-09-29"])
+        );
+    }
+
+    #[test]
+    fn limits_open_ended_expansion() {
+        let series = series("2025-09-01", 0x7f, 1, Some("2035-01-01"), None);
+        assert_eq!(series.occurrence_dates().len(), MAX_SERIES_OCCURRENCES);
+    }
+
+    #[test]
+    fn keeps_local_start_time_across_dst_change() {
+        let schedule = ScheduleConfig {
+            timezone: Berlin,
+            default_lesson_start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            default_lesson_duration: TimeDelta::minutes(45),
+            max_lesson_duration: TimeDelta::minutes(240),
+        };
+        let series = series("2025-10-20", MONDAY, 1, None, Some(2));
+        let starts: Vec<_> = series
+            .occurrence_dates()
+            .into_iter()
+            .map(|date| schedule.local_to_utc(date, series.start_time).unwrap())
+            .collect();
+
+        assert_eq!(
+            starts,
+            vec![
+                Utc.with_ymd_and_hms(2025, 10, 20, 8, 0, 0).unwrap(),
+                Utc.with_ymd_and_hms(2025, 10, 27, 9, 0, 0).unwrap(),
+            ]
+        );
+        // 2:30 в день перехода на летнее время не существует
+        let skipped_hour = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+        assert!(
+            schedule
+                .local_to_utc(date("2025-03-30"), skipped_hour)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn planned_dates_skip_cancelled_dates() {
+        let original = series("2025-09-01", MONDAY | WEDNESDAY, 1, None, Some(4));
+        let new_series = empty_update()
+            .into_new_lesson_series(&original, date("2025-09-01"), 0)
+            .unwrap();
+        assert_eq!(
+            new_series.planned_dates(&dates(&["2025-09-03", "2025-09-20"])),
+            dates(&["2025-09-01", "2025-09-08", "2025-09-10"])
+        );
+    }
+
+    #[test]
+    fn passed_occurrences_counts_earlier_dates() {
+        let series = series("2025-09-01", MONDAY | WEDNESDAY, 1, None, Some(6));
+        assert_eq!(series.passed_occurrences(date("2025-09-01")).unwrap(), 0);
+        assert_eq!(series.passed_occurrences(date("2025-09-10")).unwrap(), 3);
+        assert!(series.passed_occurrences(date("2025-09-02")).is_err());
+        assert!(series.passed_occurrences(date("2025-09-22")).is_err());
+    }
+
+    #[test]
+    fn split_series_gets_remaining_occurrences() {
+        let original = series("2025-09-01", MONDAY | WEDNESDAY, 1, None, Some(6));
+        let split_date = date("2025-09-08");
+        let passed = original.passed_occurrences(split_date).unwrap();
+
+        let new_series = empty_update()
+            .into_new_lesson_series(&original, split_date, passed)
+            .unwrap();
+
+        assert_eq!(new_series.occurrence_count, Some(4));
+        assert_eq!(
+            new_series.occurrence_dates(),
+            original.occurrence_dates()[passed..].to_vec()
+        );
+    }
+}
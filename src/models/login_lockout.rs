@@ -0,0 +1,39 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// По какому признаку считаются неудачные попытки входа.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LockoutTarget {
+    Username,
+    Ip,
+}
+
+impl fmt::Display for LockoutTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockoutTarget::Username => write!(f, "username"),
+            LockoutTarget::Ip => write!(f, "ip"),
+        }
+    }
+}
+
+/// Временная блокировка входа по логину или IP-адресу. Записывается в журнал изменений.
+#[derive(Debug, Serialize)]
+pub struct LoginLockout {
+    pub target: LockoutTarget,
+    /// Логин или IP-адрес
+    pub subject: String,
+    /// Число неудачных попыток входа подряд
+    pub failures: i64,
+    pub locked_until: DateTime<Utc>,
+}
+
+impl LoginLockout {
+    /// ID блокировки в журнале изменений, например `username:admin` или `ip:10.0.0.1`.
+    pub fn audit_id(&self) -> String {
+        format!("{}:{}", self.target, self.subject)
+    }
+}
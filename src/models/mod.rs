@@ -1,9 +1,23 @@
+pub mod api_token;
 pub mod attendance;
+pub mod audit;
 pub mod document;
+pub mod enrollment;
 pub mod lesson;
+pub mod lesson_conflict;
+pub mod lesson_series;
+pub mod login_lockout;
+pub mod pagination;
 pub mod parent;
+pub mod password_reset;
+pub mod patch;
+pub mod problem_details;
+pub mod room;
 pub mod student;
 pub mod student_group;
 pub mod teacher;
+pub mod timetable;
 pub mod user;
+pub mod validation;
+pub mod waitlist;
 
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const MAX_PAGE_SIZE: i64 = 100;
+
+#[derive(Serialize, ToSchema)]
+pub struct PaginatedResponse<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
+}
+
+impl<T> PaginatedResponse<T> {
+    pub fn new(items: Vec<T>, total: i64, pagination: &PaginationParams) -> Self {
+        Self {
+            items,
+            total,
+            page: pagination.page(),
+            page_size: pagination.limit(),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationParams {
+    /// Номер страницы, начиная с 1
+    #[serde(default = "default_page")]
+    #[param(minimum = 1, default = 1)]
+    pub page: i64,
+    /// Количество записей на странице (не больше 100)
+    #[serde(default = "default_page_size")]
+    #[param(minimum = 1, maximum = 100, default = 25)]
+    pub page_size: i64,
+}
+
+impl PaginationParams {
+    pub fn page(&self) -> i64 {
+        self.page.max(1)
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.page_size.clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page() - 1) * self.limit()
+    }
+}
+
+fn default_page() -> i64 {
+    1
+}
+
+fn default_page_size() -> i64 {
+    25
+}
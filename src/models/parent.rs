@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use diesel::{
     Selectable,
     prelude::{AsChangeset, Identifiable, Insertable, Queryable},
@@ -5,25 +6,59 @@ use diesel::{
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::schema::parents;
+use crate::{
+    models::validation::{NAME_MAX_LENGTH, TEXT_MAX_LENGTH, Validate, ValidationErrors},
+    schema::parents,
+};
 
 #[derive(Serialize, Deserialize, Queryable, Selectable, Identifiable, ToSchema)]
 pub struct Parent {
     pub id: i32,
     pub name: String,
     pub additional_info: Option<String>,
+    /// Время удаления. Возвращается только для удаленных записей
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = parents)]
 pub struct NewParent {
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
+    #[schema(max_length = 2000)]
     pub additional_info: Option<String>,
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = parents)]
 pub struct UpdateParent {
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
+    #[schema(max_length = 2000)]
     pub additional_info: Option<String>,
 }
+
+impl UpdateParent {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.additional_info.is_none()
+    }
+}
+
+impl Validate for NewParent {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("name", &self.name, NAME_MAX_LENGTH);
+        if let Some(additional_info) = &self.additional_info {
+            errors.max_length("additional_info", additional_info, TEXT_MAX_LENGTH);
+        }
+    }
+}
+
+impl Validate for UpdateParent {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("name", self.name.as_deref(), NAME_MAX_LENGTH);
+        if let Some(additional_info) = &self.additional_info {
+            errors.max_length("additional_info", additional_info, TEXT_MAX_LENGTH);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use diesel::{Insertable, Queryable, Selectable};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    models::{
+        user::PASSWORD_MAX_LENGTH,
+        validation::{Validate, ValidationErrors},
+    },
+    schema::password_reset_tokens,
+};
+
+/// Одноразовый токен сброса пароля. Сам токен не хранится, только его хеш.
+#[derive(Debug, Queryable, Selectable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct PasswordResetToken {
+    pub id: i32,
+    pub user_id: i32,
+    pub token_hash: String,
+    pub created_by: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = password_reset_tokens)]
+pub struct NewPasswordResetToken {
+    pub user_id: i32,
+    pub token_hash: String,
+    pub created_by: Option<i32>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Выданный администратором токен сброса пароля. Токен показывается только один раз.
+#[derive(Serialize, ToSchema)]
+pub struct IssuedPasswordResetToken {
+    pub user_id: i32,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Установка нового пароля по токену сброса.
+#[derive(Deserialize, ToSchema)]
+pub struct PasswordReset {
+    pub token: String,
+    #[schema(min_length = 8, max_length = 128, format = Password)]
+    pub new_password: String,
+}
+
+impl Validate for PasswordReset {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("token", &self.token, PASSWORD_MAX_LENGTH);
+        errors.max_length("new_password", &self.new_password, PASSWORD_MAX_LENGTH);
+    }
+}
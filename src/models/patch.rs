@@ -0,0 +1,97 @@
+use serde::{Deserialize, Deserializer};
+
+/// Изменение необязательного поля в запросе на обновление.
+///
+/// Различает три случая: поле не передано (`Absent`, значение не меняется), передан
+/// `null` (`Null`, значение сбрасывается) и передано значение (`Value`). Поля этого типа
+/// нужно помечать `#[serde(default)]`, иначе отсутствующее поле будет ошибкой, и
+/// `#[schema(value_type = Option<T>)]` для документации.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Patch::Absent)
+    }
+
+    /// Новое значение, если поле было передано в запросе.
+    pub fn into_change(self) -> Option<Option<T>> {
+        match self {
+            Patch::Absent => None,
+            Patch::Null => Some(None),
+            Patch::Value(value) => Some(Some(value)),
+        }
+    }
+
+    /// Значение поля после применения изменения к текущему значению `current`.
+    pub fn apply(self, current: Option<T>) -> Option<T> {
+        self.into_change().unwrap_or(current)
+    }
+
+    /// Переданное значение, если это не `null`.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Patch::Value(value) => Some(value),
+            Patch::Absent | Patch::Null => None,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::Patch;
+
+    #[derive(Deserialize)]
+    struct Update {
+        #[serde(default)]
+        room_id: Patch<i32>,
+    }
+
+    fn parse(json: &str) -> Patch<i32> {
+        serde_json::from_str::<Update>(json).unwrap().room_id
+    }
+
+    #[test]
+    fn missing_field_is_absent() {
+        assert_eq!(parse("{}"), Patch::Absent);
+    }
+
+    #[test]
+    fn null_field_is_null() {
+        assert_eq!(parse(r#"{"room_id": null}"#), Patch::Null);
+    }
+
+    #[test]
+    fn present_field_is_value() {
+        assert_eq!(parse(r#"{"room_id": 5}"#), Patch::Value(5));
+    }
+
+    #[test]
+    fn invalid_value_is_an_error() {
+        assert!(serde_json::from_str::<Update>(r#"{"room_id": "five"}"#).is_err());
+    }
+
+    #[test]
+    fn apply_keeps_or_replaces_current_value() {
+        assert_eq!(Patch::Absent.apply(Some(1)), Some(1));
+        assert_eq!(Patch::Null.apply(Some(1)), None);
+        assert_eq!(Patch::Value(2).apply(Some(1)), Some(2));
+        assert_eq!(Patch::Value(2).apply(None), Some(2));
+    }
+}
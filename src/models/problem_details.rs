@@ -0,0 +1,54 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::models::lesson_conflict::LessonConflict;
+
+/// Тело ответа с ошибкой в формате RFC 7807 (`application/problem+json`).
+#[derive(Serialize, ToSchema)]
+pub struct ProblemDetails {
+    /// Тип ошибки. Всегда `about:blank`, конкретная ошибка определяется полем `code`
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Текстовое описание HTTP статуса
+    pub title: String,
+    /// HTTP статус ответа
+    pub status: u16,
+    /// Описание ошибки для человека
+    pub detail: String,
+    /// Стабильный машиночитаемый код ошибки, например `not_found` или `group_full`
+    pub code: String,
+    /// ID запроса, по которому ошибку можно найти в логах сервера
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Ошибки в отдельных полях входных данных
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    /// Уроки, с которыми пересекается сохраняемый урок
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<Vec<LessonConflict>>,
+}
+
+/// Ошибка в конкретном поле входных данных.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Имя поля, например `birth_date`
+    pub field: String,
+    /// Машиночитаемый код ошибки, например `required` или `too_short`
+    pub code: String,
+    /// Описание ошибки для человека
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    Identifiable, Queryable, Selectable,
+    prelude::{AsChangeset, Insertable},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    models::validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    schema::rooms,
+};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Selectable, Identifiable, ToSchema)]
+pub struct Room {
+    pub id: i32,
+    pub name: String,
+    /// Количество мест в кабинете
+    pub capacity: i32,
+    pub building: Option<String>,
+    /// Оборудование кабинета, например `projector` или `piano`
+    pub equipment: Vec<String>,
+}
+
+#[derive(Insertable, ToSchema, Deserialize)]
+#[diesel(table_name = rooms)]
+pub struct NewRoom {
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: String,
+    #[schema(minimum = 1)]
+    pub capacity: i32,
+    #[schema(min_length = 1, max_length = 255)]
+    pub building: Option<String>,
+    #[serde(default)]
+    pub equipment: Vec<String>,
+}
+
+#[derive(AsChangeset, ToSchema, Deserialize)]
+#[diesel(table_name = rooms)]
+pub struct UpdateRoom {
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    #[schema(minimum = 1)]
+    pub capacity: Option<i32>,
+    #[schema(min_length = 1, max_length = 255)]
+    pub building: Option<String>,
+    pub equipment: Option<Vec<String>>,
+}
+
+impl UpdateRoom {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.capacity.is_none()
+            && self.building.is_none()
+            && self.equipment.is_none()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RoomFilter {
+    /// Подстрока названия кабинета (без учета регистра)
+    pub name: Option<String>,
+    /// Здание
+    pub building: Option<String>,
+    /// Кабинеты не меньше чем на указанное количество мест
+    pub min_capacity: Option<i32>,
+    /// Необходимое оборудование через запятую, например `projector,whiteboard`
+    pub equipment: Option<String>,
+}
+
+impl RoomFilter {
+    pub fn equipment_tags(&self) -> Vec<String> {
+        self.equipment
+            .iter()
+            .flat_map(|equipment| equipment.split(','))
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Промежуток времени, в который кабинет должен быть свободен.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RoomAvailabilityParams {
+    /// Начало промежутка в формате RFC 3339
+    pub from: DateTime<Utc>,
+    /// Конец промежутка в формате RFC 3339
+    pub to: DateTime<Utc>,
+}
+
+impl Validate for NewRoom {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("name", &self.name, NAME_MAX_LENGTH);
+        errors.min("capacity", self.capacity, 1);
+        errors.optional_text("building", self.building.as_deref(), NAME_MAX_LENGTH);
+        validate_equipment(errors, &self.equipment);
+    }
+}
+
+impl Validate for UpdateRoom {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("name", self.name.as_deref(), NAME_MAX_LENGTH);
+        if let Some(capacity) = self.capacity {
+            errors.min("capacity", capacity, 1);
+        }
+        errors.optional_text("building", self.building.as_deref(), NAME_MAX_LENGTH);
+        if let Some(equipment) = &self.equipment {
+            validate_equipment(errors, equipment);
+        }
+    }
+}
+
+fn validate_equipment(errors: &mut ValidationErrors, equipment: &[String]) {
+    for (index, tag) in equipment.iter().enumerate() {
+        errors.text(&format!("equipment[{}]", index), tag, NAME_MAX_LENGTH);
+    }
+}
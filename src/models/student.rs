@@ -1,13 +1,18 @@
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::{
     Selectable,
     prelude::{AsChangeset, Associations, Identifiable, Insertable, Queryable},
 };
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    models::{parent::Parent, student_group::StudentGroup},
+    models::{
+        parent::Parent,
+        patch::Patch,
+        student_group::StudentGroup,
+        validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    },
     schema::students,
 };
 
@@ -22,6 +27,9 @@ pub struct Student {
     pub parent_id: Option<i32>,
     #[serde(skip_serializing)]
     pub student_group_id: Option<i32>,
+    /// Время удаления. Возвращается только для удаленных записей
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -35,17 +43,104 @@ pub struct StudentWithRelations {
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = students)]
 pub struct NewStudent {
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
+    /// Дата рождения, не позже текущей даты
     pub birth_date: NaiveDate,
+    #[schema(minimum = 1)]
     pub parent_id: Option<i32>,
+    #[schema(minimum = 1)]
     pub student_group_id: Option<i32>,
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+/// Изменения ученика в том виде, в котором их присылает клиент.
+///
+/// Не переданные поля не меняются, `null` в `parent_id` и `student_group_id` отвязывает
+/// ученика от родителя или группы.
+#[derive(ToSchema, Deserialize)]
+pub struct RawUpdateStudent {
+    #[schema(min_length = 1, max_length = 255)]
+    pub name: Option<String>,
+    /// Дата рождения, не позже текущей даты
+    pub birth_date: Option<NaiveDate>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub parent_id: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub student_group_id: Patch<i32>,
+}
+
+impl From<RawUpdateStudent> for UpdateStudent {
+    fn from(raw: RawUpdateStudent) -> Self {
+        Self {
+            name: raw.name,
+            birth_date: raw.birth_date,
+            parent_id: raw.parent_id.into_change(),
+            student_group_id: raw.student_group_id.into_change(),
+        }
+    }
+}
+
+/// Изменения ученика: `None` оставляет поле без изменений, `Some(None)` сбрасывает его.
+#[derive(AsChangeset)]
 #[diesel(table_name = students)]
 pub struct UpdateStudent {
     pub name: Option<String>,
     pub birth_date: Option<NaiveDate>,
-    pub parent_id: Option<i32>,
+    pub parent_id: Option<Option<i32>>,
+    pub student_group_id: Option<Option<i32>>,
+}
+
+impl UpdateStudent {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.birth_date.is_none()
+            && self.parent_id.is_none()
+            && self.student_group_id.is_none()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct StudentFilter {
+    /// ID учебной группы
     pub student_group_id: Option<i32>,
+    /// ID родителя
+    pub parent_id: Option<i32>,
+    /// Подстрока имени ученика (без учета регистра)
+    pub name: Option<String>,
+    /// Дата рождения не раньше указанной
+    pub birth_date_from: Option<NaiveDate>,
+    /// Дата рождения не позже указанной
+    pub birth_date_to: Option<NaiveDate>,
+}
+
+impl Validate for NewStudent {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("name", &self.name, NAME_MAX_LENGTH);
+        validate_birth_date(errors, self.birth_date);
+        errors.id("parent_id", self.parent_id);
+        errors.id("student_group_id", self.student_group_id);
+    }
+}
+
+impl Validate for RawUpdateStudent {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("name", self.name.as_deref(), NAME_MAX_LENGTH);
+        if let Some(birth_date) = self.birth_date {
+            validate_birth_date(errors, birth_date);
+        }
+        errors.id("parent_id", self.parent_id.value().copied());
+        errors.id("student_group_id", self.student_group_id.value().copied());
+    }
+}
+
+fn validate_birth_date(errors: &mut ValidationErrors, birth_date: NaiveDate) {
+    errors.check(
+        birth_date <= Utc::now().date_naive(),
+        "birth_date",
+        "in_future",
+        "Birth date can not be in the future",
+    );
 }
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use diesel::{
     Selectable,
     prelude::{AsChangeset, Associations, Identifiable, Insertable, Queryable},
@@ -5,10 +6,16 @@ use diesel::{
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-use crate::models::teacher::Teacher;
+use crate::models::{
+    patch::Patch,
+    teacher::Teacher,
+    validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+};
 use crate::schema::student_groups;
 
-#[derive(Serialize, Deserialize, Selectable, Identifiable, Associations, Queryable, ToSchema)]
+#[derive(
+    Debug, Serialize, Deserialize, Selectable, Identifiable, Associations, Queryable, ToSchema,
+)]
 #[diesel(belongs_to(Teacher))]
 pub struct StudentGroup {
     pub id: i32,
@@ -16,6 +23,9 @@ pub struct StudentGroup {
     pub free_spots: i32,
     #[serde(skip_serializing)]
     pub teacher_id: Option<i32>,
+    /// Время удаления. Возвращается только для удаленных записей
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -23,20 +33,76 @@ pub struct StudentGroupWithRelations {
     #[serde(flatten)]
     pub student_group: StudentGroup,
     pub teacher: Option<Teacher>,
+    /// Количество учеников в группе
+    pub enrolled: i64,
+    /// Всего мест в группе: занятые и свободные
+    pub capacity: i64,
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = student_groups)]
 pub struct NewStudentGroup {
+    #[schema(min_length = 1, max_length = 255)]
     pub direction: Option<String>,
+    #[schema(minimum = 0)]
     pub free_spots: i32,
+    #[schema(minimum = 1)]
     pub teacher_id: Option<i32>,
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+/// Изменения группы в том виде, в котором их присылает клиент.
+///
+/// Не переданные поля не меняются, `null` в `teacher_id` отвязывает преподавателя.
+#[derive(ToSchema, Deserialize)]
+pub struct RawUpdateStudentGroup {
+    #[schema(min_length = 1, max_length = 255)]
+    pub direction: Option<String>,
+    #[schema(minimum = 0)]
+    pub free_spots: Option<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub teacher_id: Patch<i32>,
+}
+
+impl From<RawUpdateStudentGroup> for UpdateStudentGroup {
+    fn from(raw: RawUpdateStudentGroup) -> Self {
+        Self {
+            direction: raw.direction,
+            free_spots: raw.free_spots,
+            teacher_id: raw.teacher_id.into_change(),
+        }
+    }
+}
+
+/// Изменения группы: `None` оставляет поле без изменений, `Some(None)` сбрасывает его.
+#[derive(AsChangeset)]
 #[diesel(table_name = student_groups)]
 pub struct UpdateStudentGroup {
     pub direction: Option<String>,
     pub free_spots: Option<i32>,
-    pub teacher_id: Option<i32>,
+    pub teacher_id: Option<Option<i32>>,
+}
+
+impl UpdateStudentGroup {
+    pub fn is_empty(&self) -> bool {
+        self.direction.is_none() && self.free_spots.is_none() && self.teacher_id.is_none()
+    }
+}
+
+impl Validate for NewStudentGroup {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("direction", self.direction.as_deref(), NAME_MAX_LENGTH);
+        errors.min("free_spots", self.free_spots, 0);
+        errors.id("teacher_id", self.teacher_id);
+    }
+}
+
+impl Validate for RawUpdateStudentGroup {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("direction", self.direction.as_deref(), NAME_MAX_LENGTH);
+        if let Some(free_spots) = self.free_spots {
+            errors.min("free_spots", free_spots, 0);
+        }
+        errors.id("teacher_id", self.teacher_id.value().copied());
+    }
 }
@@ -1,4 +1,9 @@
-use crate::schema::teachers;
+use chrono::{DateTime, Utc};
+
+use crate::{
+    models::validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    schema::teachers,
+};
 use diesel::{
     Identifiable, Queryable, Selectable,
     prelude::{AsChangeset, Insertable},
@@ -10,16 +15,39 @@ use utoipa::ToSchema;
 pub struct Teacher {
     pub id: i32,
     pub name: String,
+    /// Время удаления. Возвращается только для удаленных записей
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = teachers)]
 pub struct NewTeacher {
+    #[schema(min_length = 1, max_length = 255)]
     pub name: String,
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
 #[diesel(table_name = teachers)]
 pub struct UpdateTeacher {
+    #[schema(min_length = 1, max_length = 255)]
     pub name: Option<String>,
 }
+
+impl UpdateTeacher {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+    }
+}
+
+impl Validate for NewTeacher {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.text("name", &self.name, NAME_MAX_LENGTH);
+    }
+}
+
+impl Validate for UpdateTeacher {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.optional_text("name", self.name.as_deref(), NAME_MAX_LENGTH);
+    }
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use diesel::{Identifiable, Insertable, Queryable, Selectable, prelude::Associations};
+use diesel_derive_enum::DbEnum;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::schema::{timetable_jobs, timetable_proposed_lessons, timetable_violations};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DbEnum, Serialize, Deserialize, ToSchema)]
+#[db_enum(existing_type_path = "crate::schema::sql_types::TimetableJobStatus")]
+#[serde(rename_all = "snake_case")]
+pub enum TimetableJobStatus {
+    /// Задача ожидает запуска
+    Pending,
+    /// Расписание составляется
+    Running,
+    /// Расписание составлено и ожидает решения директора
+    Completed,
+    /// Во время составления произошла ошибка
+    Failed,
+    /// Директор принял расписание, уроки созданы
+    Accepted,
+}
+
+#[derive(Serialize, Queryable, Selectable, Identifiable, ToSchema)]
+pub struct TimetableJob {
+    pub id: i32,
+    pub status: TimetableJobStatus,
+    pub week_start: NaiveDate,
+    pub seed: i64,
+    /// Количество нарушенных жестких ограничений (уроков, которые не удалось поставить)
+    pub hard_violations: Option<i32>,
+    /// Количество нарушенных мягких ограничений
+    pub soft_violations: Option<i32>,
+    /// Итоговая оценка расписания: чем меньше, тем лучше
+    pub score: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = timetable_jobs)]
+pub struct NewTimetableJob {
+    pub week_start: NaiveDate,
+    pub seed: i64,
+}
+
+#[derive(Serialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[diesel(belongs_to(TimetableJob))]
+pub struct TimetableProposedLesson {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub timetable_job_id: i32,
+    pub topic: String,
+    pub student_group_id: i32,
+    pub room_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = timetable_proposed_lessons)]
+pub struct NewTimetableProposedLesson {
+    pub timetable_job_id: i32,
+    pub topic: String,
+    pub student_group_id: i32,
+    pub room_id: Option<i32>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[diesel(belongs_to(TimetableJob))]
+pub struct TimetableViolation {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub timetable_job_id: i32,
+    /// Вид ограничения, например `unplaced` или `preferred_period`
+    pub kind: String,
+    pub is_hard: bool,
+    pub description: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = timetable_violations)]
+pub struct NewTimetableViolation {
+    pub timetable_job_id: i32,
+    pub kind: String,
+    pub is_hard: bool,
+    pub description: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimetableJobWithProposal {
+    #[serde(flatten)]
+    pub job: TimetableJob,
+    pub lessons: Vec<TimetableProposedLesson>,
+    pub violations: Vec<TimetableViolation>,
+}
+
+/// Параметры составления расписания на неделю.
+#[derive(Deserialize, ToSchema)]
+pub struct TimetableRequest {
+    /// Понедельник недели, на которую составляется расписание
+    pub week_start: NaiveDate,
+    /// Зерно генератора случайных чисел. С одним и тем же зерном и теми же данными
+    /// получается одно и то же расписание
+    pub seed: Option<i64>,
+    /// Учебные дни: 1 - понедельник, 7 - воскресенье (по умолчанию с понедельника по пятницу)
+    pub weekdays: Option<Vec<u32>>,
+    /// Время уроков внутри дня, по порядку и без пересечений
+    pub periods: Vec<TimetablePeriod>,
+    /// Сколько уроков каждого предмета нужно каждой группе в неделю
+    pub requirements: Vec<LessonRequirement>,
+    /// Когда преподаватели могут вести уроки. Преподаватель без записей доступен всегда
+    #[serde(default)]
+    pub teacher_availability: Vec<TeacherAvailability>,
+    /// Кабинеты, которые можно использовать (по умолчанию все)
+    pub room_ids: Option<Vec<i32>>,
+    /// Максимальное количество уроков у группы в день
+    pub max_lessons_per_day: Option<u32>,
+    /// Количество итераций улучшения расписания
+    pub iterations: Option<u32>,
+}
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct TimetablePeriod {
+    /// Местное время начала урока
+    #[schema(value_type = String, example = "09:00:00")]
+    pub start_time: NaiveTime,
+    pub duration_minutes: i32,
+}
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct LessonRequirement {
+    pub student_group_id: i32,
+    pub topic: String,
+    pub lessons_per_week: u32,
+    /// Номера предпочтительных уроков дня, начиная с 0 (мягкое ограничение)
+    #[serde(default)]
+    pub preferred_periods: Vec<usize>,
+}
+
+#[derive(Deserialize, ToSchema, Clone)]
+pub struct TeacherAvailability {
+    pub teacher_id: i32,
+    /// День недели: 1 - понедельник, 7 - воскресенье
+    pub weekday: u32,
+    #[schema(value_type = String, example = "09:00:00")]
+    pub from: NaiveTime,
+    #[schema(value_type = String, example = "15:00:00")]
+    pub to: NaiveTime,
+}
@@ -3,7 +3,16 @@ use std::fmt::Debug;
 use diesel::prelude::*;
 use diesel_derive_enum::DbEnum;
 
-use crate::schema::users::{self};
+use crate::{
+    config::PasswordConfig,
+    models::{
+        parent::Parent,
+        patch::Patch,
+        teacher::Teacher,
+        validation::{NAME_MAX_LENGTH, Validate, ValidationErrors},
+    },
+    schema::users::{self},
+};
 use axum_login::AuthUser;
 use password_auth::{generate_hash, verify_password};
 use serde::{Deserialize, Serialize};
@@ -17,11 +26,28 @@ pub struct User {
     password: String,
     pub role: PermissionRole,
     pub full_name: Option<String>,
+    /// ID преподавателя, профилем которого является пользователь
+    pub teacher_id: Option<i32>,
+    /// ID родителя, профилем которого является пользователь
+    pub parent_id: Option<i32>,
+}
+
+/// Пользователь вместе со связанными профилями преподавателя и родителя.
+#[derive(Serialize, ToSchema)]
+pub struct UserWithProfile {
+    #[serde(flatten)]
+    pub user: User,
+    pub teacher: Option<Teacher>,
+    pub parent: Option<Parent>,
 }
 
 impl User {
     pub fn check_password(&self, creds: Credentials) -> bool {
-        verify_password(creds.password, &self.password).is_ok()
+        self.has_password(&creds.password)
+    }
+
+    pub fn has_password(&self, password: &str) -> bool {
+        verify_password(password, &self.password).is_ok()
     }
 }
 
@@ -42,6 +68,8 @@ impl AuthUser for User {
         self.id
     }
 
+    /// Хеш пароля сохраняется в сессии при входе. После смены пароля он перестает
+    /// совпадать, и все открытые ранее сессии пользователя становятся недействительными.
     fn session_auth_hash(&self) -> &[u8] {
         self.password.as_bytes()
     }
@@ -49,9 +77,35 @@ impl AuthUser for User {
 
 #[derive(Deserialize, ToSchema)]
 pub struct RawNewUser {
+    /// Латинские буквы, цифры и символы `_`, `.`, `-`
+    #[schema(min_length = 3, max_length = 50, pattern = "^[A-Za-z0-9_.-]+$")]
     username: String,
+    #[schema(min_length = 8, max_length = 128, format = Password)]
     password: String,
+    #[schema(min_length = 1, max_length = 255)]
     full_name: Option<String>,
+    /// ID преподавателя, с которым связывается пользователь
+    #[schema(minimum = 1)]
+    teacher_id: Option<i32>,
+    /// ID родителя, с которым связывается пользователь
+    #[schema(minimum = 1)]
+    parent_id: Option<i32>,
+}
+
+impl RawNewUser {
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+}
+
+impl Validate for RawNewUser {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        validate_username(errors, &self.username);
+        errors.max_length("password", &self.password, PASSWORD_MAX_LENGTH);
+        errors.optional_text("full_name", self.full_name.as_deref(), NAME_MAX_LENGTH);
+        errors.id("teacher_id", self.teacher_id);
+        errors.id("parent_id", self.parent_id);
+    }
 }
 
 #[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
@@ -60,6 +114,8 @@ pub struct NewUser {
     pub username: String,
     password: String,
     pub full_name: Option<String>,
+    pub teacher_id: Option<i32>,
+    pub parent_id: Option<i32>,
 }
 
 impl From<RawNewUser> for NewUser {
@@ -68,19 +124,151 @@ impl From<RawNewUser> for NewUser {
             username: raw.username,
             password: generate_hash(raw.password),
             full_name: raw.full_name,
+            teacher_id: raw.teacher_id,
+            parent_id: raw.parent_id,
         }
     }
 }
 
-#[derive(Insertable, AsChangeset, ToSchema, Deserialize)]
+/// Не переданные поля не меняются, `null` в `teacher_id` и `parent_id` отвязывает
+/// пользователя от профиля преподавателя или родителя.
+#[derive(ToSchema, Deserialize)]
+pub struct RawUpdateUser {
+    /// Латинские буквы, цифры и символы `_`, `.`, `-`
+    #[schema(min_length = 3, max_length = 50, pattern = "^[A-Za-z0-9_.-]+$")]
+    pub username: Option<String>,
+    pub role: Option<PermissionRole>,
+    #[schema(min_length = 1, max_length = 255)]
+    pub full_name: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub teacher_id: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>, minimum = 1)]
+    pub parent_id: Patch<i32>,
+}
+
+impl From<RawUpdateUser> for UpdateUser {
+    fn from(raw: RawUpdateUser) -> Self {
+        Self {
+            username: raw.username,
+            role: raw.role,
+            full_name: raw.full_name,
+            teacher_id: raw.teacher_id.into_change(),
+            parent_id: raw.parent_id.into_change(),
+        }
+    }
+}
+
+/// Изменения пользователя: `None` оставляет поле без изменений, `Some(None)` сбрасывает его.
+#[derive(AsChangeset)]
 #[diesel(table_name = users)]
 pub struct UpdateUser {
     pub username: Option<String>,
     pub role: Option<PermissionRole>,
     pub full_name: Option<String>,
+    pub teacher_id: Option<Option<i32>>,
+    pub parent_id: Option<Option<i32>>,
+}
+
+impl UpdateUser {
+    pub fn is_empty(&self) -> bool {
+        self.username.is_none()
+            && self.role.is_none()
+            && self.full_name.is_none()
+            && self.teacher_id.is_none()
+            && self.parent_id.is_none()
+    }
+}
+
+impl Validate for RawUpdateUser {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        if let Some(username) = &self.username {
+            validate_username(errors, username);
+        }
+        errors.optional_text("full_name", self.full_name.as_deref(), NAME_MAX_LENGTH);
+        errors.id("teacher_id", self.teacher_id.value().copied());
+        errors.id("parent_id", self.parent_id.value().copied());
+    }
+}
+
+/// Смена пароля пользователем, который знает текущий пароль.
+#[derive(Deserialize, ToSchema)]
+pub struct ChangePassword {
+    #[schema(format = Password)]
+    pub old_password: String,
+    #[schema(min_length = 8, max_length = 128, format = Password)]
+    pub new_password: String,
+}
+
+impl Validate for ChangePassword {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.max_length("new_password", &self.new_password, PASSWORD_MAX_LENGTH);
+        errors.check(
+            self.new_password != self.old_password,
+            "new_password",
+            "unchanged",
+            "New password must differ from the old one",
+        );
+    }
+}
+
+/// Проверяет пароль на соответствие требованиям из настроек. Общие ограничения длины
+/// проверяются при разборе запроса, здесь только настраиваемые.
+pub fn validate_password_policy(
+    errors: &mut ValidationErrors,
+    field: &str,
+    password: &str,
+    policy: &PasswordConfig,
+) {
+    errors.min_length(field, password, policy.min_length);
+    errors.check(
+        !policy.require_lowercase || password.chars().any(char::is_lowercase),
+        field,
+        "missing_lowercase",
+        "Password must contain a lowercase letter",
+    );
+    errors.check(
+        !policy.require_uppercase || password.chars().any(char::is_uppercase),
+        field,
+        "missing_uppercase",
+        "Password must contain an uppercase letter",
+    );
+    errors.check(
+        !policy.require_digit || password.chars().any(|c| c.is_ascii_digit()),
+        field,
+        "missing_digit",
+        "Password must contain a digit",
+    );
+    errors.check(
+        !policy.require_symbol || password.chars().any(|c| !c.is_alphanumeric()),
+        field,
+        "missing_symbol",
+        "Password must contain a character other than a letter or digit",
+    );
+}
+
+const USERNAME_MIN_LENGTH: usize = 3;
+const USERNAME_MAX_LENGTH: usize = 50;
+pub(crate) const PASSWORD_MAX_LENGTH: usize = 128;
+
+fn validate_username(errors: &mut ValidationErrors, username: &str) {
+    errors.min_length("username", username, USERNAME_MIN_LENGTH);
+    errors.max_length("username", username, USERNAME_MAX_LENGTH);
+    errors.check(
+        username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')),
+        "username",
+        "invalid_format",
+        "Username may contain only latin letters, digits, '_', '.' and '-'",
+    );
 }
 
-#[derive(Debug, Clone, DbEnum, Serialize, Deserialize, ToSchema)]
+/// Роли упорядочены по возрастанию прав: каждая следующая включает права предыдущих.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, DbEnum, Serialize, Deserialize, ToSchema,
+)]
 #[db_enum(existing_type_path = "crate::schema::sql_types::PermissionRole")]
 pub enum PermissionRole {
     User,
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+
+use crate::{error::AppError, models::problem_details::FieldError};
+
+/// Максимальная длина имен, названий и тем.
+pub const NAME_MAX_LENGTH: usize = 255;
+/// Максимальная длина произвольных текстовых полей.
+pub const TEXT_MAX_LENGTH: usize = 2000;
+
+/// Проверка входных данных, присланных клиентом.
+///
+/// Реализуется для каждой структуры, которую хендлеры принимают через
+/// [`ValidatedJson`](crate::handlers::utils::validated_json::ValidatedJson). Ограничения
+/// стоит дублировать в атрибутах `#[schema(...)]`, чтобы они попадали в документацию.
+pub trait Validate {
+    /// Добавляет в `errors` все найденные ошибки, не останавливаясь на первой.
+    fn validate(&self, errors: &mut ValidationErrors);
+
+    fn validated(&self) -> Result<(), AppError> {
+        let mut errors = ValidationErrors::default();
+        self.validate(&mut errors);
+        errors.into_result()
+    }
+}
+
+/// Накопитель ошибок валидации по полям.
+#[derive(Default)]
+pub struct ValidationErrors(Vec<FieldError>);
+
+impl ValidationErrors {
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, code, message));
+    }
+
+    /// Добавляет ошибку, если условие `valid` не выполнено.
+    pub fn check(&mut self, valid: bool, field: &str, code: &str, message: impl Into<String>) {
+        if !valid {
+            self.add(field, code, message);
+        }
+    }
+
+    /// Строка не пустая (без учета пробелов) и не длиннее `max_length` символов.
+    pub fn text(&mut self, field: &str, value: &str, max_length: usize) {
+        if value.trim().is_empty() {
+            self.add(field, "blank", "Value must not be blank");
+        } else {
+            self.max_length(field, value, max_length);
+        }
+    }
+
+    /// Необязательная строка: если передана, то проверяется как [`Self::text`].
+    pub fn optional_text(&mut self, field: &str, value: Option<&str>, max_length: usize) {
+        if let Some(value) = value {
+            self.text(field, value, max_length);
+        }
+    }
+
+    pub fn max_length(&mut self, field: &str, value: &str, max_length: usize) {
+        self.check(
+            value.chars().count() <= max_length,
+            field,
+            "too_long",
+            format!("Value must be at most {} characters long", max_length),
+        );
+    }
+
+    pub fn min_length(&mut self, field: &str, value: &str, min_length: usize) {
+        self.check(
+            value.chars().count() >= min_length,
+            field,
+            "too_short",
+            format!("Value must be at least {} characters long", min_length),
+        );
+    }
+
+    /// Число не меньше `min`.
+    pub fn min<T: PartialOrd + Display>(&mut self, field: &str, value: T, min: T) {
+        self.check(
+            value >= min,
+            field,
+            "out_of_range",
+            format!("Value must be at least {}", min),
+        );
+    }
+
+    /// Число в пределах от `min` до `max` включительно.
+    pub fn range<T: PartialOrd + Display>(&mut self, field: &str, value: T, min: T, max: T) {
+        self.check(
+            value >= min && value <= max,
+            field,
+            "out_of_range",
+            format!("Value must be between {} and {}", min, max),
+        );
+    }
+
+    /// ID связанной сущности: положительное число.
+    pub fn id(&mut self, field: &str, value: Option<i32>) {
+        if let Some(value) = value {
+            self.min(field, value, 1);
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.0))
+        }
+    }
+}
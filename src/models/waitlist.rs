@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use diesel::{
+    Selectable,
+    prelude::{Associations, Identifiable, Insertable, Queryable},
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    models::{
+        student::Student,
+        student_group::StudentGroup,
+        validation::{Validate, ValidationErrors},
+    },
+    schema::waitlist_entries,
+};
+
+/// Место ученика в очереди на зачисление в заполненную группу.
+#[derive(Serialize, Queryable, Selectable, Identifiable, Associations, ToSchema)]
+#[diesel(belongs_to(StudentGroup))]
+#[diesel(belongs_to(Student))]
+#[diesel(table_name = waitlist_entries)]
+pub struct WaitlistEntry {
+    pub id: i32,
+    #[serde(skip_serializing)]
+    pub student_group_id: i32,
+    #[serde(skip_serializing)]
+    pub student_id: i32,
+    /// Позиция в очереди, начиная с 1
+    pub position: i32,
+    /// Время постановки в очередь
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct WaitlistEntryWithStudent {
+    #[serde(flatten)]
+    pub entry: WaitlistEntry,
+    pub student: Student,
+}
+
+/// Данные для постановки ученика в очередь в том виде, в котором их присылает клиент.
+#[derive(Deserialize, ToSchema)]
+pub struct RawNewWaitlistEntry {
+    #[schema(minimum = 1)]
+    pub student_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = waitlist_entries)]
+pub struct NewWaitlistEntry {
+    pub student_group_id: i32,
+    pub student_id: i32,
+    pub position: i32,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateWaitlistEntry {
+    /// Новая позиция в очереди, начиная с 1. Позиция больше длины очереди ставит ученика
+    /// в конец
+    #[schema(minimum = 1)]
+    pub position: i32,
+}
+
+impl Validate for RawNewWaitlistEntry {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.id("student_id", Some(self.student_id));
+    }
+}
+
+impl Validate for UpdateWaitlistEntry {
+    fn validate(&self, errors: &mut ValidationErrors) {
+        errors.min("position", self.position, 1);
+    }
+}
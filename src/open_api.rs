@@ -1,5 +1,123 @@
-use utoipa::OpenApi;
+use std::collections::BTreeMap;
+
+use utoipa::{
+    Modify, OpenApi,
+    openapi::{
+        Content, PathItem, Ref, RefOr,
+        path::Operation,
+        security::{
+            ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+        },
+    },
+};
+
+use crate::models::problem_details::{FieldError, ProblemDetails};
+
+const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
 
 #[derive(OpenApi)]
-#[openapi()]
+#[openapi(
+    components(schemas(ProblemDetails, FieldError)),
+    modifiers(&SessionSecurity)
+)]
 pub struct ApiDoc;
+
+/// Схемы авторизации: по сессионной cookie и по API токену. В `security` каждого
+/// эндпоинта указывается минимальная роль пользователя, необходимая для доступа к нему.
+struct SessionSecurity;
+
+impl Modify for SessionSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "session",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("id"))),
+        );
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .description(Some(
+                        "API токен в заголовке `Authorization: Bearer <токен>`",
+                    ))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Проставляет всем ответам с ошибкой (4xx и 5xx) тело [`ProblemDetails`] с типом
+/// `application/problem+json`, чтобы не указывать его в каждом эндпоинте.
+///
+/// Применяется к документации после добавления всех роутеров, так как до этого в ней
+/// еще нет эндпоинтов.
+pub struct ProblemResponses;
+
+impl Modify for ProblemResponses {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for operation in operations(openapi) {
+            for (status, response) in operation.responses.responses.iter_mut() {
+                let RefOr::T(response) = response else {
+                    continue;
+                };
+                if !(status.starts_with('4') || status.starts_with('5')) {
+                    continue;
+                }
+                let content = response
+                    .content
+                    .shift_remove("application/json")
+                    .unwrap_or_else(|| Content::new(Some(Ref::from_schema_name("ProblemDetails"))));
+                response.content.clear();
+                response
+                    .content
+                    .insert(PROBLEM_CONTENT_TYPE.to_string(), content);
+            }
+        }
+    }
+}
+
+/// Добавляет API токен как равноправную альтернативу сессии во все эндпоинты, требующие
+/// авторизации, с той же минимальной ролью.
+///
+/// Применяется к документации после добавления всех роутеров, как и [`ProblemResponses`].
+pub struct BearerSecurity;
+
+impl Modify for BearerSecurity {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for operation in operations(openapi) {
+            let Some(security) = operation.security.as_mut() else {
+                continue;
+            };
+            let bearer_requirements = security
+                .iter()
+                .filter_map(|requirement| {
+                    // Поля требования закрыты, поэтому роли читаются через сериализацию
+                    let schemes: BTreeMap<String, Vec<String>> =
+                        serde_json::from_value(serde_json::to_value(requirement).ok()?).ok()?;
+                    let roles = schemes.get("session")?.clone();
+                    Some(SecurityRequirement::new("bearer", roles))
+                })
+                .collect::<Vec<_>>();
+            security.extend(bearer_requirements);
+        }
+    }
+}
+
+fn operations(openapi: &mut utoipa::openapi::OpenApi) -> impl Iterator<Item = &mut Operation> {
+    openapi
+        .paths
+        .paths
+        .values_mut()
+        .flat_map(|path_item: &mut PathItem| {
+            [
+                &mut path_item.get,
+                &mut path_item.put,
+                &mut path_item.post,
+                &mut path_item.delete,
+                &mut path_item.patch,
+            ]
+            .into_iter()
+            .flatten()
+        })
+}
@@ -1,9 +1,44 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "audit_action"))]
+    pub struct AuditAction;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "audit_entity_type"))]
+    pub struct AuditEntityType;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "document_category"))]
+    pub struct DocumentCategory;
+
     #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "permission_role"))]
     pub struct PermissionRole;
+
+    #[derive(diesel::query_builder::QueryId, Clone, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "timetable_job_status"))]
+    pub struct TimetableJobStatus;
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::PermissionRole;
+
+    api_tokens (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        role -> PermissionRole,
+        created_at -> Timestamptz,
+        expires_at -> Nullable<Timestamptz>,
+        last_used_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
 }
 
 diesel::table! {
@@ -17,11 +52,79 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::AuditAction;
+    use super::sql_types::AuditEntityType;
+
+    audit_log (id) {
+        id -> Int8,
+        created_at -> Timestamptz,
+        user_id -> Nullable<Int4>,
+        username -> Nullable<Varchar>,
+        action -> AuditAction,
+        entity_type -> AuditEntityType,
+        entity_id -> Varchar,
+        before -> Nullable<Jsonb>,
+        after -> Nullable<Jsonb>,
+        request_id -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DocumentCategory;
+
     documents (id) {
         id -> Uuid,
         name -> Varchar,
         uploaded_at -> Timestamp,
-        teacher_id -> Int4,
+        teacher_id -> Nullable<Int4>,
+        #[max_length = 255]
+        content_type -> Varchar,
+        size -> Nullable<Int8>,
+        #[max_length = 64]
+        checksum -> Nullable<Varchar>,
+        #[max_length = 512]
+        storage_key -> Varchar,
+        category -> DocumentCategory,
+        description -> Nullable<Text>,
+        valid_until -> Nullable<Date>,
+        student_id -> Nullable<Int4>,
+        student_group_id -> Nullable<Int4>,
+        lesson_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    enrollments (id) {
+        id -> Int4,
+        student_id -> Int4,
+        student_group_id -> Int4,
+        start_date -> Date,
+        end_date -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    lesson_series (id) {
+        id -> Int4,
+        topic -> Varchar,
+        student_group_id -> Nullable<Int4>,
+        start_date -> Date,
+        start_time -> Time,
+        duration_minutes -> Int4,
+        weekdays -> Int4,
+        interval_weeks -> Int4,
+        until_date -> Nullable<Date>,
+        occurrence_count -> Nullable<Int4>,
+        room_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    lesson_series_cancellations (lesson_series_id, occurrence_date) {
+        lesson_series_id -> Int4,
+        occurrence_date -> Date,
     }
 }
 
@@ -29,8 +132,13 @@ diesel::table! {
     lessons (id) {
         id -> Int4,
         topic -> Varchar,
-        scheduled_at -> Date,
         student_group_id -> Nullable<Int4>,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+        lesson_series_id -> Nullable<Int4>,
+        series_date -> Nullable<Date>,
+        room_id -> Nullable<Int4>,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -39,6 +147,30 @@ diesel::table! {
         id -> Int4,
         name -> Varchar,
         additional_info -> Nullable<Text>,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    password_reset_tokens (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        created_by -> Nullable<Int4>,
+        created_at -> Timestamptz,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    rooms (id) {
+        id -> Int4,
+        name -> Varchar,
+        capacity -> Int4,
+        building -> Nullable<Varchar>,
+        equipment -> Array<Text>,
     }
 }
 
@@ -48,6 +180,7 @@ diesel::table! {
         direction -> Nullable<Varchar>,
         free_spots -> Int4,
         teacher_id -> Nullable<Int4>,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -58,6 +191,7 @@ diesel::table! {
         birth_date -> Date,
         parent_id -> Nullable<Int4>,
         student_group_id -> Nullable<Int4>,
+        deleted_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -65,6 +199,47 @@ diesel::table! {
     teachers (id) {
         id -> Int4,
         name -> Varchar,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::TimetableJobStatus;
+
+    timetable_jobs (id) {
+        id -> Int4,
+        status -> TimetableJobStatus,
+        week_start -> Date,
+        seed -> Int8,
+        hard_violations -> Nullable<Int4>,
+        soft_violations -> Nullable<Int4>,
+        score -> Nullable<Int4>,
+        error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        finished_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    timetable_proposed_lessons (id) {
+        id -> Int4,
+        timetable_job_id -> Int4,
+        topic -> Varchar,
+        student_group_id -> Int4,
+        room_id -> Nullable<Int4>,
+        starts_at -> Timestamptz,
+        ends_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    timetable_violations (id) {
+        id -> Int4,
+        timetable_job_id -> Int4,
+        kind -> Varchar,
+        is_hard -> Bool,
+        description -> Text,
     }
 }
 
@@ -78,24 +253,66 @@ diesel::table! {
         password -> Text,
         role -> PermissionRole,
         full_name -> Nullable<Text>,
+        teacher_id -> Nullable<Int4>,
+        parent_id -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    waitlist_entries (id) {
+        id -> Int4,
+        student_group_id -> Int4,
+        student_id -> Int4,
+        position -> Int4,
+        created_at -> Timestamptz,
     }
 }
 
+diesel::joinable!(api_tokens -> users (user_id));
 diesel::joinable!(attendances -> lessons (lesson_id));
 diesel::joinable!(attendances -> students (student_id));
+diesel::joinable!(documents -> lessons (lesson_id));
+diesel::joinable!(documents -> student_groups (student_group_id));
+diesel::joinable!(documents -> students (student_id));
 diesel::joinable!(documents -> teachers (teacher_id));
+diesel::joinable!(enrollments -> student_groups (student_group_id));
+diesel::joinable!(enrollments -> students (student_id));
+diesel::joinable!(lesson_series -> rooms (room_id));
+diesel::joinable!(lesson_series -> student_groups (student_group_id));
+diesel::joinable!(lesson_series_cancellations -> lesson_series (lesson_series_id));
+diesel::joinable!(lessons -> lesson_series (lesson_series_id));
+diesel::joinable!(lessons -> rooms (room_id));
 diesel::joinable!(lessons -> student_groups (student_group_id));
 diesel::joinable!(student_groups -> teachers (teacher_id));
 diesel::joinable!(students -> parents (parent_id));
 diesel::joinable!(students -> student_groups (student_group_id));
+diesel::joinable!(timetable_proposed_lessons -> rooms (room_id));
+diesel::joinable!(timetable_proposed_lessons -> student_groups (student_group_id));
+diesel::joinable!(timetable_proposed_lessons -> timetable_jobs (timetable_job_id));
+diesel::joinable!(timetable_violations -> timetable_jobs (timetable_job_id));
+diesel::joinable!(users -> parents (parent_id));
+diesel::joinable!(users -> teachers (teacher_id));
+diesel::joinable!(waitlist_entries -> student_groups (student_group_id));
+diesel::joinable!(waitlist_entries -> students (student_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
     attendances,
+    audit_log,
     documents,
+    enrollments,
+    lesson_series,
+    lesson_series_cancellations,
     lessons,
     parents,
+    password_reset_tokens,
+    rooms,
     student_groups,
     students,
     teachers,
+    timetable_jobs,
+    timetable_proposed_lessons,
+    timetable_violations,
     users,
+    waitlist_entries,
 );